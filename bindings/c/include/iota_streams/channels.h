@@ -23,6 +23,7 @@ typedef struct PublicKey public_key_t;
 typedef struct PskId psk_id_t;
 typedef struct PskIds psk_ids_t;
 typedef struct KePks ke_pks_t;
+typedef struct ChannelMetadata channel_metadata_t;
 
 typedef struct NextMsgIds next_msg_ids_t;
 extern void drop_next_msg_ids(next_msg_ids_t const *);
@@ -107,12 +108,13 @@ extern err_t transport_get_link_details(transport_details_t *details, transport_
 ////////////
 typedef struct Author author_t;
 
-extern err_t auth_new(author_t **auth, char const *seed, uint8_t implementation, transport_t *transport);
-extern err_t auth_recover(author_t **auth, char const *seed, address_t const *announcement, uint8_t implementation, transport_t *transport);
+extern err_t auth_new(author_t **auth, char const *seed, uint8_t implementation, uint64_t channel_idx, transport_t *transport);
+extern err_t auth_recover(author_t **auth, char const *seed, address_t const *announcement, uint8_t implementation, uint64_t channel_idx, transport_t *transport);
 extern void auth_drop(author_t *);
 
 extern err_t auth_import(author_t **auth, buffer_t buffer, char const *password, transport_t *transport);
 extern err_t auth_export(buffer_t *buf, author_t const *user, char const *password);
+extern err_t auth_export_with_kdf(buffer_t *buf, author_t const *user, char const *password, uint32_t memory_kib, uint32_t iterations);
 
 extern err_t auth_channel_address(channel_address_t const **addr, author_t const *user);
 extern err_t auth_is_multi_branching(uint8_t *flag, author_t const *user);
@@ -123,9 +125,12 @@ extern err_t auth_send_announce(address_t const **addr, author_t *author);
 // Keyload
 extern err_t auth_send_keyload_for_everyone(message_links_t *links, author_t *author, address_t const *link_to);
 extern err_t auth_send_keyload(message_links_t *links, author_t *author, address_t const *link_to, psk_ids_t *psk_ids, ke_pks_t ke_pks);
+extern err_t auth_remove_subscriber(message_links_t *links, author_t *author, public_key_t const *pk, address_t const *rekey_link);
+extern err_t auth_remove_psk(message_links_t *links, author_t *author, psk_id_t const *pskid, address_t const *rekey_link);
 
 // Subscribe
 extern err_t auth_receive_subscribe(author_t *author, address_t const *address);
+extern err_t auth_receive_unsubscribe(author_t *author, address_t const *address);
 
 // Tagged Packets
 extern err_t auth_send_tagged_packet(message_links_t *links, author_t *author, message_links_t link_to, uint8_t const *public_payload_ptr, size_t public_payload_size, uint8_t const *masked_payload_ptr, size_t masked_payload_size);
@@ -158,12 +163,14 @@ extern err_t sub_new(subscriber_t **sub, char const *seed, transport_t *transpor
 extern err_t sub_recover(subscriber_t **sub, char const *seed, address_t const *announcement, transport_t *transport);
 extern err_t sub_import(subscriber_t **sub, buffer_t buffer, char const *password, transport_t *transport);
 extern err_t sub_export(buffer_t *buf, subscriber_t const *subscriber, char const *password);
+extern err_t sub_export_with_kdf(buffer_t *buf, subscriber_t const *subscriber, char const *password, uint32_t memory_kib, uint32_t iterations);
 extern void sub_drop(subscriber_t *);
 
 extern err_t sub_channel_address(channel_address_t const **addr, subscriber_t const *subscriber);
 extern err_t sub_is_multi_branching(uint8_t *flag, subscriber_t const *subscriber);
 extern err_t sub_get_public_key(public_key_t const **pk, subscriber_t const *subscriber);
 extern err_t sub_author_public_key(public_key_t const **pk, subscriber_t const *subscriber);
+extern err_t sub_channel_metadata(channel_metadata_t const **metadata, subscriber_t const *subscriber);
 
 // Registration state
 extern uint8_t sub_is_registered(subscriber_t const *subscriber);
@@ -173,6 +180,7 @@ extern void sub_unregister(subscriber_t *subscriber);
 extern err_t sub_receive_announce(subscriber_t *subscriber, address_t const *address);
 // Subscribe
 extern err_t sub_send_subscribe(address_t const **link, subscriber_t *subscriber, address_t const *announcement_link);
+extern err_t sub_send_unsubscribe(address_t const **link, subscriber_t *subscriber, address_t const *subscription_link);
 // Keyload
 extern err_t sub_receive_keyload(subscriber_t *subscriber, address_t const *address);
 extern err_t sub_receive_keyload_from_ids(message_links_t *links, subscriber_t *subscriber, next_msg_ids_t const *next_msg_ids);
@@ -209,6 +217,7 @@ extern char const *get_msgid_str(msgid_t const *msgid);
 
 extern char const *get_address_inst_str(address_t const *address);
 extern char const *get_address_id_str(address_t const *address);
+extern char const *get_address_bech32_str(address_t const *address);
 
 extern char const *public_key_to_string(public_key_t *pubkey);
 
@@ -218,6 +227,13 @@ extern packet_payloads_t get_indexed_payload(unwrapped_messages_t const *message
 
 extern char const *get_address_index_str(address_t const *address);
 
+extern char const *get_channel_metadata_name(channel_metadata_t const *metadata);
+extern char const *get_channel_metadata_description(channel_metadata_t const *metadata);
+extern char const *get_channel_metadata_content_schema(channel_metadata_t const *metadata);
+extern size_t get_channel_metadata_properties_count(channel_metadata_t const *metadata);
+extern char const *get_channel_metadata_property_key(channel_metadata_t const *metadata, size_t index);
+extern char const *get_channel_metadata_property_value(channel_metadata_t const *metadata, size_t index);
+
 extern address_t const *get_link_from_state(user_state_t const *state, public_key_t const *pub_key);
 
 extern char const *pskid_as_str(psk_id_t const *pskid);
@@ -71,7 +71,7 @@ int main()
   tsp = transport_new();
 #endif
   printf("Making author with seed '%s'... ", seed);
-  e = auth_new(&auth, seed, implementation_type, tsp);
+  e = auth_new(&auth, seed, implementation_type, 0, tsp);
   printf("%s\n", !e ? "done" : "failed");
   if(e) goto cleanup;
 
@@ -486,7 +486,7 @@ cleanup7:
     unwrapped_messages_t const *message_returns = NULL;
 
     printf("Recovering author... ");
-    e = auth_recover(&recovered_auth, seed, ann_link, implementation_type, tsp);
+    e = auth_recover(&recovered_auth, seed, ann_link, implementation_type, 0, tsp);
     printf("  %s\n", !e ? "done" : "failed");
     if(e) goto cleanup8;
 
@@ -8,6 +8,7 @@ pub unsafe extern "C" fn auth_new(
     c_author: *mut *mut Author,
     c_seed: *const c_char,
     channel_type: uint8_t,
+    channel_idx: uint64_t,
     transport: *mut TransportWrap,
 ) -> Err {
     if c_seed == null() {
@@ -18,7 +19,7 @@ pub unsafe extern "C" fn auth_new(
     CStr::from_ptr(c_seed).to_str().map_or(Err::BadArgument, |seed| {
         transport.as_ref().map_or(Err::NullArgument, |tsp| {
             c_author.as_mut().map_or(Err::NullArgument, |author| {
-                let user = Author::new(seed, channel_impl, tsp.clone());
+                let user = Author::new_with_index(seed, channel_impl, channel_idx, tsp.clone());
                 *author = safe_into_mut_ptr(user);
                 Err::Ok
             })
@@ -33,6 +34,7 @@ pub unsafe extern "C" fn auth_recover(
     c_seed: *const c_char,
     c_ann_address: *const Address,
     channel_type: uint8_t,
+    channel_idx: uint64_t,
     transport: *mut TransportWrap,
 ) -> Err {
     if c_seed == null() {
@@ -43,10 +45,13 @@ pub unsafe extern "C" fn auth_recover(
         c_ann_address.as_ref().map_or(Err::NullArgument, |addr| {
             transport.as_ref().map_or(Err::NullArgument, |tsp| {
                 c_author.as_mut().map_or(Err::NullArgument, |author| {
-                    Author::recover(seed, addr, channel_impl, tsp.clone()).map_or(Err::OperationFailed, |user| {
-                        *author = safe_into_mut_ptr(user);
-                        Err::Ok
-                    })
+                    Author::recover_with_index(seed, addr, channel_impl, channel_idx, tsp.clone()).map_or(
+                        Err::OperationFailed,
+                        |user| {
+                            *author = safe_into_mut_ptr(user);
+                            Err::Ok
+                        },
+                    )
                 })
             })
         })
@@ -96,6 +101,32 @@ pub unsafe extern "C" fn auth_export(buf: *mut Buffer, c_author: *mut Author, c_
     })
 }
 
+/// Export user state with custom cost of the password key derivation
+#[no_mangle]
+pub unsafe extern "C" fn auth_export_with_kdf(
+    buf: *mut Buffer,
+    c_author: *mut Author,
+    c_password: *const c_char,
+    memory_kib: uint32_t,
+    iterations: uint32_t,
+) -> Err {
+    if c_password == null() {
+        return Err::NullArgument;
+    }
+
+    let kdf = KdfParams::new(memory_kib, iterations);
+    CStr::from_ptr(c_password).to_str().map_or(Err::BadArgument, |password| {
+        c_author.as_ref().map_or(Err::NullArgument, |user| {
+            buf.as_mut().map_or(Err::NullArgument, |buf| {
+                user.export_with_kdf(password, &kdf).map_or(Err::OperationFailed, |bytes| {
+                    *buf = bytes.into();
+                    Err::Ok
+                })
+            })
+        })
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn auth_drop(user: *mut Author) {
     safe_drop_mut_ptr(user)
@@ -157,6 +188,16 @@ pub unsafe extern "C" fn auth_receive_subscribe(user: *mut Author, link: *const
     })
 }
 
+/// unwrap an unsubscribe message and remove the subscriber from the list of subscribers
+#[no_mangle]
+pub unsafe extern "C" fn auth_receive_unsubscribe(user: *mut Author, link: *const Address) -> Err {
+    user.as_mut().map_or(Err::NullArgument, |user| {
+        link.as_ref().map_or(Err::NullArgument, |link| {
+            user.receive_unsubscribe(link).map_or(Err::OperationFailed, |_| Err::Ok)
+        })
+    })
+}
+
 /// Create a new keyload for a list of subscribers.
 #[no_mangle]
 pub unsafe extern "C" fn auth_send_keyload(
@@ -206,6 +247,48 @@ pub unsafe extern "C" fn auth_send_keyload_for_everyone(
     })
 }
 
+/// Remove a subscriber, then share a new keyload with the remaining subscribers unless `rekey_link` is null.
+#[no_mangle]
+pub unsafe extern "C" fn auth_remove_subscriber(
+    r: *mut MessageLinks,
+    user: *mut Author,
+    pk: *const PublicKey,
+    rekey_link: *const Address,
+) -> Err {
+    r.as_mut().map_or(Err::NullArgument, |r| {
+        user.as_mut().map_or(Err::NullArgument, |user| {
+            pk.as_ref().map_or(Err::NullArgument, |pk| {
+                user.remove_subscriber(pk, rekey_link.as_ref())
+                    .map_or(Err::OperationFailed, |response| {
+                        *r = response.map_or(MessageLinks::default(), Into::into);
+                        Err::Ok
+                    })
+            })
+        })
+    })
+}
+
+/// Remove a pre shared key, then share a new keyload with the remaining subscribers unless `rekey_link` is null.
+#[no_mangle]
+pub unsafe extern "C" fn auth_remove_psk(
+    r: *mut MessageLinks,
+    user: *mut Author,
+    pskid: *const PskId,
+    rekey_link: *const Address,
+) -> Err {
+    r.as_mut().map_or(Err::NullArgument, |r| {
+        user.as_mut().map_or(Err::NullArgument, |user| {
+            pskid.as_ref().map_or(Err::NullArgument, |pskid| {
+                user.remove_psk(*pskid, rekey_link.as_ref())
+                    .map_or(Err::OperationFailed, |response| {
+                        *r = response.map_or(MessageLinks::default(), Into::into);
+                        Err::Ok
+                    })
+            })
+        })
+    })
+}
+
 /// Process a Tagged packet message
 #[no_mangle]
 pub unsafe extern "C" fn auth_send_tagged_packet(
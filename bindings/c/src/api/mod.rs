@@ -7,6 +7,8 @@ use iota_streams::{
         cty::{
             c_char,
             size_t,
+            uint32_t,
+            uint64_t,
             uint8_t,
         },
         identifier::Identifier,
@@ -63,7 +65,7 @@ pub(crate) fn safe_drop_mut_ptr<T>(p: *mut T) {
 /// The pointer might be [`null`] if the String contains a null byte (which is invalid)
 ///
 /// [`null`]: https://doc.rust-lang.org/std/ptr/fn.null.html
-fn _string_into_raw(string: impl Into<Vec<u8>>) -> *const c_char {
+fn string_into_raw(string: impl Into<Vec<u8>>) -> *const c_char {
     CString::new(string).map_or_else(|_e| null_mut(), CString::into_raw)
 }
 
@@ -570,6 +572,13 @@ pub unsafe extern "C" fn get_address_id_str(address: *const Address) -> *const c
     address.as_ref().map_or(null(), |addr| get_msgid_str(&addr.msgid))
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn get_address_bech32_str(address: *const Address) -> *const c_char {
+    address
+        .as_ref()
+        .map_or(null(), |addr| string_into_raw_unchecked(addr.to_bech32()))
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn get_address_index_str(address: *const Address) -> *const c_char {
     address.as_ref().map_or(null(), |addr| {
@@ -595,8 +604,54 @@ pub unsafe extern "C" fn get_indexed_payload(msgs: *const UnwrappedMessages, ind
         .map_or(PacketPayloads::default(), |msgs| handle_message_contents(&msgs[index]))
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn get_channel_metadata_name(metadata: *const ChannelMetadata) -> *const c_char {
+    metadata.as_ref().map_or(null(), |m| string_into_raw(m.name.as_str()))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn get_channel_metadata_description(metadata: *const ChannelMetadata) -> *const c_char {
+    metadata
+        .as_ref()
+        .map_or(null(), |m| string_into_raw(m.description.as_str()))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn get_channel_metadata_content_schema(metadata: *const ChannelMetadata) -> *const c_char {
+    metadata
+        .as_ref()
+        .map_or(null(), |m| string_into_raw(m.content_schema.as_str()))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn get_channel_metadata_properties_count(metadata: *const ChannelMetadata) -> size_t {
+    metadata.as_ref().map_or(0, |m| m.properties.len())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn get_channel_metadata_property_key(
+    metadata: *const ChannelMetadata,
+    index: size_t,
+) -> *const c_char {
+    metadata
+        .as_ref()
+        .and_then(|m| m.properties.get(index))
+        .map_or(null(), |(key, _)| string_into_raw(key.as_str()))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn get_channel_metadata_property_value(
+    metadata: *const ChannelMetadata,
+    index: size_t,
+) -> *const c_char {
+    metadata
+        .as_ref()
+        .and_then(|m| m.properties.get(index))
+        .map_or(null(), |(_, value)| string_into_raw(value.as_str()))
+}
+
 fn handle_message_contents(m: &UnwrappedMessage) -> PacketPayloads {
-    match &m.body {
+    match &m.body.content {
         MessageContent::TaggedPacket {
             public_payload: p,
             masked_payload: m,
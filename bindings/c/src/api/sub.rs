@@ -93,6 +93,32 @@ pub unsafe extern "C" fn sub_export(buf: *mut Buffer, c_sub: *mut Subscriber, c_
     })
 }
 
+/// Export user state with custom cost of the password key derivation
+#[no_mangle]
+pub unsafe extern "C" fn sub_export_with_kdf(
+    buf: *mut Buffer,
+    c_sub: *mut Subscriber,
+    c_password: *const c_char,
+    memory_kib: uint32_t,
+    iterations: uint32_t,
+) -> Err {
+    if c_password == null() {
+        return Err::NullArgument;
+    }
+
+    let kdf = KdfParams::new(memory_kib, iterations);
+    CStr::from_ptr(c_password).to_str().map_or(Err::BadArgument, |password| {
+        c_sub.as_ref().map_or(Err::NullArgument, |user| {
+            buf.as_mut().map_or(Err::NullArgument, |buf| {
+                user.export_with_kdf(password, &kdf).map_or(Err::OperationFailed, |bytes| {
+                    *buf = bytes.into();
+                    Err::Ok
+                })
+            })
+        })
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn sub_drop(user: *mut Subscriber) {
     safe_drop_mut_ptr(user)
@@ -143,6 +169,19 @@ pub unsafe extern "C" fn sub_author_public_key(pk: *mut *const PublicKey, user:
     })
 }
 
+/// Channel metadata received with the announcement.
+#[no_mangle]
+pub unsafe extern "C" fn sub_channel_metadata(metadata: *mut *const ChannelMetadata, user: *const Subscriber) -> Err {
+    user.as_ref().map_or(Err::NullArgument, |user| {
+        metadata.as_mut().map_or(Err::NullArgument, |metadata| {
+            user.channel_metadata().map_or(Err::OperationFailed, |channel_metadata| {
+                *metadata = channel_metadata as *const ChannelMetadata;
+                Err::Ok
+            })
+        })
+    })
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn sub_is_registered(user: *const Subscriber) -> u8 {
     user.as_ref().map_or(0, |user| if user.is_registered() { 1 } else { 0 })
@@ -188,6 +227,28 @@ pub unsafe extern "C" fn sub_send_subscribe(
     })
 }
 
+/// Unsubscribe from a Channel app instance.
+#[no_mangle]
+pub unsafe extern "C" fn sub_send_unsubscribe(
+    r: *mut *const Address,
+    user: *mut Subscriber,
+    subscription_link: *const Address,
+) -> Err {
+    r.as_mut().map_or(Err::NullArgument, |r| {
+        user.as_mut().map_or(Err::NullArgument, |user| {
+            subscription_link
+                .as_ref()
+                .map_or(Err::NullArgument, |subscription_link| -> Err {
+                    user.send_unsubscribe(subscription_link)
+                        .map_or(Err::OperationFailed, |link| -> Err {
+                            *r = safe_into_ptr(link);
+                            Err::Ok
+                        })
+                })
+        })
+    })
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn sub_send_tagged_packet(
     r: *mut MessageLinks,
@@ -1,4 +1,7 @@
-use core::convert::TryInto as _;
+use core::convert::{
+    TryFrom,
+    TryInto as _,
+};
 use wasm_bindgen::prelude::*;
 
 use crate::{
@@ -26,6 +29,7 @@ use iota_streams::{
         tangle::{
             Address as ApiAddress,
             Author as ApiAuthor,
+            KdfParams,
         },
     },
     core::{
@@ -34,7 +38,10 @@ use iota_streams::{
             String,
             ToString,
         },
-        psk::pskid_to_hex_string,
+        psk::{
+            pskid_from_hex_str,
+            pskid_to_hex_string,
+        },
     },
     ddml::types::*,
 };
@@ -47,19 +54,25 @@ pub struct Author {
 #[wasm_bindgen]
 impl Author {
     #[wasm_bindgen(constructor)]
-    pub fn new(seed: String, options: SendOptions, implementation: ChannelType) -> Author {
+    pub fn new(seed: String, options: SendOptions, implementation: ChannelType, channel_idx: u64) -> Author {
         let mut client = ApiClient::new_from_url(&options.url());
         client.set_send_options(options.into());
         let transport = Rc::new(RefCell::new(client));
 
-        let author = Rc::new(RefCell::new(ApiAuthor::new(&seed, implementation.into(), transport)));
+        let author = Rc::new(RefCell::new(ApiAuthor::new_with_index(
+            &seed,
+            implementation.into(),
+            channel_idx,
+            transport,
+        )));
         Author { author }
     }
 
-    pub fn from_client(client: Client, seed: String, implementation: ChannelType) -> Author {
-        let author = Rc::new(RefCell::new(ApiAuthor::new(
+    pub fn from_client(client: Client, seed: String, implementation: ChannelType, channel_idx: u64) -> Author {
+        let author = Rc::new(RefCell::new(ApiAuthor::new_with_index(
             &seed,
             implementation.into(),
+            channel_idx,
             client.to_inner(),
         )));
         Author { author }
@@ -85,6 +98,14 @@ impl Author {
             .map_or_else(|err| Err(JsValue::from_str(&err.to_string())), Ok)
     }
 
+    #[wasm_bindgen(catch)]
+    pub fn export_with_kdf(&self, password: &str, memory_kib: u32, iterations: u32) -> Result<Vec<u8>> {
+        self.author
+            .borrow_mut()
+            .export_with_kdf(password, &KdfParams::new(memory_kib, iterations))
+            .map_or_else(|err| Err(JsValue::from_str(&err.to_string())), Ok)
+    }
+
     pub fn clone(&self) -> Author {
         Author {
             author: self.author.clone(),
@@ -130,7 +151,7 @@ impl Author {
     pub async fn send_announce(self) -> Result<UserResponse> {
         self.author.borrow_mut().send_announce().await.map_or_else(
             |err| Err(JsValue::from_str(&err.to_string())),
-            |addr| Ok(UserResponse::new(Address::from_string(addr.to_string()), None, None)),
+            |addr| Ok(UserResponse::new(Address::from(&addr), None, None)),
         )
     }
 
@@ -147,15 +168,11 @@ impl Author {
             .map_or_else(
                 |err| Err(JsValue::from_str(&err.to_string())),
                 |(link, seq_link)| {
-                    if let Some(seq_link) = seq_link {
-                        Ok(UserResponse::from_strings(
-                            link.to_string(),
-                            Some(seq_link.to_string()),
-                            None,
-                        ))
-                    } else {
-                        Ok(UserResponse::from_strings(link.to_string(), None, None))
-                    }
+                    Ok(UserResponse::new(
+                        Address::from(&link),
+                        seq_link.as_ref().map(Address::from),
+                        None,
+                    ))
                 },
             )
     }
@@ -177,15 +194,57 @@ impl Author {
             .map_or_else(
                 |err| Err(JsValue::from_str(&err.to_string())),
                 |(link, seq_link)| {
-                    if let Some(seq_link) = seq_link {
-                        Ok(UserResponse::from_strings(
-                            link.to_string(),
-                            Some(seq_link.to_string()),
-                            None,
-                        ))
-                    } else {
-                        Ok(UserResponse::from_strings(link.to_string(), None, None))
-                    }
+                    Ok(UserResponse::new(
+                        Address::from(&link),
+                        seq_link.as_ref().map(Address::from),
+                        None,
+                    ))
+                },
+            )
+    }
+
+    /// Removes the recipient from the keyload recipients. When `rekey_link` is given, a new keyload for
+    /// the remaining recipients is sent linked to it and its links are returned.
+    #[wasm_bindgen(catch)]
+    pub async fn remove_subscriber(self, pk_str: String, rekey_link: Option<Address>) -> Result<Option<UserResponse>> {
+        let pk = public_key_from_string(&pk_str)?;
+        let rekey_link = match rekey_link {
+            Some(link) => Some(ApiAddress::try_from(link)?),
+            None => None,
+        };
+        self.author
+            .borrow_mut()
+            .remove_subscriber(&pk, rekey_link.as_ref())
+            .await
+            .map_or_else(
+                |err| Err(JsValue::from_str(&err.to_string())),
+                |rekeyed| {
+                    Ok(rekeyed.map(|(link, seq_link)| {
+                        UserResponse::new(Address::from(&link), seq_link.as_ref().map(Address::from), None)
+                    }))
+                },
+            )
+    }
+
+    /// Removes the recipient from the keyload recipients. When `rekey_link` is given, a new keyload for
+    /// the remaining recipients is sent linked to it and its links are returned.
+    #[wasm_bindgen(catch)]
+    pub async fn remove_psk(self, pskid_str: String, rekey_link: Option<Address>) -> Result<Option<UserResponse>> {
+        let pskid = to_result(pskid_from_hex_str(&pskid_str))?;
+        let rekey_link = match rekey_link {
+            Some(link) => Some(ApiAddress::try_from(link)?),
+            None => None,
+        };
+        self.author
+            .borrow_mut()
+            .remove_psk(pskid, rekey_link.as_ref())
+            .await
+            .map_or_else(
+                |err| Err(JsValue::from_str(&err.to_string())),
+                |rekeyed| {
+                    Ok(rekeyed.map(|(link, seq_link)| {
+                        UserResponse::new(Address::from(&link), seq_link.as_ref().map(Address::from), None)
+                    }))
                 },
             )
     }
@@ -210,15 +269,11 @@ impl Author {
             .map_or_else(
                 |err| Err(JsValue::from_str(&err.to_string())),
                 |(link, seq_link)| {
-                    if let Some(seq_link) = seq_link {
-                        Ok(UserResponse::from_strings(
-                            link.to_string(),
-                            Some(seq_link.to_string()),
-                            None,
-                        ))
-                    } else {
-                        Ok(UserResponse::from_strings(link.to_string(), None, None))
-                    }
+                    Ok(UserResponse::new(
+                        Address::from(&link),
+                        seq_link.as_ref().map(Address::from),
+                        None,
+                    ))
                 },
             )
     }
@@ -243,15 +298,11 @@ impl Author {
             .map_or_else(
                 |err| Err(JsValue::from_str(&err.to_string())),
                 |(link, seq_link)| {
-                    if let Some(seq_link) = seq_link {
-                        Ok(UserResponse::from_strings(
-                            link.to_string(),
-                            Some(seq_link.to_string()),
-                            None,
-                        ))
-                    } else {
-                        Ok(UserResponse::from_strings(link.to_string(), None, None))
-                    }
+                    Ok(UserResponse::new(
+                        Address::from(&link),
+                        seq_link.as_ref().map(Address::from),
+                        None,
+                    ))
                 },
             )
     }
@@ -269,6 +320,19 @@ impl Author {
             .map_or_else(|err| Err(JsValue::from_str(&err.to_string())), |_| Ok(()))
     }
 
+    #[wasm_bindgen(catch)]
+    pub async fn receive_unsubscribe(self, link_to: Address) -> Result<()> {
+        self.author
+            .borrow_mut()
+            .receive_unsubscribe(
+                &link_to
+                    .try_into()
+                    .map_or_else(|_err| ApiAddress::default(), |addr| addr),
+            )
+            .await
+            .map_or_else(|err| Err(JsValue::from_str(&err.to_string())), |_| Ok(()))
+    }
+
     #[wasm_bindgen(catch)]
     pub async fn receive_tagged_packet(self, link: Address) -> Result<UserResponse> {
         self.author
@@ -327,7 +391,7 @@ impl Author {
             .await
             .map_or_else(
                 |err| Err(JsValue::from_str(&err.to_string())),
-                |address| Ok(Address::from_string(address.to_string())),
+                |address| Ok(Address::from(&address)),
             )
     }
 
@@ -425,10 +489,7 @@ impl Author {
         let branching = self.author.borrow_mut().is_multi_branching();
         let mut ids = Vec::new();
         for (id, cursor) in self.author.borrow_mut().gen_next_msg_ids(branching).iter() {
-            ids.push(NextMsgId::new(
-                identifier_to_string(id),
-                Address::from_string(cursor.link.to_string()),
-            ));
+            ids.push(NextMsgId::new(identifier_to_string(id), Address::from(&cursor.link)));
         }
         Ok(ids.into_iter().map(JsValue::from).collect())
     }
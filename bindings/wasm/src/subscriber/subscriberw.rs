@@ -18,6 +18,7 @@ use iota_streams::{
         pskid_from_psk,
         tangle::{
             Address as ApiAddress,
+            KdfParams,
             Subscriber as ApiSubscriber,
         },
     },
@@ -117,6 +118,11 @@ impl Subscriber {
         )
     }
 
+    /// Channel metadata received with the announcement, if any
+    pub fn channel_metadata(&self) -> Option<ChannelMetadata> {
+        self.subscriber.borrow().channel_metadata().cloned().map(Into::into)
+    }
+
     #[wasm_bindgen(catch)]
     pub fn is_registered(&self) -> Result<bool> {
         Ok(self.subscriber.borrow_mut().is_registered())
@@ -136,6 +142,14 @@ impl Subscriber {
             .map_or_else(|err| Err(JsValue::from_str(&err.to_string())), Ok)
     }
 
+    #[wasm_bindgen(catch)]
+    pub fn export_with_kdf(&self, password: &str, memory_kib: u32, iterations: u32) -> Result<Vec<u8>> {
+        self.subscriber
+            .borrow_mut()
+            .export_with_kdf(password, &KdfParams::new(memory_kib, iterations))
+            .map_or_else(|err| Err(JsValue::from_str(&err.to_string())), Ok)
+    }
+
     #[wasm_bindgen(catch)]
     pub async fn receive_announcement(self, link: Address) -> Result<()> {
         self.subscriber
@@ -212,7 +226,7 @@ impl Subscriber {
             .await
             .map_or_else(
                 |err| Err(JsValue::from_str(&err.to_string())),
-                |address| Ok(Address::from_string(address.to_string())),
+                |address| Ok(Address::from(&address)),
             )
     }
 
@@ -265,7 +279,23 @@ impl Subscriber {
             .await
             .map_or_else(
                 |err| Err(JsValue::from_str(&err.to_string())),
-                |link| Ok(UserResponse::new(Address::from_string(link.to_string()), None, None)),
+                |link| Ok(UserResponse::new(Address::from(&link), None, None)),
+            )
+    }
+
+    #[wasm_bindgen(catch)]
+    pub async fn send_unsubscribe(self, link: Address) -> Result<UserResponse> {
+        self.subscriber
+            .borrow_mut()
+            .send_unsubscribe(
+                &link
+                    .try_into()
+                    .map_or_else(|_err| ApiAddress::default(), |addr: ApiAddress| addr),
+            )
+            .await
+            .map_or_else(
+                |err| Err(JsValue::from_str(&err.to_string())),
+                |link| Ok(UserResponse::new(Address::from(&link), None, None)),
             )
     }
 
@@ -289,15 +319,11 @@ impl Subscriber {
             .map_or_else(
                 |err| Err(JsValue::from_str(&err.to_string())),
                 |(link, seq_link)| {
-                    if let Some(seq_link) = seq_link {
-                        Ok(UserResponse::from_strings(
-                            link.to_string(),
-                            Some(seq_link.to_string()),
-                            None,
-                        ))
-                    } else {
-                        Ok(UserResponse::from_strings(link.to_string(), None, None))
-                    }
+                    Ok(UserResponse::new(
+                        Address::from(&link),
+                        seq_link.as_ref().map(Address::from),
+                        None,
+                    ))
                 },
             )
     }
@@ -322,15 +348,11 @@ impl Subscriber {
             .map_or_else(
                 |err| Err(JsValue::from_str(&err.to_string())),
                 |(link, seq_link)| {
-                    if let Some(seq_link) = seq_link {
-                        Ok(UserResponse::from_strings(
-                            link.to_string(),
-                            Some(seq_link.to_string()),
-                            None,
-                        ))
-                    } else {
-                        Ok(UserResponse::from_strings(link.to_string(), None, None))
-                    }
+                    Ok(UserResponse::new(
+                        Address::from(&link),
+                        seq_link.as_ref().map(Address::from),
+                        None,
+                    ))
                 },
             )
     }
@@ -21,6 +21,7 @@ use iota_streams::{
     },
     app_channels::api::tangle::{
         Address as ApiAddress,
+        ChannelMetadata as ApiChannelMetadata,
         ChannelType as ApiChannelType,
         MessageContent,
         PublicKey,
@@ -123,20 +124,13 @@ impl Address {
         self.msg_id = msg_id;
     }
 
-    #[wasm_bindgen(static_method_of = Address)]
-    pub fn from_string(link: String) -> Self {
-        let link_vec: Vec<&str> = link
-            .strip_prefix("<")
-            .unwrap_or(&link)
-            .strip_suffix(">")
-            .unwrap_or(&link)
-            .split(':')
-            .collect();
-
-        Address {
-            addr_id: link_vec[0].to_string(),
-            msg_id: link_vec[1].to_string(),
-        }
+    /// Parse an `appinst:msgid` address whose parts are either hex or Bech32 encoded. Throws if a
+    /// part is malformed or its Bech32 checksum does not match.
+    #[wasm_bindgen(static_method_of = Address, catch)]
+    pub fn from_string(link: String) -> Result<Address> {
+        let link = link.strip_prefix("<").unwrap_or(&link);
+        let link = link.strip_suffix(">").unwrap_or(link);
+        to_result(ApiAddress::from_str(link)).map(|addr| Address::from(&addr))
     }
 
     #[wasm_bindgen]
@@ -148,6 +142,13 @@ impl Address {
         link
     }
 
+    /// Checksummed representation of the address: the Bech32 encoded appinst and msgid separated
+    /// by a colon.
+    #[wasm_bindgen]
+    pub fn to_bech32(&self) -> Result<String> {
+        ApiAddress::try_from(self.copy()).map(|addr| addr.to_bech32())
+    }
+
     pub fn copy(&self) -> Self {
         Address {
             addr_id: self.addr_id.clone(),
@@ -156,6 +157,15 @@ impl Address {
     }
 }
 
+impl From<&ApiAddress> for Address {
+    fn from(addr: &ApiAddress) -> Self {
+        Address {
+            addr_id: addr.appinst.to_string(),
+            msg_id: addr.msgid.to_string(),
+        }
+    }
+}
+
 pub type ClientWrap = Rc<RefCell<Client>>;
 
 impl TryFrom<Address> for ApiAddress {
@@ -168,13 +178,13 @@ impl TryFrom<Address> for ApiAddress {
 pub fn get_message_contents(msgs: Vec<UnwrappedMessage>) -> Vec<UserResponse> {
     let mut payloads = Vec::new();
     for msg in msgs {
-        match msg.body {
+        match msg.body.content {
             MessageContent::SignedPacket {
                 pk,
                 public_payload: p,
                 masked_payload: m,
             } => payloads.push(UserResponse::new(
-                Address::from_string(msg.link.to_string()),
+                Address::from(&msg.link),
                 None,
                 Some(Message::new(Some(hex::encode(pk.to_bytes())), p.0, m.0)),
             )),
@@ -182,16 +192,12 @@ pub fn get_message_contents(msgs: Vec<UnwrappedMessage>) -> Vec<UserResponse> {
                 public_payload: p,
                 masked_payload: m,
             } => payloads.push(UserResponse::new(
-                Address::from_string(msg.link.to_string()),
+                Address::from(&msg.link),
                 None,
                 Some(Message::new(None, p.0, m.0)),
             )),
             MessageContent::Sequence => (),
-            _ => payloads.push(UserResponse::new(
-                Address::from_string(msg.link.to_string()),
-                None,
-                None,
-            )),
+            _ => payloads.push(UserResponse::new(Address::from(&msg.link), None, None)),
         };
     }
     payloads
@@ -243,7 +249,7 @@ pub struct Cursor {
 impl From<ApiCursor<ApiAddress>> for Cursor {
     fn from(cursor: ApiCursor<ApiAddress>) -> Self {
         Cursor {
-            link: Address::from_string(cursor.link.to_string()),
+            link: Address::from(&cursor.link),
             seq_no: cursor.seq_no,
             branch_no: cursor.branch_no,
         }
@@ -370,6 +376,49 @@ impl Message {
     }
 }
 
+#[wasm_bindgen]
+pub struct ChannelMetadata {
+    metadata: ApiChannelMetadata,
+}
+
+impl From<ApiChannelMetadata> for ChannelMetadata {
+    fn from(metadata: ApiChannelMetadata) -> Self {
+        ChannelMetadata { metadata }
+    }
+}
+
+#[wasm_bindgen]
+impl ChannelMetadata {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.metadata.name.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn description(&self) -> String {
+        self.metadata.description.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn content_schema(&self) -> String {
+        self.metadata.content_schema.clone()
+    }
+
+    /// Key/value properties as an array of `[key, value]` pairs
+    pub fn get_properties(&self) -> Array {
+        self.metadata
+            .properties
+            .iter()
+            .map(|(key, value)| -> JsValue {
+                let pair = Array::new();
+                pair.push(&JsValue::from(key.as_str()));
+                pair.push(&JsValue::from(value.as_str()));
+                pair.into()
+            })
+            .collect()
+    }
+}
+
 #[wasm_bindgen]
 impl NextMsgId {
     pub fn new(identifier: String, msgid: Address) -> Self {
@@ -395,19 +444,15 @@ impl UserResponse {
         }
     }
 
-    pub fn from_strings(link: String, seq_link: Option<String>, message: Option<Message>) -> Self {
-        let seq;
-        if let Some(seq_link) = seq_link {
-            seq = Some(Address::from_string(seq_link));
-        } else {
-            seq = None;
-        }
-
-        UserResponse {
-            link: Address::from_string(link),
-            seq_link: seq,
+    /// Parse the links of the response, see [`Address::from_string`].
+    #[wasm_bindgen(catch)]
+    pub fn from_strings(link: String, seq_link: Option<String>, message: Option<Message>) -> Result<UserResponse> {
+        let seq_link = seq_link.map(Address::from_string).transpose()?;
+        Ok(UserResponse {
+            link: Address::from_string(link)?,
+            seq_link,
             message,
-        }
+        })
     }
 
     pub fn copy(&self) -> Self {
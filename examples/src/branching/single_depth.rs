@@ -129,7 +129,7 @@ pub fn example<T: Transport>(transport: Rc<RefCell<T>>, channel_impl: ChannelTyp
 
     assert_eq!(unwrapped.len(), 10);
     for msg in unwrapped {
-        if let MessageContent::SignedPacket {pk: _, public_payload: _, masked_payload } = &msg.body {
+        if let MessageContent::SignedPacket {pk: _, public_payload: _, masked_payload } = &msg.body.content {
             println!("  Msg => <{}>: {}", msg.link.msgid, String::from_utf8(masked_payload.0.to_vec())?);
         } else {
             panic!("Packet found was not a signed packet from author")
@@ -141,7 +141,7 @@ pub fn example<T: Transport>(transport: Rc<RefCell<T>>, channel_impl: ChannelTyp
 
     println!("\nSubscriber B fetching 4th message");
     let msg = subscriberB.receive_msg_by_sequence_number(&anchor_msg_link, 4)?;
-    if let MessageContent::SignedPacket {pk: _, public_payload: _, masked_payload } = &msg.body {
+    if let MessageContent::SignedPacket {pk: _, public_payload: _, masked_payload } = &msg.body.content {
         println!("  Msg => <{}>: {}", msg.link.msgid, String::from_utf8(masked_payload.0.to_vec())?);
         assert_eq!(masked_payload.0, "Message 4".as_bytes().to_vec());
     } else {
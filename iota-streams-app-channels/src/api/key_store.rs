@@ -27,6 +27,8 @@ pub trait KeyStore<Info, F: PRP>: Default {
     fn contains(&self, id: &Identifier) -> bool;
     fn insert_cursor(&mut self, id: Identifier, info: Info) -> Result<()>;
     fn insert_psk(&mut self, id: Identifier, psk: Option<Psk>, info: Info) -> Result<()>;
+    /// Forget a participant, returns `false` if it was not known
+    fn remove(&mut self, id: &Identifier) -> bool;
     fn get_next_pskid(&self) -> Option<&Identifier>;
     fn keys(&self) -> Vec<(&Identifier, Vec<u8>)>;
     fn iter(&self) -> Vec<(&Identifier, &Info)>;
@@ -146,6 +148,13 @@ impl<Info, F: PRP> KeyStore<Info, F> for KeyMap<Info> {
         }
     }
 
+    fn remove(&mut self, id: &Identifier) -> bool {
+        match id {
+            Identifier::EdPubKey(_pk) => self.ke_pks.remove(id).is_some(),
+            Identifier::PskId(_id) => self.psks.remove(id).is_some(),
+        }
+    }
+
     fn keys(&self) -> Vec<(&Identifier, Vec<u8>)> {
         let mut keys: Vec<(&Identifier, Vec<u8>)> = self
             .ke_pks
@@ -14,6 +14,8 @@ use iota_streams_app::identifier::Identifier;
 use iota_streams_core::{
     panic_if_not,
     prelude::{
+        Arc,
+        Box,
         String,
         Vec,
     },
@@ -39,8 +41,19 @@ impl<Trans> Author<Trans> {
     /// * `multi_branching` - Boolean representing use of multi-branch or single-branch sequencing
     /// * `transport` - Transport object used for sending and receiving
     pub fn new(seed: &str, channel_type: ChannelType, transport: Trans) -> Self {
+        Self::new_with_index(seed, channel_type, 0, transport)
+    }
+
+    /// Create a new Author instance owning the channel with the given index, generate new Ed25519
+    /// key pair. The same seed can own many independent channels, each with its own index.
+    ///
+    /// # Arguments
+    /// * `seed` - A string slice representing the seed of the user [Characters: A-Z, 9]
+    /// * `channel_type` - Implementation type: [0: Single Branch, 1: Multi Branch , 2: Single Depth]
+    /// * `channel_idx` - Index of the channel owned by the seed
+    /// * `transport` - Transport object used for sending and receiving
+    pub fn new_with_index(seed: &str, channel_type: ChannelType, channel_idx: u64, transport: Trans) -> Self {
         let mut user = User::new(seed, channel_type, transport);
-        let channel_idx = 0_u64;
         let _ = user.user.create_channel(channel_idx);
         Self { user }
     }
@@ -70,6 +83,121 @@ impl<Trans> Author<Trans> {
         self.user.get_public_key()
     }
 
+    /// Channel metadata published in the announcement
+    pub fn channel_metadata(&self) -> Option<&ChannelMetadata> {
+        self.user.channel_metadata()
+    }
+
+    /// Set channel metadata (name, description, content schema and key/value properties) to be
+    /// signed and published in the announcement. Fails once the channel is announced.
+    ///
+    ///   # Arguments
+    ///   * `metadata` - Channel metadata, `None` to announce the channel without metadata
+    pub fn set_channel_metadata(&mut self, metadata: Option<ChannelMetadata>) -> Result<()> {
+        self.user.set_channel_metadata(metadata)
+    }
+
+    /// Whether the channel is announced with private links
+    pub fn has_private_links(&self) -> bool {
+        self.user.has_private_links()
+    }
+
+    /// Announce the channel with private links: links of messages following a keyload are derived
+    /// from a secret only its recipients know, so that other users can neither find the messages
+    /// nor tell who published them. Fails once the channel is announced.
+    ///
+    ///   # Arguments
+    ///   * `enabled` - Whether links are private
+    pub fn set_private_links(&mut self, enabled: bool) -> Result<()> {
+        self.user.set_private_links(enabled)
+    }
+
+    /// Replace the system clock as source of the timestamps of sent messages.
+    ///
+    ///   # Arguments
+    ///   * `clock` - Clock shared with the application, eg. a [`ManualClock`]
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.user.set_clock(clock)
+    }
+
+    /// Include the current time of the clock as authenticated publish timestamp in sent signed
+    /// and tagged packets.
+    ///
+    ///   # Arguments
+    ///   * `enabled` - Whether packets are timestamped, they are not by default
+    pub fn set_packet_timestamps(&mut self, enabled: bool) {
+        self.user.set_packet_timestamps(enabled)
+    }
+
+    /// Set the requirements on the publish timestamps of received signed and tagged packets,
+    /// packets failing them are not returned.
+    ///
+    ///   # Arguments
+    ///   * `freshness` - Required timestamp, maximum clock skew and maximum age of packets
+    pub fn set_freshness_policy(&mut self, freshness: FreshnessPolicy) {
+        self.user.set_freshness_policy(freshness)
+    }
+
+    /// Return boolean representing whether a message whose send failed is staged, no other message
+    /// can be sent until it is retried or discarded.
+    pub fn is_staged(&self) -> bool {
+        self.user.is_staged()
+    }
+
+    /// Drop the message whose send failed without committing it, the next message is sent at its
+    /// link. Only discard a message none of whose frames has been published. Returns whether a
+    /// message was staged.
+    pub fn discard_staged(&mut self) -> bool {
+        self.user.discard_staged()
+    }
+
+    /// Queue sent messages in the outbox instead of publishing them, eg. while the transport is
+    /// unreachable. Messages are committed as if they were published.
+    ///
+    ///   # Arguments
+    ///   * `offline` - Whether messages are queued, once back online they are published with `flush_outbox`
+    pub fn set_offline(&mut self, offline: bool) {
+        self.user.set_offline(offline)
+    }
+
+    /// Returns a boolean representing whether sent messages are queued in the outbox
+    pub fn is_offline(&self) -> bool {
+        self.user.is_offline()
+    }
+
+    /// Committed messages waiting to be published, exported and imported along with the user
+    /// state.
+    pub fn outbox(&self) -> &Outbox {
+        self.user.outbox()
+    }
+
+    /// Replace the outbox, eg. with one serialized with `Outbox::to_bytes`.
+    ///
+    ///   # Arguments
+    ///   * `outbox` - Messages waiting to be published
+    pub fn set_outbox(&mut self, outbox: Outbox) {
+        self.user.set_outbox(outbox)
+    }
+
+    /// Replace the blocking sleep between the retries of `flush_outbox`.
+    ///
+    ///   # Arguments
+    ///   * `sleep` - Wait for the given backoff, eg. a platform timer or a recorder in tests
+    #[cfg(not(feature = "async"))]
+    pub fn set_retry_sleep(&mut self, sleep: Sleep) {
+        self.user.set_retry_sleep(sleep)
+    }
+
+    /// Persist the user state to a state store from now on, the changes made by each operation
+    /// are saved when it completes. A state saved before, eg. by an instance that crashed, is
+    /// restored: the store must have been filled by a user created with the same seed.
+    ///
+    ///   # Arguments
+    ///   * `store` - Backend receiving the changes of the user state, eg. a [`FileStateStore`]
+    pub fn set_state_store(&mut self, store: Box<dyn StateStore>) -> Result<()> {
+        self.user.set_state_store(store)
+    }
+
     /// Store a PSK in the user instance
     ///
     ///   # Arguments
@@ -119,14 +247,38 @@ impl<Trans> Author<Trans> {
         Ok(state)
     }
 
-    /// Serialize user state and encrypt it with password.
+    /// Serialize user state and encrypt it with password, the outbox follows it as it is to be
+    /// published.
     ///
     ///   # Arguments
     ///   * `pwd` - Encryption password
+    #[cfg(feature = "std")]
     pub fn export(&self, pwd: &str) -> Result<Vec<u8>> {
         self.user.export(0, pwd)
     }
 
+    /// Serialize user state and encrypt it with password using custom key derivation parameters,
+    /// the outbox follows it as it is to be published.
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Encryption password
+    ///   * `kdf` - Memory and iteration cost of the password key derivation
+    #[cfg(feature = "std")]
+    pub fn export_with_kdf(&self, pwd: &str, kdf: &KdfParams) -> Result<Vec<u8>> {
+        self.user.export_with_kdf(0, pwd, kdf)
+    }
+
+    /// Serialize user state and encrypt it with password, salting the key derivation from the
+    /// given RNG. Unlike `export`, available without `std`.
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Encryption password
+    ///   * `kdf` - Memory and iteration cost of the password key derivation
+    ///   * `rng` - Cryptographically secure RNG drawing the salt
+    pub fn export_with_rng<R: RngCore + CryptoRng>(&self, pwd: &str, kdf: &KdfParams, rng: &mut R) -> Result<Vec<u8>> {
+        self.user.export_with_rng(0, pwd, kdf, rng)
+    }
+
     /// Deserialize user state and decrypt it with password.
     ///
     ///   # Arguments
@@ -136,6 +288,18 @@ impl<Trans> Author<Trans> {
     pub fn import(bytes: &[u8], pwd: &str, tsp: Trans) -> Result<Self> {
         User::<Trans>::import(bytes, 0, pwd, tsp).map(|user| Self { user })
     }
+
+    /// Deserialize user state and decrypt it with password, rejecting state whose key derivation
+    /// cost exceeds `limits` instead of the default [`KdfLimits`].
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user state
+    ///   * `pwd` - Encryption password
+    ///   * `limits` - Highest accepted memory and iteration cost of the password key derivation
+    ///   * `tsp` - Transport object
+    pub fn import_with_limits(bytes: &[u8], pwd: &str, limits: &KdfLimits, tsp: Trans) -> Result<Self> {
+        User::<Trans>::import_with_limits(bytes, 0, pwd, limits, tsp).map(|user| Self { user })
+    }
 }
 
 #[cfg(not(feature = "async"))]
@@ -150,7 +314,26 @@ impl<Trans: Transport + Clone> Author<Trans> {
     /// * `multi_branching` - Boolean representing use of multi-branch or single-branch sequencing
     /// * `transport` - Transport object used for sending and receiving
     pub fn recover(seed: &str, announcement: &Address, channel_type: ChannelType, transport: Trans) -> Result<Self> {
-        let mut author = Author::new(seed, channel_type, transport);
+        Self::recover_with_index(seed, announcement, channel_type, 0, transport)
+    }
+
+    /// Generates a new Author implementation owning the channel with the given index and syncs it
+    /// to the latest state, provided the announcement it generates matches the one at the given link
+    ///
+    ///  # Arguments
+    /// * `seed` - A string slice representing the seed of the user [Characters: A-Z, 9]
+    /// * `announcement` - An existing announcement message link for validation of ownership
+    /// * `channel_type` - Implementation type: [0: Single Branch, 1: Multi Branch , 2: Single Depth]
+    /// * `channel_idx` - Index of the channel owned by the seed
+    /// * `transport` - Transport object used for sending and receiving
+    pub fn recover_with_index(
+        seed: &str,
+        announcement: &Address,
+        channel_type: ChannelType,
+        channel_idx: u64,
+        transport: Trans,
+    ) -> Result<Self> {
+        let mut author = Author::new_with_index(seed, channel_type, channel_idx, transport);
 
         let ann = author.user.user.announce()?;
         let retrieved: Message = author.user.transport.recv_message(announcement)?;
@@ -187,6 +370,45 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.send_keyload_for_everyone(link_to)
     }
 
+    /// Remove a subscriber from the channel. If `rekey_link` is provided, a new keyload for the
+    /// remaining subscribers is attached to it: the removed subscriber will not be able to read
+    /// messages linked to the new keyload.
+    ///
+    ///  # Arguments
+    ///  * `pk` - ed25519 Public Key of the subscriber to be removed
+    ///  * `rekey_link` - Optional Address of the message the new keyload will be attached to
+    pub fn remove_subscriber(
+        &mut self,
+        pk: &PublicKey,
+        rekey_link: Option<&Address>,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        self.user.remove_subscriber(pk)?;
+        self.rekey(rekey_link)
+    }
+
+    /// Remove a pre shared key from the channel. If `rekey_link` is provided, a new keyload for the
+    /// remaining subscribers is attached to it: holders of the removed key will not be able to read
+    /// messages linked to the new keyload.
+    ///
+    ///  # Arguments
+    ///  * `pskid` - An identifier representing a pre shared key
+    ///  * `rekey_link` - Optional Address of the message the new keyload will be attached to
+    pub fn remove_psk(
+        &mut self,
+        pskid: PskId,
+        rekey_link: Option<&Address>,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        self.user.remove_psk(pskid)?;
+        self.rekey(rekey_link)
+    }
+
+    fn rekey(&mut self, rekey_link: Option<&Address>) -> Result<Option<(Address, Option<Address>)>> {
+        match rekey_link {
+            Some(link_to) => self.user.send_keyload_for_everyone(link_to).map(Some),
+            None => Ok(None),
+        }
+    }
+
     /// Create and send a signed packet.
     ///
     ///  # Arguments
@@ -217,6 +439,12 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.send_tagged_packet(link_to, public_payload, masked_payload)
     }
 
+    /// Send again the message whose send failed, along with its sequence message, and return their
+    /// links.
+    pub fn retry_staged(&mut self) -> Result<(Address, Option<Address>)> {
+        self.user.retry_staged()
+    }
+
     /// Receive and process a subscribe message.
     ///
     ///  # Arguments
@@ -249,11 +477,35 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_sequence(link)
     }
 
+    /// Publish the messages queued in the outbox in order and return their number. Stops at the
+    /// first message that cannot be sent after retrying, it is kept in the outbox with the
+    /// following ones.
+    ///
+    ///   # Arguments
+    ///   * `policy` - Retries of each failed send
+    pub fn flush_outbox(&mut self, policy: RetryPolicy) -> Result<usize> {
+        self.user.flush_outbox(policy)
+    }
+
     /// Retrieves the next message for each user (if present in transport layer) and returns them
     pub fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
         self.user.fetch_next_msgs()
     }
 
+    /// Retrieves the next message for each user and returns the result per publisher, so that
+    /// transport failures can be told apart from messages that fail to unwrap. Publishers with no
+    /// new message are left out.
+    pub fn try_fetch_next_msgs(&mut self) -> Vec<FetchResult> {
+        self.user.try_fetch_next_msgs()
+    }
+
+    /// Iterator over new messages, fetched one at a time from the next message links of all known
+    /// publishers. Messages that fail to unwrap are returned as errors, iteration can be stopped at
+    /// any point.
+    pub fn messages(&mut self) -> Messages<'_, Trans> {
+        Messages::new(&mut self.user)
+    }
+
     /// Iteratively fetches next message until no new messages can be found, and return a vector
     /// containing all of them.
     pub fn fetch_all_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
@@ -297,10 +549,14 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_message(link)
     }
 
-    // Unsubscribe a subscriber
-    // pub pub fn receive_unsubscribe(&mut self, link: Address) -> Result<()> {
-    // self.user.handle_unsubscribe(link, MsgInfo::Unsubscribe)
-    // }
+    /// Receive and process an unsubscribe message. The sender is removed from the list of
+    /// subscribers and will not be included in subsequent keyloads.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_unsubscribe(&mut self, link: &Address) -> Result<()> {
+        self.user.receive_unsubscribe(link)
+    }
 
     /// Receive and process a message with a known anchor link and message number. This can only
     /// be used if the channel is a single depth channel.
@@ -330,7 +586,26 @@ impl<Trans: Transport + Clone> Author<Trans> {
         channel_type: ChannelType,
         transport: Trans,
     ) -> Result<Self> {
-        let mut author = Author::new(seed, channel_type, transport);
+        Self::recover_with_index(seed, announcement, channel_type, 0, transport).await
+    }
+
+    /// Generates a new Author implementation owning the channel with the given index and syncs it
+    /// to the latest state, provided the announcement it generates matches the one at the given link
+    ///
+    ///  # Arguments
+    /// * `seed` - A string slice representing the seed of the user [Characters: A-Z, 9]
+    /// * `announcement` - An existing announcement message link for validation of ownership
+    /// * `channel_type` - Implementation type: [0: Single Branch, 1: Multi Branch , 2: Single Depth]
+    /// * `channel_idx` - Index of the channel owned by the seed
+    /// * `transport` - Transport object used for sending and receiving
+    pub async fn recover_with_index(
+        seed: &str,
+        announcement: &Address,
+        channel_type: ChannelType,
+        channel_idx: u64,
+        transport: Trans,
+    ) -> Result<Self> {
+        let mut author = Author::new_with_index(seed, channel_type, channel_idx, transport);
 
         let ann = author.user.user.announce()?;
         let retrieved: Message = author.user.transport.recv_message(announcement).await?;
@@ -367,6 +642,45 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.send_keyload_for_everyone(link_to).await
     }
 
+    /// Remove a subscriber from the channel. If `rekey_link` is provided, a new keyload for the
+    /// remaining subscribers is attached to it: the removed subscriber will not be able to read
+    /// messages linked to the new keyload.
+    ///
+    ///  # Arguments
+    ///  * `pk` - ed25519 Public Key of the subscriber to be removed
+    ///  * `rekey_link` - Optional Address of the message the new keyload will be attached to
+    pub async fn remove_subscriber(
+        &mut self,
+        pk: &PublicKey,
+        rekey_link: Option<&Address>,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        self.user.remove_subscriber(pk)?;
+        self.rekey(rekey_link).await
+    }
+
+    /// Remove a pre shared key from the channel. If `rekey_link` is provided, a new keyload for the
+    /// remaining subscribers is attached to it: holders of the removed key will not be able to read
+    /// messages linked to the new keyload.
+    ///
+    ///  # Arguments
+    ///  * `pskid` - An identifier representing a pre shared key
+    ///  * `rekey_link` - Optional Address of the message the new keyload will be attached to
+    pub async fn remove_psk(
+        &mut self,
+        pskid: PskId,
+        rekey_link: Option<&Address>,
+    ) -> Result<Option<(Address, Option<Address>)>> {
+        self.user.remove_psk(pskid)?;
+        self.rekey(rekey_link).await
+    }
+
+    async fn rekey(&mut self, rekey_link: Option<&Address>) -> Result<Option<(Address, Option<Address>)>> {
+        match rekey_link {
+            Some(link_to) => self.user.send_keyload_for_everyone(link_to).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
     /// Create and send a signed packet.
     ///
     ///  # Arguments
@@ -401,6 +715,12 @@ impl<Trans: Transport + Clone> Author<Trans> {
             .await
     }
 
+    /// Send again the message whose send failed, along with its sequence message, and return their
+    /// links.
+    pub async fn retry_staged(&mut self) -> Result<(Address, Option<Address>)> {
+        self.user.retry_staged().await
+    }
+
     /// Receive and process a subscribe message.
     ///
     ///  # Arguments
@@ -433,11 +753,35 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_sequence(link).await
     }
 
+    /// Publish the messages queued in the outbox in order and return their number. Stops at the
+    /// first message that cannot be sent after retrying, it is kept in the outbox with the
+    /// following ones.
+    ///
+    ///   # Arguments
+    ///   * `policy` - Retries of each failed send
+    pub async fn flush_outbox(&mut self, policy: RetryPolicy) -> Result<usize> {
+        self.user.flush_outbox(policy).await
+    }
+
     /// Retrieves the next message for each user (if present in transport layer) and returns them
     pub async fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
         self.user.fetch_next_msgs().await
     }
 
+    /// Retrieves the next message for each user and returns the result per publisher, so that
+    /// transport failures can be told apart from messages that fail to unwrap. Publishers with no
+    /// new message are left out.
+    pub async fn try_fetch_next_msgs(&mut self) -> Vec<FetchResult> {
+        self.user.try_fetch_next_msgs().await
+    }
+
+    /// Stream of new messages, fetched one at a time from the next message links of all known
+    /// publishers. Messages that fail to unwrap are returned as errors, the stream can be dropped at
+    /// any point.
+    pub fn messages(&mut self) -> impl futures::Stream<Item = Result<UnwrappedMessage>> + '_ {
+        Messages::new(&mut self.user).into_stream()
+    }
+
     /// Iteratively fetches next message until no new messages can be found, and return a vector
     /// containing all of them.
     pub async fn fetch_all_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
@@ -482,10 +826,14 @@ impl<Trans: Transport + Clone> Author<Trans> {
         self.user.receive_message(link).await
     }
 
-    // Unsubscribe a subscriber
-    // pub async fn receive_unsubscribe(&mut self, link: Address) -> Result<()> {
-    // self.user.handle_unsubscribe(link, MsgInfo::Unsubscribe).await
-    // }
+    /// Receive and process an unsubscribe message. The sender is removed from the list of
+    /// subscribers and will not be included in subsequent keyloads.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_unsubscribe(&mut self, link: &Address) -> Result<()> {
+        self.user.receive_unsubscribe(link).await
+    }
 
     /// Receive and process a message with a known anchor link and message number. This can only
     /// be used if the channel is a single depth channel.
@@ -502,6 +850,15 @@ impl<Trans: Transport + Clone> Author<Trans> {
     }
 }
 
+#[cfg(feature = "async")]
+impl<Trans: Transport + TransportSubscribe<Address>> Author<Trans> {
+    /// Waits until new messages are published by known publishers and returns them, without
+    /// polling the transport.
+    pub async fn await_next_msgs(&mut self) -> Result<Vec<UnwrappedMessage>> {
+        self.user.await_next_msgs().await
+    }
+}
+
 impl<Trans: Clone> fmt::Display for Author<Trans> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -0,0 +1,67 @@
+use core::time::Duration;
+
+use iota_streams_core::{
+    err,
+    Errors::{
+        MissingMsgTimestamp,
+        MsgTimestampExpired,
+        MsgTimestampInFuture,
+    },
+    Result,
+};
+
+/// Requirements on the authenticated publish timestamps of received signed and tagged packets.
+///
+/// Timestamps are compared against the receiver's clock, see `User::set_clock`. The default policy
+/// accepts any packet, timestamped or not.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FreshnessPolicy {
+    /// Reject packets carrying no timestamp.
+    pub require_timestamp: bool,
+    /// Reject packets timestamped further ahead of the local clock.
+    pub max_clock_skew: Option<Duration>,
+    /// Reject packets timestamped further behind the local clock.
+    pub max_age: Option<Duration>,
+}
+
+impl FreshnessPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_required_timestamp(mut self, require_timestamp: bool) -> Self {
+        self.require_timestamp = require_timestamp;
+        self
+    }
+
+    pub fn with_max_clock_skew(mut self, max_clock_skew: Duration) -> Self {
+        self.max_clock_skew = Some(max_clock_skew);
+        self
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Check a packet `timestamp` against the local time `now`, both in milliseconds since the
+    /// Unix epoch.
+    pub fn check(&self, timestamp: Option<u64>, now: u64) -> Result<()> {
+        let timestamp = match timestamp {
+            Some(timestamp) => timestamp,
+            None if self.require_timestamp => return err!(MissingMsgTimestamp),
+            None => return Ok(()),
+        };
+        if let Some(skew) = self.max_clock_skew {
+            if timestamp > now.saturating_add(skew.as_millis() as u64) {
+                return err!(MsgTimestampInFuture(timestamp, now));
+            }
+        }
+        if let Some(age) = self.max_age {
+            if timestamp < now.saturating_sub(age.as_millis() as u64) {
+                return err!(MsgTimestampExpired(timestamp, now));
+            }
+        }
+        Ok(())
+    }
+}
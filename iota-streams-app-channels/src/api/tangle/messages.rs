@@ -0,0 +1,103 @@
+//! Streaming of new messages for Author and Subscriber.
+//!
+//! Next message links are generated for all known publishers and each of them is fetched in turn.
+//! Once every link of a round has been tried, new links are generated from the updated state
+//! and the process repeats for as long as the previous round has yielded at least one message.
+//! Links at which messages failed to be retrieved or unwrapped are not retried.
+
+use iota_streams_app::identifier::Identifier;
+use iota_streams_core::{
+    prelude::Vec,
+    Result,
+};
+
+use super::*;
+
+/// Messages fetched one by one from the next message links of all known publishers.
+///
+/// Implements `Iterator` in sync builds and can be turned into a `futures::Stream` with the
+/// `async` feature. Dropping it stops fetching, messages already returned are committed to the
+/// user state.
+pub struct Messages<'a, Trans> {
+    user: &'a mut User<Trans>,
+    ids: Vec<(Identifier, Cursor<Address>)>,
+    failed: Vec<Address>,
+    progressed: bool,
+}
+
+impl<'a, Trans> Messages<'a, Trans> {
+    pub(crate) fn new(user: &'a mut User<Trans>) -> Self {
+        Self {
+            user,
+            ids: Vec::new(),
+            failed: Vec::new(),
+            progressed: true,
+        }
+    }
+
+    /// Next publisher and link to fetch, `None` once a whole round has yielded no message.
+    fn next_link(&mut self) -> Option<(Identifier, Address)> {
+        loop {
+            if self.ids.is_empty() {
+                if !self.progressed {
+                    return None;
+                }
+                self.progressed = false;
+                self.ids = self.user.user.gen_next_msg_ids(self.user.is_multi_branching());
+                self.ids.reverse();
+            }
+            let (id, cursor) = self.ids.pop()?;
+            if !self.failed.contains(&cursor.link) {
+                return Some((id, cursor.link));
+            }
+        }
+    }
+
+    fn record(&mut self, msg: Option<FetchResult>) -> Option<Result<UnwrappedMessage>> {
+        match msg? {
+            Ok(msg) => {
+                self.progressed = true;
+                Some(Ok(msg))
+            }
+            Err(e) => {
+                self.failed.push(e.link);
+                Some(Err(e.error))
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<'a, Trans: Transport> Iterator for Messages<'a, Trans> {
+    type Item = Result<UnwrappedMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((id, link)) = self.next_link() {
+            let msg = self.user.fetch_next_msg(id, &link);
+            if let Some(msg) = self.record(msg) {
+                return Some(msg);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, Trans: Transport> Messages<'a, Trans> {
+    async fn next_msg(&mut self) -> Option<Result<UnwrappedMessage>> {
+        while let Some((id, link)) = self.next_link() {
+            let msg = self.user.fetch_next_msg(id, &link).await;
+            if let Some(msg) = self.record(msg) {
+                return Some(msg);
+            }
+        }
+        None
+    }
+
+    /// Turn into a stream of messages.
+    pub fn into_stream(self) -> impl futures::Stream<Item = Result<UnwrappedMessage>> + 'a {
+        futures::stream::unfold(self, |mut messages| async move {
+            messages.next_msg().await.map(|msg| (msg, messages))
+        })
+    }
+}
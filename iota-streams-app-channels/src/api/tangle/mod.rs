@@ -2,6 +2,7 @@
 
 use super::key_store::KeyMap;
 use iota_streams_app::{
+    identifier::Identifier,
     message::{
         self,
         BinaryBody,
@@ -29,10 +30,50 @@ pub use transport::{
     TransportOptions as _,
 };
 
+/// Transports notifying about new messages, see `User::await_next_msgs`.
+pub use transport::TransportSubscribe;
+
+/// Retries of the sends publishing the outbox, see `User::flush_outbox`.
+pub use transport::layer::RetryPolicy;
+/// Wait between the retries publishing the outbox, see `User::set_retry_sleep`.
+#[cfg(not(feature = "async"))]
+pub use transport::layer::Sleep;
+
+/// Time sources of the timestamps of sent messages, see `User::set_clock`.
+pub use transport::clock::{
+    Clock,
+    ManualClock,
+    SystemClock,
+};
+
 pub use super::ChannelType;
 use super::DefaultF;
-use iota_streams_core::psk;
-use iota_streams_ddml::link_store::DefaultLinkStore;
+#[cfg(feature = "async")]
+use core::{
+    future::Future,
+    pin::Pin,
+};
+/// Password key derivation used for state export, its cost limits on import and the RNGs salting it.
+pub use iota_streams_core::prng::{
+    CryptoRng,
+    KdfLimits,
+    KdfParams,
+    RngCore,
+};
+#[cfg(feature = "async")]
+use iota_streams_core::{
+    prelude::Box,
+    Result,
+};
+use iota_streams_core::{
+    prelude::Vec,
+    psk,
+    Error,
+};
+use iota_streams_ddml::link_store::{
+    DefaultLinkStore,
+    JournaledLinkStore,
+};
 pub use iota_streams_ddml::types::Bytes;
 
 use iota_streams_core_edsig::signature::ed25519;
@@ -40,6 +81,9 @@ use iota_streams_core_edsig::signature::ed25519;
 /// Identifiers for Pre-Shared Keys
 pub type PskIds = psk::PskIds;
 
+/// Descriptive channel metadata carried by the announcement.
+pub use crate::message::announce::ChannelMetadata;
+
 /// Tangle Address Link type.
 pub type Address = TangleAddress;
 /// Tangle Address representing Channel Application Instance.
@@ -71,17 +115,112 @@ pub type KeyStore = KeyMap<SeqState>;
 /// Link Generator specifies algorithm for generating new message addressed.
 pub type LinkGen = DefaultTangleLinkGenerator<DefaultF>;
 
-/// Link Store.
-pub type LinkStore = DefaultLinkStore<DefaultF, MsgId, MsgInfo>;
+/// Link Store, journaling committed messages for the state store, see `User::set_state_store`.
+pub type LinkStore = JournaledLinkStore<DefaultLinkStore<DefaultF, MsgId, MsgInfo>, MsgId>;
 
 /// Test Transport.
 pub type BucketTransport = transport::BucketTransport<Address, Message>;
 
+/// Transport replicating messages over several backends.
+pub type MultiTransport = transport::MultiTransport<Address, Message>;
+
 /// Transportation trait for Tangle Client implementation
 // TODO: Use trait synonyms `pub Transport = transport::Transport<DefaultF, Address>;`.
+#[cfg(not(feature = "async"))]
 pub trait Transport: transport::Transport<Address, Message> + Clone {}
+#[cfg(not(feature = "async"))]
 impl<T> Transport for T where T: transport::Transport<Address, Message> + Clone {}
 
+/// Transportation trait for Tangle Client implementation
+///
+/// Implemented by async transports and, with the `async-send` feature, by `Send` transports
+/// wrapped in [`Sendable`].
+#[cfg(feature = "async")]
+pub trait Transport: for<'a> TransportFutures<'a> + Clone {}
+#[cfg(feature = "async")]
+impl<T> Transport for T where T: for<'a> TransportFutures<'a> + Clone {}
+
+/// Transport operations awaited by users; the futures of `User` are `Send` if these are.
+#[cfg(feature = "async")]
+pub trait TransportFutures<'a> {
+    type SendMessage: Future<Output = Result<()>> + 'a;
+    type RecvMessages: Future<Output = Result<Vec<Message>>> + 'a;
+    type RecvMessage: Future<Output = Result<Message>> + 'a;
+    type ConfirmationTimestamp: Future<Output = Result<Option<u64>>> + 'a;
+
+    fn send_message(&'a mut self, msg: &'a Message) -> Self::SendMessage;
+    fn recv_messages(&'a mut self, link: &'a Address) -> Self::RecvMessages;
+    fn recv_message(&'a mut self, link: &'a Address) -> Self::RecvMessage;
+    fn confirmation_timestamp(&'a mut self, msg: &'a Message) -> Self::ConfirmationTimestamp;
+}
+
+#[cfg(feature = "async")]
+impl<'a, T> TransportFutures<'a> for T
+where
+    T: transport::Transport<Address, Message> + 'a,
+{
+    type SendMessage = Pin<Box<dyn Future<Output = Result<()>> + 'a>>;
+    type RecvMessages = Pin<Box<dyn Future<Output = Result<Vec<Message>>> + 'a>>;
+    type RecvMessage = Pin<Box<dyn Future<Output = Result<Message>> + 'a>>;
+    type ConfirmationTimestamp = Pin<Box<dyn Future<Output = Result<Option<u64>>> + 'a>>;
+
+    fn send_message(&'a mut self, msg: &'a Message) -> Self::SendMessage {
+        transport::Transport::send_message(self, msg)
+    }
+    fn recv_messages(&'a mut self, link: &'a Address) -> Self::RecvMessages {
+        transport::Transport::recv_messages(self, link)
+    }
+    fn recv_message(&'a mut self, link: &'a Address) -> Self::RecvMessage {
+        transport::Transport::recv_message(self, link)
+    }
+    fn confirmation_timestamp(&'a mut self, msg: &'a Message) -> Self::ConfirmationTimestamp {
+        transport::Transport::confirmation_timestamp(self, msg)
+    }
+}
+
+/// `Send` transport, the futures of users sending and receiving messages over it are `Send` and
+/// can be spawned on multi-threaded executors.
+///
+/// ```ignore
+/// let transport = Sendable(new_shared_transport(client));
+/// let mut author = Author::new(seed, ChannelType::SingleBranch, transport.clone());
+/// tokio::spawn(async move { author.send_announce().await });
+/// ```
+#[cfg(feature = "async-send")]
+#[derive(Clone)]
+pub struct Sendable<T>(pub T);
+
+#[cfg(feature = "async-send")]
+impl<'a, T> TransportFutures<'a> for Sendable<T>
+where
+    T: transport::SendTransport<Address, Message> + 'a,
+{
+    type SendMessage = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+    type RecvMessages = Pin<Box<dyn Future<Output = Result<Vec<Message>>> + Send + 'a>>;
+    type RecvMessage = Pin<Box<dyn Future<Output = Result<Message>> + Send + 'a>>;
+    type ConfirmationTimestamp = Pin<Box<dyn Future<Output = Result<Option<u64>>> + Send + 'a>>;
+
+    fn send_message(&'a mut self, msg: &'a Message) -> Self::SendMessage {
+        transport::SendTransport::send_message(&mut self.0, msg)
+    }
+    fn recv_messages(&'a mut self, link: &'a Address) -> Self::RecvMessages {
+        transport::SendTransport::recv_messages(&mut self.0, link)
+    }
+    fn recv_message(&'a mut self, link: &'a Address) -> Self::RecvMessage {
+        transport::SendTransport::recv_message(&mut self.0, link)
+    }
+    fn confirmation_timestamp(&'a mut self, msg: &'a Message) -> Self::ConfirmationTimestamp {
+        transport::SendTransport::confirmation_timestamp(&mut self.0, msg)
+    }
+}
+
+#[cfg(feature = "async-send")]
+impl<T: TransportSubscribe<Address>> TransportSubscribe<Address> for Sendable<T> {
+    fn subscribe(&mut self, links: Vec<Address>) -> Result<transport::Subscription<Address>> {
+        self.0.subscribe(links)
+    }
+}
+
 mod msginfo;
 pub use msginfo::MsgInfo;
 
@@ -94,7 +233,9 @@ pub use msginfo::MsgInfo;
 /// Message body returned as part of handle message routine.
 pub enum MessageContent {
     Announce,
-    Keyload,
+    Keyload {
+        recipients: Vec<Identifier>,
+    },
     SignedPacket {
         pk: PublicKey,
         public_payload: Bytes,
@@ -115,8 +256,8 @@ impl MessageContent {
         Self::Announce
     }
 
-    pub fn new_keyload() -> Self {
-        Self::Keyload
+    pub fn new_keyload(recipients: Vec<Identifier>) -> Self {
+        Self::Keyload { recipients }
     }
 
     pub fn new_signed_packet(pk: PublicKey, public_payload: Bytes, masked_payload: Bytes) -> Self {
@@ -134,21 +275,135 @@ impl MessageContent {
         }
     }
 
+    pub fn new_unsubscribe() -> Self {
+        Self::Unsubscribe
+    }
+
     pub fn unreadable() -> Self {
         Self::Unreadable
     }
 }
 
+/// Header data of an unwrapped message.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct MessageMetadata {
+    /// Identifier of the publisher of the message.
+    pub sender: Identifier,
+    /// Sequence number of the message.
+    pub seq_num: u64,
+    /// Content type of the message (`SIGNED_PACKET`, `KEYLOAD`, ...).
+    pub content_type: u8,
+    /// Number of frames the message has been split into.
+    pub frame_count: u32,
+    /// Timestamp the transport layer returned the message with (`TangleMessage::timestamp`), in
+    /// milliseconds since the Unix epoch, 0 if the transport does not provide one.
+    pub timestamp: u64,
+    /// Timestamp of the milestone confirming the message in milliseconds since the Unix epoch,
+    /// looked up with `Transport::confirmation_timestamp`. `None` while the message is not
+    /// confirmed and with transports other than a node client.
+    pub milestone_timestamp: Option<u64>,
+    /// Publish time claimed by the publisher in milliseconds since the Unix epoch, authenticated
+    /// along with the packet content. `None` for other messages and untimestamped packets.
+    pub published_at: Option<u64>,
+}
+
+impl MessageMetadata {
+    pub fn new(header: &message::HDF<Address>, timestamp: u64, milestone_timestamp: Option<u64>) -> Self {
+        Self {
+            sender: header.sender_id,
+            seq_num: header.seq_num.0,
+            content_type: header.content_type,
+            frame_count: header.payload_frame_count,
+            timestamp,
+            milestone_timestamp,
+            published_at: None,
+        }
+    }
+}
+
+/// Unwrapped message body: message content along with header data.
+pub struct UnwrappedBody {
+    /// Header data of the message, `None` for an `Unreadable` message: its header could not be
+    /// authenticated.
+    pub metadata: Option<MessageMetadata>,
+    pub content: MessageContent,
+}
+
+impl UnwrappedBody {
+    pub fn new(metadata: MessageMetadata, content: MessageContent) -> Self {
+        Self {
+            metadata: Some(metadata),
+            content,
+        }
+    }
+
+    pub fn unreadable() -> Self {
+        Self {
+            metadata: None,
+            content: MessageContent::unreadable(),
+        }
+    }
+}
+
 /// Generic unwrapped message type containing possible message contents
-pub type UnwrappedMessage = message::GenericMessage<Address, MessageContent>;
+pub type UnwrappedMessage = message::GenericMessage<Address, UnwrappedBody>;
 
 /// Generic binary message type for sequence handling
 pub type BinaryMessage = message::GenericMessage<Address, BinaryBody<DefaultF>>;
 
+/// Kind of failure to fetch the next message of a publisher.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FetchErrorKind {
+    /// Messages at the expected link could not be retrieved from the transport.
+    Transport,
+    /// Messages were found at the expected link, but none of them could be parsed and authenticated,
+    /// or the packet was rejected by the freshness policy.
+    Unwrap,
+}
+
+/// Failure to fetch the next message of a publisher.
+#[derive(Debug)]
+pub struct FetchError {
+    /// Identifier of the publisher.
+    pub id: Identifier,
+    /// Link the next message of the publisher was expected at.
+    pub link: Address,
+    /// Kind of failure.
+    pub kind: FetchErrorKind,
+    /// Underlying transport or unwrap error.
+    pub error: Error,
+}
+
+/// Result of fetching the next message of a publisher.
+pub type FetchResult = core::result::Result<UnwrappedMessage, FetchError>;
+
+mod freshness;
+/// Requirements on the authenticated publish timestamps of received packets.
+pub use freshness::FreshnessPolicy;
+
+mod outbox;
+/// Messages committed while offline, waiting to be published.
+pub use outbox::Outbox;
+
+mod state_store;
+/// State store appending the updates to an encrypted file.
+#[cfg(feature = "std")]
+pub use state_store::FileStateStore;
+/// Persistence of the user state by incremental updates.
+pub use state_store::{
+    ChannelState,
+    StateStore,
+    StateUpdate,
+};
+
 mod user;
 /// User object storing the Auth/Sub implementation as well as the transport instance
 pub use user::User;
 
+mod messages;
+/// Iterator (or stream with the `async` feature) over new messages.
+pub use messages::Messages;
+
 mod author;
 /// Tangle-specific Channel Author type.
 pub use author::Author;
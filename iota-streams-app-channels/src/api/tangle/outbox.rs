@@ -0,0 +1,108 @@
+use iota_streams_app::{
+    message::HasLink as _,
+    transport::tangle::{
+        APPINST_SIZE,
+        MSGID_SIZE,
+    },
+};
+use iota_streams_core::{
+    err,
+    prelude::{
+        Vec,
+        VecDeque,
+    },
+    Errors::OutboxRecoveryFailure,
+    Result,
+};
+
+use super::{
+    Address,
+    BinaryMessage,
+    Message,
+};
+
+/// Size of the link, timestamp and body length preceding the body of a serialized message.
+const ENTRY_HEADER_SIZE: usize = APPINST_SIZE + MSGID_SIZE + 8 + 4;
+
+/// Messages committed by the user but not published yet, in publishing order.
+///
+/// Messages sent while the user is offline are queued here, see `User::set_offline`. The outbox
+/// is exported along with the user state.
+#[derive(Clone, Default)]
+pub struct Outbox {
+    msgs: VecDeque<Message>,
+}
+
+impl Outbox {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of queued messages.
+    pub fn len(&self) -> usize {
+        self.msgs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.msgs.is_empty()
+    }
+
+    /// Oldest queued message, the next one to publish.
+    pub fn front(&self) -> Option<&Message> {
+        self.msgs.front()
+    }
+
+    /// Queued messages in publishing order.
+    pub fn iter(&self) -> impl Iterator<Item = &Message> {
+        self.msgs.iter()
+    }
+
+    pub(crate) fn push(&mut self, msg: Message) {
+        self.msgs.push_back(msg);
+    }
+
+    pub(crate) fn pop_front(&mut self) -> Option<Message> {
+        self.msgs.pop_front()
+    }
+
+    /// Serialize the queued messages: for each of them its link, big-endian timestamp, big-endian
+    /// body length and body. Like on the Tangle, the previous message link is not kept.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for msg in &self.msgs {
+            let body = &msg.binary.body.bytes;
+            bytes.extend_from_slice(&msg.binary.link.to_bytes());
+            bytes.extend_from_slice(&msg.timestamp.to_be_bytes());
+            bytes.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(body);
+        }
+        bytes
+    }
+
+    /// Recover an outbox serialized with `to_bytes`.
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self> {
+        let mut msgs = VecDeque::new();
+        while !bytes.is_empty() {
+            if bytes.len() < ENTRY_HEADER_SIZE {
+                return err!(OutboxRecoveryFailure(ENTRY_HEADER_SIZE, bytes.len()));
+            }
+            let (link, rest) = bytes.split_at(APPINST_SIZE + MSGID_SIZE);
+            let (timestamp, rest) = rest.split_at(8);
+            let (body_len, rest) = rest.split_at(4);
+            let mut timestamp_bytes = [0_u8; 8];
+            timestamp_bytes.copy_from_slice(timestamp);
+            let mut body_len_bytes = [0_u8; 4];
+            body_len_bytes.copy_from_slice(body_len);
+            let body_len = u32::from_be_bytes(body_len_bytes) as usize;
+            if rest.len() < body_len {
+                return err!(OutboxRecoveryFailure(body_len, rest.len()));
+            }
+            let (body, rest) = rest.split_at(body_len);
+
+            let binary = BinaryMessage::new(Address::from_bytes(link)?, Address::default(), body.to_vec().into());
+            msgs.push_back(Message::with_timestamp(binary, u64::from_be_bytes(timestamp_bytes)));
+            bytes = rest;
+        }
+        Ok(Self { msgs })
+    }
+}
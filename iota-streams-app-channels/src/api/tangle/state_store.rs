@@ -0,0 +1,949 @@
+//! Incremental persistence of the user state.
+//!
+//! Instead of exporting the whole user state after every operation, the changes made by each
+//! operation are passed to a [`StateStore`] as a batch of [`StateUpdate`]s: messages committed to
+//! the link store, advanced cursors, stored pre-shared keys, channel level state and the outbox.
+//! Replaying the saved updates in order on a user created with the same seed restores its state.
+
+use iota_streams_app::{
+    identifier::Identifier,
+    message::{
+        ContentSizeof,
+        ContentUnwrap,
+        ContentUnwrapNew,
+        ContentWrap,
+        LinkGenerator as _,
+    },
+};
+use iota_streams_core::{
+    err,
+    prelude::{
+        hex,
+        Box,
+        HashMap,
+        HashSet,
+        Vec,
+    },
+    psk::{
+        self,
+        Psk,
+    },
+    sponge::prp::{
+        Inner,
+        PRP,
+    },
+    Errors::{
+        StateStoreSeedMismatch,
+        StateUpdateRecoveryFailure,
+    },
+    Result,
+};
+use iota_streams_ddml::{
+    command::*,
+    io,
+    link_store::LinkStore as _,
+    types::*,
+};
+
+use super::{
+    user::UserImp,
+    Address,
+    ChannelMetadata,
+    Cursor,
+    DefaultF,
+    MsgId,
+    MsgInfo,
+    Outbox,
+    PublicKey,
+    SeqState,
+};
+use crate::api::{
+    key_store::KeyStore,
+    user::{
+        ChannelDescription,
+        ChannelIdentity,
+    },
+};
+
+#[cfg(feature = "std")]
+pub use file::FileStateStore;
+
+// The key store implements `KeyStore` for any PRP, name the one the user is instantiated with.
+fn keys(user: &UserImp) -> &impl KeyStore<SeqState, DefaultF> {
+    &user.key_store
+}
+
+fn keys_mut(user: &mut UserImp) -> &mut impl KeyStore<SeqState, DefaultF> {
+    &mut user.key_store
+}
+
+/// Size of the spongos state of a committed message.
+type SpongosSize = <DefaultF as PRP>::CapacitySize;
+
+const LINK_INSERTED: u8 = 0;
+const LINK_REMOVED: u8 = 1;
+const CURSOR_STORED: u8 = 2;
+const PSK_STORED: u8 = 3;
+const KEY_REMOVED: u8 = 4;
+const CHANNEL: u8 = 5;
+const OUTBOX: u8 = 6;
+
+/// Persistent backend of the user state receiving its changes incrementally, see
+/// `User::set_state_store`.
+pub trait StateStore: Send {
+    /// Persist the changes of the user state made by one or more operations, in order. Either all
+    /// of them or none should be persisted.
+    fn save(&mut self, updates: &[StateUpdate]) -> Result<()>;
+
+    /// Load all the changes persisted so far, in order.
+    fn load(&mut self) -> Result<Vec<StateUpdate>>;
+
+    /// Whether the changes persisted so far should be replaced by a snapshot of the whole state,
+    /// eg. once replaying them costs more than loading the state. Never by default.
+    fn wants_snapshot(&self) -> bool {
+        false
+    }
+
+    /// Replace all the changes persisted so far by `updates`, the whole current state. Either the
+    /// old changes or the snapshot should be persisted. Appended to the changes by default.
+    fn snapshot(&mut self, updates: &[StateUpdate]) -> Result<()> {
+        self.save(updates)
+    }
+}
+
+/// Change of the user state.
+///
+/// Updates carry secrets (spongos states, pre-shared keys), stores are expected to
+/// encrypt them.
+#[derive(Clone)]
+pub enum StateUpdate {
+    /// Spongos state and type of a message committed to the link store.
+    LinkInserted(MsgId, Inner<DefaultF>, MsgInfo),
+    /// Message removed from the link store.
+    LinkRemoved(MsgId),
+    /// Sequencing cursor of a publisher inserted or advanced.
+    CursorStored(Identifier, SeqState),
+    /// Pre-shared key stored.
+    PskStored(Identifier, Psk),
+    /// Publisher or pre-shared key removed from the key store.
+    KeyRemoved(Identifier),
+    /// Channel level state changed.
+    Channel(Box<ChannelState>),
+    /// Messages waiting to be published changed.
+    Outbox(Outbox),
+}
+
+/// Channel level part of the user state, saved as a whole whenever any of it changes.
+///
+/// The signature key pair, message encoding and payload length are not part of it, they are
+/// derived from the seed and constants when the user is created. The public key is kept to detect
+/// a state restored by a user created with another seed.
+#[derive(Clone, Default)]
+pub struct ChannelState {
+    /// Ed25519 public key of the user.
+    pub public_key: PublicKey,
+    /// Link to the channel announcement.
+    pub appinst: Option<Address>,
+    /// Author's Ed25519 public key.
+    pub author_sig_pk: Option<PublicKey>,
+    /// Whether the Author's public key was pinned rather than received with the announcement.
+    pub author_sig_pk_pinned: bool,
+    /// Channel type flags.
+    pub flags: u8,
+    /// Whether the user sends messages under its pre-shared key.
+    pub use_psk: bool,
+    /// Anchor message of a single depth channel.
+    pub anchor: Option<Cursor<Address>>,
+    /// Channel metadata published in or received with the announcement.
+    pub channel_metadata: Option<ChannelMetadata>,
+}
+
+impl ChannelState {
+    fn of(user: &UserImp) -> Self {
+        Self {
+            public_key: user.sig_kp.public,
+            appinst: user.appinst.clone(),
+            author_sig_pk: user.author_sig_pk,
+            author_sig_pk_pinned: user.author_sig_pk_pinned,
+            flags: user.flags,
+            use_psk: user.use_psk,
+            anchor: user.anchor.clone(),
+            channel_metadata: user.channel_metadata.clone(),
+        }
+    }
+
+    /// Check that the state was saved by a user created with the same seed as `user`.
+    pub(crate) fn check_owner(&self, user: &UserImp) -> Result<()> {
+        if self.public_key != user.sig_kp.public {
+            return err!(StateStoreSeedMismatch(
+                hex::encode(user.sig_kp.public.as_bytes()),
+                hex::encode(self.public_key.as_bytes())
+            ));
+        }
+        Ok(())
+    }
+
+    fn identity(&self) -> ChannelIdentity<Address> {
+        ChannelIdentity {
+            appinst: self.appinst.clone(),
+            author_sig_pk: self.author_sig_pk,
+            author_sig_pk_pinned: self.author_sig_pk_pinned,
+        }
+    }
+
+    fn description(&self) -> ChannelDescription<Address> {
+        ChannelDescription {
+            anchor: self.anchor.clone(),
+            channel_metadata: self.channel_metadata.clone(),
+        }
+    }
+
+    fn restore(&self, user: &mut UserImp) -> Result<()> {
+        self.check_owner(user)?;
+        if let Some(appinst) = &self.appinst {
+            user.link_gen.reset(appinst.clone());
+        }
+        user.appinst = self.appinst.clone();
+        user.author_sig_pk = self.author_sig_pk;
+        user.author_sig_pk_pinned = self.author_sig_pk_pinned;
+        user.flags = self.flags;
+        user.use_psk = self.use_psk;
+        user.anchor = self.anchor.clone();
+        user.channel_metadata = self.channel_metadata.clone();
+        Ok(())
+    }
+}
+
+impl PartialEq for ChannelState {
+    fn eq(&self, other: &Self) -> bool {
+        let anchor = |state: &Self| {
+            state
+                .anchor
+                .as_ref()
+                .map(|anchor| (anchor.link.clone(), anchor.branch_no, anchor.seq_no))
+        };
+        self.public_key == other.public_key
+            && self.appinst == other.appinst
+            && self.author_sig_pk == other.author_sig_pk
+            && self.author_sig_pk_pinned == other.author_sig_pk_pinned
+            && self.flags == other.flags
+            && self.use_psk == other.use_psk
+            && anchor(self) == anchor(other)
+            && self.channel_metadata == other.channel_metadata
+    }
+}
+
+fn same_cursor(a: &SeqState, b: &SeqState) -> bool {
+    a.link == b.link && a.branch_no == b.branch_no && a.seq_no == b.seq_no
+}
+
+impl StateUpdate {
+    fn tag(&self) -> u8 {
+        match self {
+            StateUpdate::LinkInserted(..) => LINK_INSERTED,
+            StateUpdate::LinkRemoved(_) => LINK_REMOVED,
+            StateUpdate::CursorStored(..) => CURSOR_STORED,
+            StateUpdate::PskStored(..) => PSK_STORED,
+            StateUpdate::KeyRemoved(_) => KEY_REMOVED,
+            StateUpdate::Channel(_) => CHANNEL,
+            StateUpdate::Outbox(_) => OUTBOX,
+        }
+    }
+
+    /// Apply the change to the user state and outbox.
+    pub(crate) fn restore(&self, user: &mut UserImp, outbox: &mut Outbox) -> Result<()> {
+        match self {
+            StateUpdate::LinkInserted(link, spongos, info) => {
+                user.link_store.borrow_mut().insert(link, spongos.clone(), *info)?;
+            }
+            StateUpdate::LinkRemoved(link) => user.link_store.borrow_mut().erase(link),
+            StateUpdate::CursorStored(id, cursor) => {
+                let key_store = keys_mut(user);
+                match key_store.get_mut(id) {
+                    Some(stored) => *stored = cursor.clone(),
+                    None => key_store.insert_cursor(*id, cursor.clone())?,
+                }
+            }
+            StateUpdate::PskStored(id, psk) => {
+                let key_store = keys_mut(user);
+                let cursor = key_store.get(id).cloned().unwrap_or_default();
+                key_store.insert_psk(*id, Some(*psk), cursor)?;
+            }
+            StateUpdate::KeyRemoved(id) => {
+                keys_mut(user).remove(id);
+            }
+            StateUpdate::Channel(channel) => channel.restore(user)?,
+            StateUpdate::Outbox(saved) => *outbox = saved.clone(),
+        }
+        Ok(())
+    }
+}
+
+/// User state last saved to the state store, the changes of the next save are computed against it.
+///
+/// Messages are not kept: the link store journals the messages committed since the last save.
+#[derive(Default)]
+pub(crate) struct SavedState {
+    channel: ChannelState,
+    keys: HashMap<Identifier, (SeqState, Option<Psk>)>,
+    outbox: Vec<u8>,
+}
+
+impl SavedState {
+    /// Changes of the user state and outbox since they were saved. With `all_links`, every message
+    /// in the link store is included instead of the journaled ones.
+    pub(crate) fn changes(&self, user: &UserImp, outbox: &Outbox, all_links: bool) -> Result<Vec<StateUpdate>> {
+        let mut updates = Vec::new();
+
+        let link_store = user.link_store.borrow();
+        if all_links {
+            for (link, (spongos, info)) in link_store.iter() {
+                updates.push(StateUpdate::LinkInserted(link.clone(), spongos.clone(), *info));
+            }
+        } else {
+            let mut seen = HashSet::new();
+            for link in link_store.journal() {
+                if !seen.insert(link) {
+                    continue;
+                }
+                match link_store.lookup(link) {
+                    Ok((spongos, info)) => {
+                        updates.push(StateUpdate::LinkInserted(link.clone(), spongos.to_inner()?, info))
+                    }
+                    Err(_) => updates.push(StateUpdate::LinkRemoved(link.clone())),
+                }
+            }
+        }
+
+        let channel = ChannelState::of(user);
+        if channel != self.channel {
+            updates.push(StateUpdate::Channel(Box::new(channel)));
+        }
+
+        // The key store only holds one entry per publisher, unlike the link store it is cheap to
+        // compare as a whole.
+        let key_store = keys(user);
+        for id in self.keys.keys() {
+            if !key_store.contains(id) {
+                updates.push(StateUpdate::KeyRemoved(*id));
+            }
+        }
+        for (id, cursor) in key_store.iter() {
+            let psk = key_store.get_psk(id);
+            match self.keys.get(id) {
+                Some((saved_cursor, saved_psk)) => {
+                    if psk.is_none() && saved_psk.is_some() {
+                        updates.push(StateUpdate::KeyRemoved(*id));
+                        updates.push(StateUpdate::CursorStored(*id, cursor.clone()));
+                        continue;
+                    }
+                    if let Some(psk) = psk.filter(|psk| Some(*psk) != *saved_psk) {
+                        updates.push(StateUpdate::PskStored(*id, psk));
+                    }
+                    if !same_cursor(cursor, saved_cursor) {
+                        updates.push(StateUpdate::CursorStored(*id, cursor.clone()));
+                    }
+                }
+                None => {
+                    if let Some(psk) = psk {
+                        updates.push(StateUpdate::PskStored(*id, psk));
+                    }
+                    updates.push(StateUpdate::CursorStored(*id, cursor.clone()));
+                }
+            }
+        }
+
+        if outbox.to_bytes() != self.outbox {
+            updates.push(StateUpdate::Outbox(outbox.clone()));
+        }
+
+        Ok(updates)
+    }
+
+    /// Take a saved or restored change into account.
+    pub(crate) fn record(&mut self, update: &StateUpdate) {
+        match update {
+            StateUpdate::LinkInserted(..) | StateUpdate::LinkRemoved(_) => {}
+            StateUpdate::CursorStored(id, cursor) => {
+                self.keys.entry(*id).or_insert_with(|| (SeqState::default(), None)).0 = cursor.clone();
+            }
+            StateUpdate::PskStored(id, psk) => {
+                self.keys.entry(*id).or_insert_with(|| (SeqState::default(), None)).1 = Some(*psk);
+            }
+            StateUpdate::KeyRemoved(id) => {
+                self.keys.remove(id);
+            }
+            StateUpdate::Channel(channel) => self.channel = (**channel).clone(),
+            StateUpdate::Outbox(outbox) => self.outbox = outbox.to_bytes(),
+        }
+    }
+}
+
+impl ContentSizeof<DefaultF> for ChannelState {
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<DefaultF>) -> Result<&'c mut sizeof::Context<DefaultF>> {
+        ctx.absorb(&self.public_key)?;
+        self.identity().sizeof(ctx)?;
+        ctx.absorb(Uint8(self.flags))?
+            .absorb(Uint8(if self.use_psk { 1 } else { 0 }))?;
+        self.description().sizeof(ctx)?;
+        Ok(ctx)
+    }
+}
+
+impl<Store> ContentWrap<DefaultF, Store> for ChannelState {
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        store: &Store,
+        ctx: &'c mut wrap::Context<DefaultF, OS>,
+    ) -> Result<&'c mut wrap::Context<DefaultF, OS>> {
+        ctx.absorb(&self.public_key)?;
+        self.identity().wrap(store, ctx)?;
+        ctx.absorb(Uint8(self.flags))?
+            .absorb(Uint8(if self.use_psk { 1 } else { 0 }))?;
+        self.description().wrap(store, ctx)?;
+        Ok(ctx)
+    }
+}
+
+impl<Store> ContentUnwrap<DefaultF, Store> for ChannelState {
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        store: &Store,
+        ctx: &'c mut unwrap::Context<DefaultF, IS>,
+    ) -> Result<&'c mut unwrap::Context<DefaultF, IS>> {
+        ctx.absorb(&mut self.public_key)?;
+
+        let mut identity = ChannelIdentity::default();
+        identity.unwrap(store, ctx)?;
+        self.appinst = identity.appinst;
+        self.author_sig_pk = identity.author_sig_pk;
+        self.author_sig_pk_pinned = identity.author_sig_pk_pinned;
+
+        let mut flags = Uint8(0);
+        let mut use_psk = Uint8(0);
+        ctx.absorb(&mut flags)?.absorb(&mut use_psk)?;
+        self.flags = flags.0;
+        self.use_psk = use_psk.0 != 0;
+
+        let mut description = ChannelDescription::default();
+        description.unwrap(store, ctx)?;
+        self.anchor = description.anchor;
+        self.channel_metadata = description.channel_metadata;
+
+        Ok(ctx)
+    }
+}
+
+impl ContentSizeof<DefaultF> for StateUpdate {
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<DefaultF>) -> Result<&'c mut sizeof::Context<DefaultF>> {
+        ctx.absorb(Uint8(self.tag()))?;
+        match self {
+            StateUpdate::LinkInserted(link, spongos, info) => {
+                ctx.absorb(<&Fallback<MsgId>>::from(link))?
+                    .mask(<&NBytes<SpongosSize>>::from(spongos.arr()))?
+                    .absorb(<&Fallback<MsgInfo>>::from(info))?;
+            }
+            StateUpdate::LinkRemoved(link) => {
+                ctx.absorb(<&Fallback<MsgId>>::from(link))?;
+            }
+            StateUpdate::CursorStored(id, cursor) => {
+                id.sizeof(ctx)?
+                    .absorb(<&Fallback<MsgId>>::from(&cursor.link))?
+                    .absorb(Uint32(cursor.branch_no))?
+                    .absorb(Uint32(cursor.seq_no))?;
+            }
+            StateUpdate::PskStored(id, psk) => {
+                id.sizeof(ctx)?.mask(<&NBytes<psk::PskSize>>::from(psk))?;
+            }
+            StateUpdate::KeyRemoved(id) => {
+                id.sizeof(ctx)?;
+            }
+            StateUpdate::Channel(channel) => {
+                channel.sizeof(ctx)?;
+            }
+            StateUpdate::Outbox(outbox) => {
+                ctx.mask(&Bytes(outbox.to_bytes()))?;
+            }
+        }
+        Ok(ctx)
+    }
+}
+
+impl<Store> ContentWrap<DefaultF, Store> for StateUpdate {
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        store: &Store,
+        ctx: &'c mut wrap::Context<DefaultF, OS>,
+    ) -> Result<&'c mut wrap::Context<DefaultF, OS>> {
+        ctx.absorb(Uint8(self.tag()))?;
+        match self {
+            StateUpdate::LinkInserted(link, spongos, info) => {
+                ctx.absorb(<&Fallback<MsgId>>::from(link))?
+                    .mask(<&NBytes<SpongosSize>>::from(spongos.arr()))?
+                    .absorb(<&Fallback<MsgInfo>>::from(info))?;
+            }
+            StateUpdate::LinkRemoved(link) => {
+                ctx.absorb(<&Fallback<MsgId>>::from(link))?;
+            }
+            StateUpdate::CursorStored(id, cursor) => {
+                id.wrap(store, ctx)?
+                    .absorb(<&Fallback<MsgId>>::from(&cursor.link))?
+                    .absorb(Uint32(cursor.branch_no))?
+                    .absorb(Uint32(cursor.seq_no))?;
+            }
+            StateUpdate::PskStored(id, psk) => {
+                id.wrap(store, ctx)?.mask(<&NBytes<psk::PskSize>>::from(psk))?;
+            }
+            StateUpdate::KeyRemoved(id) => {
+                id.wrap(store, ctx)?;
+            }
+            StateUpdate::Channel(channel) => {
+                channel.wrap(store, ctx)?;
+            }
+            StateUpdate::Outbox(outbox) => {
+                ctx.mask(&Bytes(outbox.to_bytes()))?;
+            }
+        }
+        Ok(ctx)
+    }
+}
+
+impl<Store> ContentUnwrapNew<DefaultF, Store> for StateUpdate {
+    fn unwrap_new<'c, IS: io::IStream>(
+        store: &Store,
+        ctx: &'c mut unwrap::Context<DefaultF, IS>,
+    ) -> Result<(Self, &'c mut unwrap::Context<DefaultF, IS>)> {
+        let mut tag = Uint8(0);
+        ctx.absorb(&mut tag)?;
+        let update = match tag.0 {
+            LINK_INSERTED => {
+                let mut link = MsgId::default();
+                let mut spongos = NBytes::<SpongosSize>::default();
+                let mut info = MsgInfo::default();
+                ctx.absorb(<&mut Fallback<MsgId>>::from(&mut link))?
+                    .mask(&mut spongos)?
+                    .absorb(<&mut Fallback<MsgInfo>>::from(&mut info))?;
+                let spongos: GenericArray<u8, SpongosSize> = spongos.into();
+                StateUpdate::LinkInserted(link, Inner::from(spongos), info)
+            }
+            LINK_REMOVED => {
+                let mut link = MsgId::default();
+                ctx.absorb(<&mut Fallback<MsgId>>::from(&mut link))?;
+                StateUpdate::LinkRemoved(link)
+            }
+            CURSOR_STORED => {
+                let (id, ctx) = Identifier::unwrap_new(store, ctx)?;
+                let mut link = MsgId::default();
+                let mut branch_no = Uint32(0);
+                let mut seq_no = Uint32(0);
+                ctx.absorb(<&mut Fallback<MsgId>>::from(&mut link))?
+                    .absorb(&mut branch_no)?
+                    .absorb(&mut seq_no)?;
+                StateUpdate::CursorStored(id, Cursor::new_at(link, branch_no.0, seq_no.0))
+            }
+            PSK_STORED => {
+                let (id, ctx) = Identifier::unwrap_new(store, ctx)?;
+                let mut psk = NBytes::<psk::PskSize>::default();
+                ctx.mask(&mut psk)?;
+                StateUpdate::PskStored(id, psk.into())
+            }
+            KEY_REMOVED => {
+                let (id, _) = Identifier::unwrap_new(store, ctx)?;
+                StateUpdate::KeyRemoved(id)
+            }
+            CHANNEL => {
+                let mut channel = ChannelState::default();
+                channel.unwrap(store, ctx)?;
+                StateUpdate::Channel(Box::new(channel))
+            }
+            OUTBOX => {
+                let mut outbox = Bytes::default();
+                ctx.mask(&mut outbox)?;
+                StateUpdate::Outbox(Outbox::from_bytes(&outbox.0)?)
+            }
+            tag => return err!(StateUpdateRecoveryFailure(tag)),
+        };
+        Ok((update, ctx))
+    }
+}
+
+#[cfg(feature = "std")]
+mod file {
+    use std::{
+        fs::{
+            self,
+            OpenOptions,
+        },
+        io::{
+            self,
+            Write,
+        },
+        path::{
+            Path,
+            PathBuf,
+        },
+    };
+
+    use iota_streams_app::message::{
+        ContentSizeof as _,
+        ContentUnwrapNew as _,
+        ContentWrap as _,
+    };
+    use iota_streams_core::{
+        err,
+        prelude::{
+            generic_array::GenericArray,
+            typenum::{
+                Unsigned as _,
+                U32,
+                U41,
+            },
+            ToString,
+            Vec,
+        },
+        prng::{
+            self,
+            KdfLimits,
+            KdfParams,
+        },
+        try_or,
+        wrapped_err,
+        Error,
+        Errors::{
+            InputStreamNotFullyConsumed,
+            KdfCostAboveLimit,
+            KdfCostOutOfBounds,
+            OutputStreamNotFullyConsumed,
+            StateStoreFileFailure,
+            StateStoreRecoveryFailure,
+            UserVersionRecoveryFailure,
+        },
+        Result,
+        WrappedError,
+    };
+    use iota_streams_ddml::{
+        command::*,
+        link_store::EmptyLinkStore,
+        types::*,
+    };
+
+    use super::{
+        DefaultF,
+        MsgId,
+        StateStore,
+        StateUpdate,
+    };
+
+    /// Version of the state file format.
+    const STATE_FILE_VERSION: u8 = 0;
+
+    /// Domain separator of the state file encryption key.
+    const STATE_FILE_KEY_DOMAIN: &str = "IOTA Streams Channels state store";
+
+    /// Size of the header: version, salt and KDF cost.
+    type HeaderSize = U41;
+    const HEADER_SIZE: usize = HeaderSize::USIZE;
+
+    /// Size of the MAC ending each record.
+    const MAC_SIZE: usize = 32;
+
+    /// Size of the length preceding each record.
+    const LENGTH_SIZE: usize = 4;
+
+    /// Number of records replaced by a snapshot by default.
+    const DEFAULT_SNAPSHOT_INTERVAL: usize = 1000;
+
+    fn io_err(path: &Path, e: io::Error) -> Error {
+        wrapped_err!(StateStoreFileFailure(path.display().to_string()), WrappedError(e))
+    }
+
+    fn be_u32(bytes: &[u8]) -> u32 {
+        let mut be = [0_u8; 4];
+        be.copy_from_slice(&bytes[..4]);
+        u32::from_be_bytes(be)
+    }
+
+    /// Split the records following the header. A truncated last record, left by a crash while it
+    /// was appended, is ignored.
+    fn split_records(mut bytes: &[u8]) -> Vec<&[u8]> {
+        let mut records = Vec::new();
+        while bytes.len() >= LENGTH_SIZE {
+            let len = be_u32(bytes) as usize;
+            if bytes.len() - LENGTH_SIZE < len {
+                break;
+            }
+            let (record, rest) = bytes[LENGTH_SIZE..].split_at(len);
+            records.push(record);
+            bytes = rest;
+        }
+        records
+    }
+
+    /// MAC ending a record, the next record is chained to it.
+    fn record_mac(record: &[u8]) -> Result<NBytes<U32>> {
+        try_or!(
+            record.len() >= MAC_SIZE,
+            StateStoreRecoveryFailure(MAC_SIZE, record.len())
+        )?;
+        Ok(NBytes(GenericArray::clone_from_slice(
+            &record[record.len() - MAC_SIZE..],
+        )))
+    }
+
+    /// Value a record is chained to: the header for the first record, the MAC of the previous
+    /// record otherwise.
+    enum Chain {
+        Header(NBytes<HeaderSize>),
+        Mac(NBytes<U32>),
+    }
+
+    /// State store appending the saved updates to a file.
+    ///
+    /// The file starts with a header holding the format version, a random salt and the cost of the
+    /// password key derivation, like exported user state. Each save appends one record: its
+    /// big-endian length followed by the updates encrypted and authenticated with the key derived
+    /// from the password. The file is synced after each save.
+    ///
+    /// Records are chained: the first one authenticates the header, each following one the MAC of
+    /// the record before it. Modified, reordered or removed records fail to load, except that
+    /// records dropped from the end of the file cannot be told apart from records never saved: the
+    /// state restored is then the one saved before them.
+    ///
+    /// Once the file holds as many records as the snapshot interval, it is replaced by a file holding
+    /// a single record with the whole state, chained to the header again. The new file is written
+    /// next to it and renamed over it, a crash leaves either the old or the new file.
+    pub struct FileStateStore {
+        path: PathBuf,
+        key: NBytes<U32>,
+        header: NBytes<HeaderSize>,
+        chain: Chain,
+        records: usize,
+        snapshot_interval: usize,
+    }
+
+    impl FileStateStore {
+        /// Open the state file at `path`, it is created if missing. The encryption key is derived
+        /// from `pwd` with default KDF cost.
+        pub fn open<P: AsRef<Path>>(path: P, pwd: &str) -> Result<Self> {
+            Self::open_with_kdf(path, pwd, &KdfParams::default())
+        }
+
+        /// Open the state file at `path`, it is created if missing. The KDF parameters only apply to a
+        /// new file, an existing file keeps the parameters stored in its header. Their cost must not
+        /// exceed the larger of the default [`KdfLimits`] and `kdf`.
+        pub fn open_with_kdf<P: AsRef<Path>>(path: P, pwd: &str, kdf: &KdfParams) -> Result<Self> {
+            let path = path.as_ref().to_path_buf();
+            let bytes = match fs::read(&path) {
+                Ok(bytes) => bytes,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+                Err(e) => return Err(io_err(&path, e)),
+            };
+
+            let (header, chain, records, kdf) = if bytes.is_empty() {
+                try_or!(kdf.is_valid(), KdfCostOutOfBounds(kdf.memory_kib, kdf.iterations))?;
+                let salt = prng::random_key();
+                let mut header = Vec::with_capacity(HEADER_SIZE);
+                header.push(STATE_FILE_VERSION);
+                header.extend_from_slice(&salt);
+                header.extend_from_slice(&kdf.memory_kib.to_be_bytes());
+                header.extend_from_slice(&kdf.iterations.to_be_bytes());
+                let mut file = fs::File::create(&path).map_err(|e| io_err(&path, e))?;
+                file.write_all(&header).map_err(|e| io_err(&path, e))?;
+                file.sync_all().map_err(|e| io_err(&path, e))?;
+                let header = NBytes::<HeaderSize>(GenericArray::clone_from_slice(&header));
+                (header, Chain::Header(header), 0, *kdf)
+            } else {
+                try_or!(
+                    bytes.len() >= HEADER_SIZE,
+                    StateStoreRecoveryFailure(HEADER_SIZE, bytes.len())
+                )?;
+                try_or!(
+                    bytes[0] == STATE_FILE_VERSION,
+                    UserVersionRecoveryFailure(STATE_FILE_VERSION, bytes[0])
+                )?;
+                let stored = KdfParams::new(be_u32(&bytes[33..]), be_u32(&bytes[37..]));
+                let limits = KdfLimits::default();
+                let limits = KdfLimits::new(
+                    limits.memory_kib.max(kdf.memory_kib),
+                    limits.iterations.max(kdf.iterations),
+                );
+                try_or!(
+                    stored.is_within(&limits),
+                    KdfCostAboveLimit(stored.memory_kib, stored.iterations)
+                )?;
+
+                // Drop a truncated last record, further records would be appended after it.
+                let records = split_records(&bytes[HEADER_SIZE..]);
+                let records_len: usize = records.iter().map(|record| LENGTH_SIZE + record.len()).sum();
+                if HEADER_SIZE + records_len < bytes.len() {
+                    let file = OpenOptions::new()
+                        .write(true)
+                        .open(&path)
+                        .map_err(|e| io_err(&path, e))?;
+                    file.set_len((HEADER_SIZE + records_len) as u64)
+                        .map_err(|e| io_err(&path, e))?;
+                    file.sync_all().map_err(|e| io_err(&path, e))?;
+                }
+                // The chain is verified when the records are loaded
+                let header = NBytes::<HeaderSize>(GenericArray::clone_from_slice(&bytes[..HEADER_SIZE]));
+                let chain = match records.last() {
+                    Some(record) => Chain::Mac(record_mac(record)?),
+                    None => Chain::Header(header),
+                };
+                (header, chain, records.len(), stored)
+            };
+
+            let key = NBytes::<U32>(
+                prng::from_password::<DefaultF>(STATE_FILE_KEY_DOMAIN, pwd, &header.as_slice()[1..33], &kdf)?
+                    .gen_arr("state store key"),
+            );
+            Ok(Self {
+                path,
+                key,
+                header,
+                chain,
+                records,
+                snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            })
+        }
+
+        /// Replace the records by a snapshot once the file holds `records` of them, `0` never does.
+        pub fn with_snapshot_interval(mut self, records: usize) -> Self {
+            self.snapshot_interval = records;
+            self
+        }
+
+        /// Path of the state file.
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        /// Record holding `updates` chained to `chain`: the length of the sealed updates followed
+        /// by them.
+        fn record(&self, updates: &[StateUpdate], chain: &Chain) -> Result<Vec<u8>> {
+            let sealed = self.seal(updates, chain)?;
+            let mut record = Vec::with_capacity(LENGTH_SIZE + sealed.len());
+            record.extend_from_slice(&(sealed.len() as u32).to_be_bytes());
+            record.extend_from_slice(&sealed);
+            Ok(record)
+        }
+
+        fn seal(&self, updates: &[StateUpdate], chain: &Chain) -> Result<Vec<u8>> {
+            let nonce = NBytes::<U32>(prng::random_key());
+            let store = EmptyLinkStore::<DefaultF, MsgId, ()>::default();
+
+            let buf_size = {
+                let mut ctx = sizeof::Context::<DefaultF>::new();
+                ctx.absorb(&nonce)?.absorb(External(&self.key))?;
+                match chain {
+                    Chain::Header(header) => ctx.absorb(External(header))?,
+                    Chain::Mac(mac) => ctx.absorb(External(mac))?,
+                };
+                ctx.absorb(Size(updates.len()))?
+                    .repeated(updates.iter(), |ctx, update| update.sizeof(ctx))?
+                    .commit()?
+                    .squeeze(Mac(32))?;
+                ctx.get_size()
+            };
+
+            let mut buf = vec![0; buf_size];
+            {
+                let mut ctx = wrap::Context::new(&mut buf[..]);
+                ctx.absorb(&nonce)?.absorb(External(&self.key))?;
+                match chain {
+                    Chain::Header(header) => ctx.absorb(External(header))?,
+                    Chain::Mac(mac) => ctx.absorb(External(mac))?,
+                };
+                ctx.absorb(Size(updates.len()))?
+                    .repeated(updates.iter(), |ctx, update| update.wrap(&store, ctx))?
+                    .commit()?
+                    .squeeze(Mac(32))?;
+                try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+            }
+            Ok(buf)
+        }
+
+        fn unseal(&self, record: &[u8], chain: &Chain) -> Result<Vec<StateUpdate>> {
+            let store = EmptyLinkStore::<DefaultF, MsgId, ()>::default();
+            let mut nonce = NBytes::<U32>::default();
+            let mut repeated_updates = Size(0);
+            let mut updates = Vec::new();
+
+            let mut ctx = unwrap::Context::new(record);
+            ctx.absorb(&mut nonce)?.absorb(External(&self.key))?;
+            match chain {
+                Chain::Header(header) => ctx.absorb(External(header))?,
+                Chain::Mac(mac) => ctx.absorb(External(mac))?,
+            };
+            ctx.absorb(&mut repeated_updates)?
+                .repeated(repeated_updates, |ctx| {
+                    let (update, ctx) = StateUpdate::unwrap_new(&store, ctx)?;
+                    updates.push(update);
+                    Ok(ctx)
+                })?
+                .commit()?
+                .squeeze(Mac(32))?;
+            try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+            Ok(updates)
+        }
+    }
+
+    impl StateStore for FileStateStore {
+        fn save(&mut self, updates: &[StateUpdate]) -> Result<()> {
+            if updates.is_empty() {
+                return Ok(());
+            }
+            let record = self.record(updates, &self.chain)?;
+
+            let mut file = OpenOptions::new()
+                .append(true)
+                .open(&self.path)
+                .map_err(|e| io_err(&self.path, e))?;
+            file.write_all(&record).map_err(|e| io_err(&self.path, e))?;
+            file.sync_data().map_err(|e| io_err(&self.path, e))?;
+            self.chain = Chain::Mac(record_mac(&record)?);
+            self.records += 1;
+            Ok(())
+        }
+
+        fn load(&mut self) -> Result<Vec<StateUpdate>> {
+            let bytes = fs::read(&self.path).map_err(|e| io_err(&self.path, e))?;
+            if bytes.len() < HEADER_SIZE {
+                return err!(StateStoreRecoveryFailure(HEADER_SIZE, bytes.len()));
+            }
+            let mut updates = Vec::new();
+            let mut chain = Chain::Header(self.header);
+            for record in split_records(&bytes[HEADER_SIZE..]) {
+                updates.extend(self.unseal(record, &chain)?);
+                chain = Chain::Mac(record_mac(record)?);
+            }
+            self.chain = chain;
+            Ok(updates)
+        }
+
+        fn wants_snapshot(&self) -> bool {
+            self.snapshot_interval != 0 && self.records >= self.snapshot_interval
+        }
+
+        fn snapshot(&mut self, updates: &[StateUpdate]) -> Result<()> {
+            let mut bytes = self.header.as_slice().to_vec();
+            let mut chain = Chain::Header(self.header);
+            if !updates.is_empty() {
+                let record = self.record(updates, &chain)?;
+                chain = Chain::Mac(record_mac(&record)?);
+                bytes.extend(record);
+            }
+
+            let mut tmp_path = self.path.clone().into_os_string();
+            tmp_path.push(".tmp");
+            let tmp_path = PathBuf::from(tmp_path);
+            let mut file = fs::File::create(&tmp_path).map_err(|e| io_err(&tmp_path, e))?;
+            file.write_all(&bytes).map_err(|e| io_err(&tmp_path, e))?;
+            file.sync_all().map_err(|e| io_err(&tmp_path, e))?;
+            fs::rename(&tmp_path, &self.path).map_err(|e| io_err(&self.path, e))?;
+            self.chain = chain;
+            self.records = if updates.is_empty() { 0 } else { 1 };
+            Ok(())
+        }
+    }
+}
@@ -16,6 +16,8 @@ use crate::api::tangle::{
 use iota_streams_app::identifier::Identifier;
 use iota_streams_core::{
     prelude::{
+        Arc,
+        Box,
         String,
         Vec,
     },
@@ -43,6 +45,20 @@ impl<Trans> Subscriber<Trans> {
         Self { user }
     }
 
+    /// Create a new Subscriber instance trusting only the given Author public key, generate new
+    /// Ed25519 key pair. Announcements signed with any other key are rejected, the key stays pinned
+    /// when the Subscriber unregisters.
+    ///
+    /// # Arguments
+    /// * `seed` - A string slice representing the seed of the user [Characters: A-Z, 9]
+    /// * `author_pk` - Expected ed25519 Public Key of the channel Author
+    /// * `transport` - Transport object used for sending and receiving
+    pub fn new_with_author_key(seed: &str, author_pk: PublicKey, transport: Trans) -> Result<Self> {
+        let mut user = User::new(seed, SingleBranch, transport);
+        user.user.pin_author_public_key(author_pk)?;
+        Ok(Self { user })
+    }
+
     /// Returns a clone of the transport object
     pub fn get_transport(&self) -> &Trans {
         self.user.get_transport()
@@ -53,7 +69,8 @@ impl<Trans> Subscriber<Trans> {
         self.user.is_registered()
     }
 
-    /// Clears inner state except for own keys and link store.
+    /// Clears inner state except for own keys, link store and a pinned Author public key. The change
+    /// is saved to the state store with the next operation.
     pub fn unregister(&mut self) {
         self.user.unregister()
     }
@@ -68,6 +85,104 @@ impl<Trans> Subscriber<Trans> {
         self.user.author_public_key()
     }
 
+    /// Signed channel metadata received with the announcement, `None` before the announcement is
+    /// received or if the Author has published none.
+    pub fn channel_metadata(&self) -> Option<&ChannelMetadata> {
+        self.user.channel_metadata()
+    }
+
+    /// Whether the Author has announced the channel with private links, links following a keyload
+    /// are then only found by its recipients.
+    pub fn has_private_links(&self) -> bool {
+        self.user.has_private_links()
+    }
+
+    /// Replace the system clock as source of the timestamps of sent messages.
+    ///
+    ///   # Arguments
+    ///   * `clock` - Clock shared with the application, eg. a [`ManualClock`]
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.user.set_clock(clock)
+    }
+
+    /// Include the current time of the clock as authenticated publish timestamp in sent signed
+    /// and tagged packets.
+    ///
+    ///   # Arguments
+    ///   * `enabled` - Whether packets are timestamped, they are not by default
+    pub fn set_packet_timestamps(&mut self, enabled: bool) {
+        self.user.set_packet_timestamps(enabled)
+    }
+
+    /// Set the requirements on the publish timestamps of received signed and tagged packets,
+    /// packets failing them are not returned.
+    ///
+    ///   # Arguments
+    ///   * `freshness` - Required timestamp, maximum clock skew and maximum age of packets
+    pub fn set_freshness_policy(&mut self, freshness: FreshnessPolicy) {
+        self.user.set_freshness_policy(freshness)
+    }
+
+    /// Return boolean representing whether a message whose send failed is staged, no other message
+    /// can be sent until it is retried or discarded.
+    pub fn is_staged(&self) -> bool {
+        self.user.is_staged()
+    }
+
+    /// Drop the message whose send failed without committing it, the next message is sent at its
+    /// link. Only discard a message none of whose frames has been published. Returns whether a
+    /// message was staged.
+    pub fn discard_staged(&mut self) -> bool {
+        self.user.discard_staged()
+    }
+
+    /// Queue sent messages in the outbox instead of publishing them, eg. while the transport is
+    /// unreachable. Messages are committed as if they were published.
+    ///
+    ///   # Arguments
+    ///   * `offline` - Whether messages are queued, once back online they are published with `flush_outbox`
+    pub fn set_offline(&mut self, offline: bool) {
+        self.user.set_offline(offline)
+    }
+
+    /// Returns a boolean representing whether sent messages are queued in the outbox
+    pub fn is_offline(&self) -> bool {
+        self.user.is_offline()
+    }
+
+    /// Committed messages waiting to be published, exported and imported along with the user
+    /// state.
+    pub fn outbox(&self) -> &Outbox {
+        self.user.outbox()
+    }
+
+    /// Replace the outbox, eg. with one serialized with `Outbox::to_bytes`.
+    ///
+    ///   # Arguments
+    ///   * `outbox` - Messages waiting to be published
+    pub fn set_outbox(&mut self, outbox: Outbox) {
+        self.user.set_outbox(outbox)
+    }
+
+    /// Replace the blocking sleep between the retries of `flush_outbox`.
+    ///
+    ///   # Arguments
+    ///   * `sleep` - Wait for the given backoff, eg. a platform timer or a recorder in tests
+    #[cfg(not(feature = "async"))]
+    pub fn set_retry_sleep(&mut self, sleep: Sleep) {
+        self.user.set_retry_sleep(sleep)
+    }
+
+    /// Persist the user state to a state store from now on, the changes made by each operation
+    /// are saved when it completes. A state saved before, eg. by an instance that crashed, is
+    /// restored: the store must have been filled by a user created with the same seed.
+    ///
+    ///   # Arguments
+    ///   * `store` - Backend receiving the changes of the user state, eg. a [`FileStateStore`]
+    pub fn set_state_store(&mut self, store: Box<dyn StateStore>) -> Result<()> {
+        self.user.set_state_store(store)
+    }
+
     /// Store a PSK in the user instance
     ///
     ///   # Arguments
@@ -140,14 +255,38 @@ impl<Trans> Subscriber<Trans> {
         self.user.gen_next_msg_ids(branching)
     }
 
-    /// Serialize user state and encrypt it with password.
+    /// Serialize user state and encrypt it with password, the outbox follows it as it is to be
+    /// published.
     ///
     ///   # Arguments
     ///   * `pwd` - Encryption password
+    #[cfg(feature = "std")]
     pub fn export(&self, pwd: &str) -> Result<Vec<u8>> {
         self.user.export(1, pwd)
     }
 
+    /// Serialize user state and encrypt it with password using custom key derivation parameters,
+    /// the outbox follows it as it is to be published.
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Encryption password
+    ///   * `kdf` - Memory and iteration cost of the password key derivation
+    #[cfg(feature = "std")]
+    pub fn export_with_kdf(&self, pwd: &str, kdf: &KdfParams) -> Result<Vec<u8>> {
+        self.user.export_with_kdf(1, pwd, kdf)
+    }
+
+    /// Serialize user state and encrypt it with password, salting the key derivation from the
+    /// given RNG. Unlike `export`, available without `std`.
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Encryption password
+    ///   * `kdf` - Memory and iteration cost of the password key derivation
+    ///   * `rng` - Cryptographically secure RNG drawing the salt
+    pub fn export_with_rng<R: RngCore + CryptoRng>(&self, pwd: &str, kdf: &KdfParams, rng: &mut R) -> Result<Vec<u8>> {
+        self.user.export_with_rng(1, pwd, kdf, rng)
+    }
+
     /// Deserialize user state and decrypt it with password.
     ///
     ///   # Arguments
@@ -157,6 +296,18 @@ impl<Trans> Subscriber<Trans> {
     pub fn import(bytes: &[u8], pwd: &str, tsp: Trans) -> Result<Self> {
         User::<Trans>::import(bytes, 1, pwd, tsp).map(|user| Self { user })
     }
+
+    /// Deserialize user state and decrypt it with password, rejecting state whose key derivation
+    /// cost exceeds `limits` instead of the default [`KdfLimits`].
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user state
+    ///   * `pwd` - Encryption password
+    ///   * `limits` - Highest accepted memory and iteration cost of the password key derivation
+    ///   * `tsp` - Transport object
+    pub fn import_with_limits(bytes: &[u8], pwd: &str, limits: &KdfLimits, tsp: Trans) -> Result<Self> {
+        User::<Trans>::import_with_limits(bytes, 1, pwd, limits, tsp).map(|user| Self { user })
+    }
 }
 
 #[cfg(not(feature = "async"))]
@@ -202,6 +353,12 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.send_tagged_packet(link_to, public_payload, masked_payload)
     }
 
+    /// Send again the message whose send failed, along with its sequence message, and return their
+    /// links.
+    pub fn retry_staged(&mut self) -> Result<(Address, Option<Address>)> {
+        self.user.retry_staged()
+    }
+
     /// Create and send a tagged packet.
     ///
     ///  # Arguments
@@ -220,11 +377,13 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.send_signed_packet(link_to, public_payload, masked_payload)
     }
 
-    // Unsubscribe from the Channel app instance.
-    // pub pub fn unsubscribe(&mut self, link_to: &Address) -> Result<Message> {
-    // TODO: lookup link_to Subscribe message.
-    // self.user.unsubscribe(link_to.rel(), MsgInfo::Unsubscribe)
-    // }
+    /// Create and Send an Unsubscribe message to a Channel app instance.
+    ///
+    /// # Arguments
+    /// * `link_to` - Address of the Subscribe message previously sent by this Subscriber
+    pub fn send_unsubscribe(&mut self, link_to: &Address) -> Result<Address> {
+        self.user.send_unsubscribe(link_to)
+    }
 
     /// Receive and Process an announcement message.
     ///
@@ -266,11 +425,28 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.receive_sequence(link)
     }
 
+    /// Publish the messages queued in the outbox in order and return their number. Stops at the
+    /// first message that cannot be sent after retrying, it is kept in the outbox with the
+    /// following ones.
+    ///
+    ///   # Arguments
+    ///   * `policy` - Retries of each failed send
+    pub fn flush_outbox(&mut self, policy: RetryPolicy) -> Result<usize> {
+        self.user.flush_outbox(policy)
+    }
+
     /// Retrieves the next message for each user (if present in transport layer) and returns them
     pub fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
         self.user.fetch_next_msgs()
     }
 
+    /// Retrieves the next message for each user and returns the result per publisher, so that
+    /// transport failures can be told apart from messages that fail to unwrap. Publishers with no
+    /// new message are left out.
+    pub fn try_fetch_next_msgs(&mut self) -> Vec<FetchResult> {
+        self.user.try_fetch_next_msgs()
+    }
+
     /// Retrieves the previous message from the message specified (provided the user has access to it)
     pub fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
         self.user.fetch_prev_msg(link)
@@ -281,6 +457,13 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.fetch_prev_msgs(link, max)
     }
 
+    /// Iterator over new messages, fetched one at a time from the next message links of all known
+    /// publishers. Messages that fail to unwrap are returned as errors, iteration can be stopped at
+    /// any point.
+    pub fn messages(&mut self) -> Messages<'_, Trans> {
+        Messages::new(&mut self.user)
+    }
+
     /// Iteratively fetches next message until no new messages can be found, and return a vector
     /// containing all of them.
     pub fn fetch_all_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
@@ -371,6 +554,12 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
             .await
     }
 
+    /// Send again the message whose send failed, along with its sequence message, and return their
+    /// links.
+    pub async fn retry_staged(&mut self) -> Result<(Address, Option<Address>)> {
+        self.user.retry_staged().await
+    }
+
     /// Create and send a tagged packet.
     ///
     ///  # Arguments
@@ -391,11 +580,13 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
             .await
     }
 
-    // Unsubscribe from the Channel app instance.
-    // pub pub async fn unsubscribe(&mut self, link_to: &Address) -> Result<Message> {
-    // TODO: lookup link_to Subscribe message.
-    // self.user.unsubscribe(link_to.rel(), MsgInfo::Unsubscribe).await
-    // }
+    /// Create and Send an Unsubscribe message to a Channel app instance.
+    ///
+    /// # Arguments
+    /// * `link_to` - Address of the Subscribe message previously sent by this Subscriber
+    pub async fn send_unsubscribe(&mut self, link_to: &Address) -> Result<Address> {
+        self.user.send_unsubscribe(link_to).await
+    }
 
     /// Receive and Process an announcement message.
     ///
@@ -437,11 +628,28 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.receive_sequence(link).await
     }
 
+    /// Publish the messages queued in the outbox in order and return their number. Stops at the
+    /// first message that cannot be sent after retrying, it is kept in the outbox with the
+    /// following ones.
+    ///
+    ///   # Arguments
+    ///   * `policy` - Retries of each failed send
+    pub async fn flush_outbox(&mut self, policy: RetryPolicy) -> Result<usize> {
+        self.user.flush_outbox(policy).await
+    }
+
     /// Retrieves the next message for each user (if present in transport layer) and returns them
     pub async fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
         self.user.fetch_next_msgs().await
     }
 
+    /// Retrieves the next message for each user and returns the result per publisher, so that
+    /// transport failures can be told apart from messages that fail to unwrap. Publishers with no
+    /// new message are left out.
+    pub async fn try_fetch_next_msgs(&mut self) -> Vec<FetchResult> {
+        self.user.try_fetch_next_msgs().await
+    }
+
     /// Retrieves the previous message from the message specified (provided the user has access to it)
     pub async fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
         self.user.fetch_prev_msg(link).await
@@ -452,6 +660,13 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
         self.user.fetch_prev_msgs(link, max).await
     }
 
+    /// Stream of new messages, fetched one at a time from the next message links of all known
+    /// publishers. Messages that fail to unwrap are returned as errors, the stream can be dropped at
+    /// any point.
+    pub fn messages(&mut self) -> impl futures::Stream<Item = Result<UnwrappedMessage>> + '_ {
+        Messages::new(&mut self.user).into_stream()
+    }
+
     /// Iteratively fetches next message until no new messages can be found, and return a vector
     /// containing all of them.
     pub async fn fetch_all_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
@@ -501,6 +716,15 @@ impl<Trans: Transport + Clone> Subscriber<Trans> {
     }
 }
 
+#[cfg(feature = "async")]
+impl<Trans: Transport + TransportSubscribe<Address>> Subscriber<Trans> {
+    /// Waits until new messages are published by known publishers and returns them, without
+    /// polling the transport.
+    pub async fn await_next_msgs(&mut self) -> Result<Vec<UnwrappedMessage>> {
+        self.user.await_next_msgs().await
+    }
+}
+
 impl<T: Transport + Clone> fmt::Display for Subscriber<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
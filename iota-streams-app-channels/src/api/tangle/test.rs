@@ -5,6 +5,9 @@ use crate::api::tangle::{
 };
 use iota_streams_app::message::HasLink;
 
+#[cfg(feature = "async")]
+use futures::StreamExt as _;
+
 #[cfg(not(feature = "async"))]
 use iota_streams_core::{
     try_or,
@@ -31,11 +34,22 @@ pub fn example<T: Transport + Clone>(transport: T) -> Result<()> {
     let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
     let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
 
+    author.set_channel_metadata(Some(ChannelMetadata {
+        name: "TESTCHANNEL".to_string(),
+        description: "Channel for testing".to_string(),
+        content_schema: "urn:example:schema".to_string(),
+        properties: vec![("unit".to_string(), "celsius".to_string())],
+    }))?;
+
     println!("announce");
     let msg = &author.send_announce()?;
     let announcement_str = msg.to_string();
     println!("  {}", announcement_str);
     let announcement_link = announcement_str.parse().unwrap();
+    ensure!(
+        author.set_channel_metadata(None).is_err(),
+        "author changed the metadata of an announced channel"
+    );
 
     {
         subscriberA.receive_announcement(&announcement_link)?;
@@ -43,6 +57,10 @@ pub fn example<T: Transport + Clone>(transport: T) -> Result<()> {
             author.channel_address() == subscriberA.channel_address(),
             "bad channel address"
         );
+        ensure!(
+            subscriberA.channel_metadata().is_some() && author.channel_metadata() == subscriberA.channel_metadata(),
+            "bad channel metadata"
+        );
         subscriberB.receive_announcement(&announcement_link)?;
         ensure!(
             subscriberA.channel_address() == subscriberB.channel_address(),
@@ -56,6 +74,19 @@ pub fn example<T: Transport + Clone>(transport: T) -> Result<()> {
         );
     }
 
+    println!("\npin author key");
+    {
+        let mut subscriberD =
+            Subscriber::new_with_author_key("SUBSCRIBERD9SEED", *subscriberA.get_public_key(), transport.clone())?;
+        ensure!(
+            subscriberD.receive_announcement(&announcement_link).is_err(),
+            "subscriberD accepted an untrusted author key"
+        );
+        let mut subscriberD =
+            Subscriber::new_with_author_key("SUBSCRIBERD9SEED", *author.get_public_key(), transport.clone())?;
+        subscriberD.receive_announcement(&announcement_link)?;
+    }
+
     println!("\nsign packet");
     let signed_packet_link = {
         let (msg, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
@@ -64,6 +95,12 @@ pub fn example<T: Transport + Clone>(transport: T) -> Result<()> {
     };
     println!("  at {}", signed_packet_link.rel());
 
+    println!("\nspam signed packet link");
+    {
+        let spam = BinaryMessage::new(signed_packet_link.clone(), Address::default(), vec![0x5a; 64].into());
+        transport.clone().send_message(&Message::new(spam))?;
+    }
+
     {
         let (_pk, unwrapped_public, unwrapped_masked) = subscriberA.receive_signed_packet(&signed_packet_link)?;
         try_or!(
@@ -76,6 +113,19 @@ pub fn example<T: Transport + Clone>(transport: T) -> Result<()> {
         )?;
     }
 
+    println!("\nstream messages");
+    {
+        let mut subscriberC = Subscriber::new("SUBSCRIBERC9SEED", transport.clone());
+        subscriberC.receive_announcement(&announcement_link)?;
+        let first = subscriberC.messages().next();
+        let author_id = Identifier::from(*author.get_public_key());
+        ensure!(
+            matches!(first, Some(Ok(msg)) if msg.link == signed_packet_link
+                && msg.body.metadata.map_or(false, |metadata| metadata.sender == author_id)),
+            "subscriberC failed to stream signed packet"
+        );
+    }
+
     println!("\nsubscribe");
     let subscribeB_link = {
         let msg = subscriberB.send_subscribe(&announcement_link)?;
@@ -117,10 +167,48 @@ pub fn example<T: Transport + Clone>(transport: T) -> Result<()> {
         ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
     }
 
+    println!("\ntag large packet");
+    let large_payload = Bytes(vec![0x5a; 100_000]);
+    let large_packet_link = {
+        let (msg, _) = author.send_tagged_packet(&tagged_packet_link, &public_payload, &large_payload)?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let (unwrapped_public, unwrapped_masked) = subscriberB.receive_tagged_packet(&large_packet_link)?;
+        ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+        ensure!(large_payload == unwrapped_masked, "bad unwrapped large masked payload");
+    }
+
     {
         subscriberB.receive_keyload(&keyload_link)?;
     }
 
+    println!("\nunsubscribe");
+    let unsubscribeB_link = {
+        let msg = subscriberB.send_unsubscribe(&subscribeB_link)?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        author.receive_unsubscribe(&unsubscribeB_link)?;
+    }
+
+    println!("\nshare keyload for everyone");
+    let keyload_link = {
+        let (msg, _) = author.send_keyload_for_everyone(&announcement_link)?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let resultB = subscriberB.receive_keyload(&keyload_link);
+        let unwrapped = resultB.is_ok() && !resultB.unwrap();
+        try_or!(unwrapped, SubscriberAccessMismatch("B".to_string()))?;
+    }
+
     let subAdump = subscriberA.export("pwdSubA").unwrap();
     let _subscriberA2 = Subscriber::import(subAdump.as_ref(), "pwdSubA", transport.clone()).unwrap();
 
@@ -145,11 +233,22 @@ pub async fn example<T: Transport + Clone>(transport: T) -> Result<()> where
     let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
     let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
 
+    author.set_channel_metadata(Some(ChannelMetadata {
+        name: "TESTCHANNEL".to_string(),
+        description: "Channel for testing".to_string(),
+        content_schema: "urn:example:schema".to_string(),
+        properties: vec![("unit".to_string(), "celsius".to_string())],
+    }))?;
+
     println!("announce");
     let msg = &author.send_announce().await?;
     let announcement_str = msg.to_string();
     println!("  {}", announcement_str);
     let announcement_link = announcement_str.parse().unwrap();
+    ensure!(
+        author.set_channel_metadata(None).is_err(),
+        "author changed the metadata of an announced channel"
+    );
 
     {
         subscriberA.receive_announcement(&announcement_link).await?;
@@ -157,6 +256,10 @@ pub async fn example<T: Transport + Clone>(transport: T) -> Result<()> where
             author.channel_address() == subscriberA.channel_address(),
             "bad channel address"
         );
+        ensure!(
+            subscriberA.channel_metadata().is_some() && author.channel_metadata() == subscriberA.channel_metadata(),
+            "bad channel metadata"
+        );
         subscriberB.receive_announcement(&announcement_link).await?;
         ensure!(
             subscriberA.channel_address() == subscriberB.channel_address(),
@@ -170,6 +273,19 @@ pub async fn example<T: Transport + Clone>(transport: T) -> Result<()> where
         );
     }
 
+    println!("\npin author key");
+    {
+        let mut subscriberD =
+            Subscriber::new_with_author_key("SUBSCRIBERD9SEED", *subscriberA.get_public_key(), transport.clone())?;
+        ensure!(
+            subscriberD.receive_announcement(&announcement_link).await.is_err(),
+            "subscriberD accepted an untrusted author key"
+        );
+        let mut subscriberD =
+            Subscriber::new_with_author_key("SUBSCRIBERD9SEED", *author.get_public_key(), transport.clone())?;
+        subscriberD.receive_announcement(&announcement_link).await?;
+    }
+
     println!("\nsign packet");
     let signed_packet_link = {
         let (msg, _) = author
@@ -180,12 +296,33 @@ pub async fn example<T: Transport + Clone>(transport: T) -> Result<()> where
     };
     println!("  at {}", signed_packet_link.rel());
 
+    println!("\nspam signed packet link");
+    {
+        let spam = BinaryMessage::new(signed_packet_link.clone(), Address::default(), vec![0x5a; 64].into());
+        transport.clone().send_message(&Message::new(spam)).await?;
+    }
+
     {
         let (_pk, unwrapped_public, unwrapped_masked) = subscriberA.receive_signed_packet(&signed_packet_link).await?;
         ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
         ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
     }
 
+    println!("\nstream messages");
+    {
+        let mut subscriberC = Subscriber::new("SUBSCRIBERC9SEED", transport.clone());
+        subscriberC.receive_announcement(&announcement_link).await?;
+        let messages = subscriberC.messages();
+        futures::pin_mut!(messages);
+        let first = messages.next().await;
+        let author_id = Identifier::from(*author.get_public_key());
+        ensure!(
+            matches!(first, Some(Ok(msg)) if msg.link == signed_packet_link
+                && msg.body.metadata.map_or(false, |metadata| metadata.sender == author_id)),
+            "subscriberC failed to stream signed packet"
+        );
+    }
+
     println!("\nsubscribe");
     let subscribeB_link = {
         let msg = subscriberB.send_subscribe(&announcement_link).await?;
@@ -231,10 +368,52 @@ pub async fn example<T: Transport + Clone>(transport: T) -> Result<()> where
         ensure!(masked_payload == unwrapped_masked, "bad unwrapped masked payload");
     }
 
+    println!("\ntag large packet");
+    let large_payload = Bytes(vec![0x5a; 100_000]);
+    let large_packet_link = {
+        let (msg, _) = author
+            .send_tagged_packet(&tagged_packet_link, &public_payload, &large_payload)
+            .await?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let (unwrapped_public, unwrapped_masked) = subscriberB.receive_tagged_packet(&large_packet_link).await?;
+        ensure!(public_payload == unwrapped_public, "bad unwrapped public payload");
+        ensure!(large_payload == unwrapped_masked, "bad unwrapped large masked payload");
+    }
+
     {
         subscriberB.receive_keyload(&keyload_link).await?;
     }
 
+    println!("\nunsubscribe");
+    let unsubscribeB_link = {
+        let msg = subscriberB.send_unsubscribe(&subscribeB_link).await?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        author.receive_unsubscribe(&unsubscribeB_link).await?;
+    }
+
+    println!("\nshare keyload for everyone");
+    let keyload_link = {
+        let (msg, _) = author.send_keyload_for_everyone(&announcement_link).await?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let resultB = subscriberB.receive_keyload(&keyload_link).await;
+        ensure!(
+            resultB.is_ok() && !resultB.unwrap(),
+            "subscriberB should not unwrap keyload after unsubscribing"
+        );
+    }
+
     let subAdump = subscriberA.export("pwdSubA").unwrap();
     let _subscriberA2 = Subscriber::import(subAdump.as_ref(), "pwdSubA", transport.clone()).unwrap();
 
@@ -260,3 +439,1610 @@ fn run_basic_scenario() {
     let transport = iota_streams_app::transport::new_shared_transport(crate::api::tangle::BucketTransport::new());
     assert!(dbg!(smol::block_on(example(transport))).is_ok());
 }
+
+/// Revoked subscribers and pre shared keys cannot read packets linked to the rekeyed keyload,
+/// the remaining recipients can.
+#[test]
+#[cfg(not(feature = "async"))]
+fn revoked_recipients_cannot_read_after_rekey() {
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let mut subscriberA = Subscriber::new("SUBSCRIBERA9SEED", transport.clone());
+    let mut subscriberB = Subscriber::new("SUBSCRIBERB9SEED", transport.clone());
+    let mut subscriberC = Subscriber::new("SUBSCRIBERC9SEED", transport);
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce().unwrap();
+    for subscriber in [&mut subscriberA, &mut subscriberB, &mut subscriberC] {
+        subscriber.receive_announcement(&announcement_link).unwrap();
+    }
+    let psk = crate::api::psk_from_seed("A pre shared key".as_bytes());
+    let pskid = crate::api::pskid_from_psk(&psk);
+    author.store_psk(pskid, psk).unwrap();
+    subscriberC.store_psk(pskid, psk).unwrap();
+    let subscribeA_link = subscriberA.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribeA_link).unwrap();
+    let subscribeB_link = subscriberB.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribeB_link).unwrap();
+
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    let (packet_link, _) = author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+    for subscriber in [&mut subscriberA, &mut subscriberB, &mut subscriberC] {
+        let msgs = subscriber.fetch_all_next_msgs();
+        assert_eq!(
+            msgs.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+            vec![keyload_link.clone(), packet_link.clone()]
+        );
+    }
+
+    assert!(author
+        .remove_subscriber(subscriberB.get_public_key(), None)
+        .unwrap()
+        .is_none());
+    let (rekey_link, _) = author
+        .remove_psk(pskid, Some(&packet_link))
+        .unwrap()
+        .expect("rekey keyload should be sent");
+    let (revoked_packet_link, _) = author.send_signed_packet(&rekey_link, &payload, &payload).unwrap();
+
+    let msgsA = subscriberA.fetch_all_next_msgs();
+    assert_eq!(
+        msgsA.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![rekey_link, revoked_packet_link.clone()]
+    );
+    for subscriber in [&mut subscriberB, &mut subscriberC] {
+        let msgs = subscriber.fetch_all_next_msgs();
+        assert!(msgs
+            .iter()
+            .all(|msg| msg.link != revoked_packet_link || matches!(msg.body.content, MessageContent::Unreadable)));
+    }
+}
+
+/// A pre-shared key subscriber of a single depth channel keeps its key, `use_psk` and anchor
+/// across an export.
+#[test]
+#[cfg(not(feature = "async"))]
+fn export_import_psk_subscriber() {
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleDepth, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9SEED", transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let psk = crate::api::psk_from_seed("A pre shared key".as_bytes());
+    let pskid = crate::api::pskid_from_psk(&psk);
+    author.store_psk(pskid, psk).unwrap();
+    subscriber.store_psk(pskid, psk).unwrap();
+    let (anchor_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    let packet_links: Vec<Address> = (0..3)
+        .map(|_| author.send_signed_packet(&anchor_link, &payload, &payload).unwrap().0)
+        .collect();
+    assert!(subscriber.receive_keyload(&anchor_link).unwrap());
+
+    let mut subscriber = Subscriber::import(&subscriber.export("PASSWORD").unwrap(), "PASSWORD", transport).unwrap();
+    let msg = subscriber.receive_msg_by_sequence_number(&anchor_link, 2).unwrap();
+    assert_eq!(msg.link, packet_links[2]);
+}
+
+/// A subscriber publishing with a pre-shared key still cannot sign packets after an export.
+#[test]
+#[cfg(not(feature = "async"))]
+fn export_import_use_psk() {
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9SEED", transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let psk = crate::api::psk_from_seed("A pre shared key".as_bytes());
+    subscriber.store_psk(crate::api::pskid_from_psk(&psk), psk).unwrap();
+
+    let mut subscriber = Subscriber::import(&subscriber.export("PASSWORD").unwrap(), "PASSWORD", transport).unwrap();
+    let e = subscriber
+        .send_signed_packet(&announcement_link, &payload, &payload)
+        .err()
+        .unwrap();
+    assert!(matches!(
+        e.downcast_ref::<iota_streams_core::Errors>(),
+        Some(MessageBuildFailure)
+    ));
+}
+
+/// Wraps a multi-branch subscriber state in the version 0 export format: no pre-shared keys,
+/// `use_psk`, anchor nor link store, and a key derived from the password without salt.
+#[cfg(all(test, not(feature = "async")))]
+fn export_v0_subscriber(sig_sk: &[u8; 32], appinst: &Address, author_pk: &PublicKey, pwd: &str) -> Result<Vec<u8>> {
+    use iota_streams_app::{
+        identifier::Identifier,
+        message::ContentWrap as _,
+    };
+    use iota_streams_core::{
+        prelude::typenum::U32,
+        prng,
+    };
+    use iota_streams_ddml::{
+        command::*,
+        link_store::EmptyLinkStore,
+        types::*,
+    };
+
+    let sig_pk = ed25519::PublicKey::from(&ed25519::SecretKey::from_bytes(sig_sk)?);
+    let key = NBytes::<U32>(prng::from_seed::<DefaultF>("IOTA Streams Channels app", pwd).gen_arr("user export key"));
+    let ids = [
+        Identifier::EdPubKey((*author_pk).into()),
+        Identifier::EdPubKey(sig_pk.into()),
+    ];
+    let store = EmptyLinkStore::<DefaultF, MsgId, ()>::default();
+
+    let mut buf = vec![0; 1024];
+    let unused = {
+        let mut ctx = wrap::Context::<DefaultF, _>::new(&mut buf[..]);
+        ctx.absorb(Uint8(0))?
+            .absorb(Uint8(1))?
+            .absorb(External(&key))?
+            .mask(<&NBytes<U32>>::from(&sig_sk[..]))?
+            .absorb(Uint8(1))?
+            .absorb(&Bytes("utf-8".as_bytes().to_vec()))?
+            .absorb(Uint64(32_000))?
+            .absorb(Uint8(1))?
+            .absorb(<&Fallback<Address>>::from(appinst))?
+            .absorb(Uint8(1))?
+            .absorb(author_pk)?
+            .absorb(Size(0))?
+            .absorb(Size(ids.len()))?;
+        for id in &ids {
+            id.wrap(&store, &mut ctx)?
+                .absorb(<&Fallback<MsgId>>::from(appinst.rel()))?
+                .absorb(Uint32(0))?
+                .absorb(Uint32(2))?;
+        }
+        ctx.commit()?.squeeze(Mac(32))?;
+        ctx.stream.len()
+    };
+    buf.truncate(buf.len() - unused);
+    Ok(buf)
+}
+
+/// State exported with the initial format can still be imported and used.
+#[test]
+#[cfg(not(feature = "async"))]
+fn import_v0_subscriber() {
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+    let announcement_link = author.send_announce().unwrap();
+
+    let sig_sk = [7; 32];
+    let blob = export_v0_subscriber(&sig_sk, &announcement_link, author.get_public_key(), "PASSWORD").unwrap();
+    let mut subscriber = Subscriber::import(&blob, "PASSWORD", transport).unwrap();
+    assert_eq!(subscriber.channel_address(), author.channel_address());
+    assert_eq!(subscriber.author_public_key(), Some(author.get_public_key()));
+    assert_eq!(
+        subscriber.get_public_key(),
+        &ed25519::PublicKey::from(&ed25519::SecretKey::from_bytes(&sig_sk).unwrap())
+    );
+    assert!(subscriber.is_multi_branching());
+
+    // Version 0 did not export the link store, the announcement has to be processed again
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    let (packet_link, _) = author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+    let msgs = subscriber.fetch_all_next_msgs();
+    assert_eq!(
+        msgs.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![keyload_link, packet_link]
+    );
+}
+
+#[cfg(all(test, not(feature = "async")))]
+fn assert_kdf_error<T>(r: Result<T>, expected: iota_streams_core::Errors) {
+    let e = r.err().unwrap();
+    assert_eq!(
+        e.downcast_ref::<iota_streams_core::Errors>().map(ToString::to_string),
+        Some(expected.to_string())
+    );
+}
+
+/// Exported state imports with the right password only.
+#[test]
+#[cfg(not(feature = "async"))]
+fn export_import_kdf() {
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let announcement_link = author.send_announce().unwrap();
+    let mut rng = iota_streams_core::prng::Rng::new(
+        iota_streams_core::prng::from_seed::<DefaultF>("export salt", "RNG9SEED"),
+        vec![0; 16],
+    );
+
+    let kdf = KdfParams::new(64, 1);
+    for blob in [
+        author.export_with_kdf("PASSWORD", &kdf).unwrap(),
+        author.export_with_rng("PASSWORD", &kdf, &mut rng).unwrap(),
+    ] {
+        let imported = Author::import(&blob, "PASSWORD", transport.clone()).unwrap();
+        assert_eq!(imported.channel_address(), Some(&announcement_link.appinst));
+        assert_eq!(imported.get_public_key(), author.get_public_key());
+        assert!(Author::import(&blob, "WRONG9PASSWORD", transport.clone()).is_err());
+    }
+}
+
+/// Exporting with key derivation parameters out of the accepted bounds is refused.
+#[test]
+#[cfg(not(feature = "async"))]
+fn export_kdf_out_of_bounds() {
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport);
+
+    for (memory_kib, iterations) in [
+        (KdfParams::MIN_MEMORY_KIB - 1, 1),
+        (KdfParams::MAX_MEMORY_KIB + 1, 1),
+        (64, 0),
+        (64, KdfParams::MAX_ITERATIONS + 1),
+    ] {
+        assert_kdf_error(
+            author.export_with_kdf("PASSWORD", &KdfParams::new(memory_kib, iterations)),
+            KdfCostOutOfBounds(memory_kib, iterations),
+        );
+    }
+}
+
+/// Importing state whose stored key derivation cost exceeds the limits fails before deriving the
+/// key, so a forged header cannot make the import exhaust memory.
+#[test]
+#[cfg(not(feature = "async"))]
+fn import_kdf_above_limit() {
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let kdf = KdfParams::new(256, 2);
+    let blob = author.export_with_kdf("PASSWORD", &kdf).unwrap();
+
+    assert!(Author::import_with_limits(&blob, "PASSWORD", &KdfLimits::new(256, 2), transport.clone()).is_ok());
+    assert_kdf_error(
+        Author::import_with_limits(&blob, "PASSWORD", &KdfLimits::new(128, 2), transport.clone()),
+        KdfCostAboveLimit(256, 2),
+    );
+    assert_kdf_error(
+        Author::import_with_limits(&blob, "PASSWORD", &KdfLimits::new(256, 1), transport.clone()),
+        KdfCostAboveLimit(256, 2),
+    );
+
+    // Forge the maximum cost into the header: version, flag, salt, memory, iterations
+    let mut forged = blob;
+    forged[34..38].copy_from_slice(&KdfParams::MAX_MEMORY_KIB.to_be_bytes());
+    assert_kdf_error(
+        Author::import(&forged, "PASSWORD", transport),
+        KdfCostAboveLimit(KdfParams::MAX_MEMORY_KIB, 2),
+    );
+}
+
+#[cfg(all(test, not(feature = "async")))]
+type SharedBucket = iota_streams_core::prelude::Rc<core::cell::RefCell<BucketTransport>>;
+
+/// Publish `junk` bodies at `link` of `to`, then relay the genuine messages found at `link` of
+/// `from`: junk is found first.
+#[cfg(all(test, not(feature = "async")))]
+fn relay_after_junk(from: &mut SharedBucket, to: &mut SharedBucket, link: &Address, junk: &[Vec<u8>]) {
+    for body in junk {
+        let junk = BinaryMessage::new(link.clone(), Address::default(), body.clone().into());
+        to.send_message(&Message::new(junk)).unwrap();
+    }
+    for msg in from.recv_messages(link).unwrap() {
+        to.send_message(&msg).unwrap();
+    }
+}
+
+/// Links of the continuation frames of the multi-frame message published at `link` of `transport`.
+#[cfg(all(test, not(feature = "async")))]
+fn find_frame_links(transport: &mut SharedBucket, announcement_link: &Address, link: &Address) -> Vec<Address> {
+    use iota_streams_app::message::{
+        Cursor,
+        LinkGenerator,
+    };
+
+    let mut link_gen = LinkGen::default();
+    link_gen.reset(announcement_link.clone());
+    (2..)
+        .map(|num| link_gen.uniform_link_from(Cursor::new_at(link.rel(), 0, num)))
+        .take_while(|frame_link| transport.recv_message(frame_link).is_ok())
+        .collect()
+}
+
+/// Copy of a continuation frame with a byte of its chunk flipped: it has a valid frame header.
+#[cfg(all(test, not(feature = "async")))]
+fn corrupt_frame(frame: &[u8], at: usize) -> Vec<u8> {
+    use iota_streams_app::message::fragment::{
+        FRAME_HASH_SIZE,
+        FRAME_HEADER_SIZE,
+    };
+
+    let mut frame = frame.to_vec();
+    frame[FRAME_HEADER_SIZE + FRAME_HASH_SIZE + at] ^= 1;
+    frame
+}
+
+/// Junk and replayed messages published at the sequence, packet and frame links of a multi-frame
+/// packet, before the genuine ones, are skipped by recipients and do not turn the packet into a
+/// readable one for non-recipients.
+#[test]
+#[cfg(not(feature = "async"))]
+fn tolerate_junk_at_sequence_and_frame_links() {
+    let mut published = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, published.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", published.clone());
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes(vec![0x2a; 100_000]);
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, keyload_seq_link) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    let (packet_link, packet_seq_link) = author
+        .send_signed_packet(&keyload_link, &public_payload, &masked_payload)
+        .unwrap();
+    let frame_links = find_frame_links(&mut published, &announcement_link, &packet_link);
+    assert_eq!(frame_links.len(), 3);
+
+    let body = |transport: &mut SharedBucket, link: &Address| transport.recv_message(link).unwrap().binary.body.bytes;
+    let spam = vec![0x5a; 64];
+    let announcement = body(&mut published, &announcement_link);
+    let keyload = body(&mut published, &keyload_link);
+    let mut transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    relay_after_junk(&mut published, &mut transport, &announcement_link, &[]);
+    relay_after_junk(&mut published, &mut transport, &keyload_link, &[]);
+    for seq_link in [keyload_seq_link.unwrap(), packet_seq_link.unwrap()].iter() {
+        let junk = [spam.clone(), announcement.clone(), keyload.clone()];
+        relay_after_junk(&mut published, &mut transport, seq_link, &junk);
+    }
+    relay_after_junk(&mut published, &mut transport, &packet_link, &[spam.clone(), keyload]);
+    for frame_link in frame_links.iter() {
+        let frame = body(&mut published, frame_link);
+        let junk = [spam.clone(), corrupt_frame(&frame, 0), corrupt_frame(&frame, 1)];
+        relay_after_junk(&mut published, &mut transport, frame_link, &junk);
+    }
+
+    let mut subscriberA = Subscriber::new("SUBSCRIBERA9SEED", transport.clone());
+    subscriberA.receive_announcement(&announcement_link).unwrap();
+    let msgs = subscriberA.fetch_all_next_msgs();
+    assert_eq!(
+        msgs.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![keyload_link.clone(), packet_link.clone()]
+    );
+    assert!(matches!(
+        &msgs[1].body.content,
+        MessageContent::SignedPacket { masked_payload: payload, .. } if *payload == masked_payload
+    ));
+
+    let mut subscriberB = Subscriber::new("SUBSCRIBERB9SEED", transport);
+    subscriberB.receive_announcement(&announcement_link).unwrap();
+    let msgs = subscriberB.fetch_all_next_msgs();
+    assert_eq!(
+        msgs.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![keyload_link.clone(), packet_link]
+    );
+    assert!(matches!(msgs[1].body.content, MessageContent::Unreadable));
+    assert!(msgs[1].body.metadata.is_none());
+    assert!(msgs[1].prev_link == keyload_link);
+}
+
+/// Frames published at a frame link by anyone else do not match the hash committed to by the
+/// genuine message and are skipped, however many there are.
+#[test]
+#[cfg(not(feature = "async"))]
+fn tolerate_flooded_frame_link() {
+    let mut published = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, published.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", transport.clone());
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes(vec![0x2a; 100_000]);
+
+    let announcement_link = author.send_announce().unwrap();
+    relay_after_junk(&mut published, &mut transport, &announcement_link, &[]);
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    relay_after_junk(&mut transport, &mut published, &subscribe_link, &[]);
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    relay_after_junk(&mut published, &mut transport, &keyload_link, &[]);
+    subscriber.receive_keyload(&keyload_link).unwrap();
+
+    let (packet_link, _) = author
+        .send_signed_packet(&keyload_link, &public_payload, &masked_payload)
+        .unwrap();
+    relay_after_junk(&mut published, &mut transport, &packet_link, &[]);
+    let frame_links = find_frame_links(&mut published, &announcement_link, &packet_link);
+    let frame = published.recv_message(&frame_links[0]).unwrap().binary.body.bytes;
+    let junk: Vec<Vec<u8>> = (0..32).map(|at| corrupt_frame(&frame, at)).collect();
+    relay_after_junk(&mut published, &mut transport, &frame_links[0], &junk);
+    for frame_link in frame_links[1..].iter() {
+        relay_after_junk(&mut published, &mut transport, frame_link, &[]);
+    }
+
+    let (_, _, unwrapped_masked) = subscriber.receive_signed_packet(&packet_link).unwrap();
+    assert!(unwrapped_masked == masked_payload);
+}
+
+/// A keyload the subscriber has no key in, replayed at the link of a keyload the subscriber can
+/// read, is not committed: the state is the one left by the genuine keyload.
+#[test]
+#[cfg(not(feature = "async"))]
+fn ignore_junk_keyload_candidates() {
+    use iota_streams_app::identifier::Identifier;
+    use iota_streams_core::prelude::String;
+
+    let mut published = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, published.clone());
+    let mut subscriberA = Subscriber::new("SUBSCRIBERA9SEED", published.clone());
+    let mut subscriberB = Subscriber::new("SUBSCRIBERB9SEED", published.clone());
+
+    let announcement_link = author.send_announce().unwrap();
+    for subscriber in [&mut subscriberA, &mut subscriberB] {
+        subscriber.receive_announcement(&announcement_link).unwrap();
+        let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+        author.receive_subscribe(&subscribe_link).unwrap();
+    }
+    let idB = Identifier::from(*subscriberB.get_public_key());
+    let (keyloadB_link, _) = author.send_keyload(&announcement_link, vec![&idB]).unwrap();
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+
+    let mut transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let keyloadB = published.recv_message(&keyloadB_link).unwrap().binary.body.bytes;
+    relay_after_junk(&mut published, &mut transport, &announcement_link, &[]);
+    relay_after_junk(
+        &mut published,
+        &mut transport,
+        &keyload_link,
+        &[keyloadB.clone(), keyloadB],
+    );
+
+    let mut expected = Subscriber::new("SUBSCRIBERA9SEED", published);
+    expected.receive_announcement(&announcement_link).unwrap();
+    assert!(expected.receive_keyload(&keyload_link).unwrap());
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", transport);
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    assert!(subscriber.receive_keyload(&keyload_link).unwrap());
+    let state = |subscriber: &Subscriber<SharedBucket>| -> Vec<(String, Address, u32)> {
+        let state = subscriber.fetch_state().unwrap().into_iter();
+        let mut state: Vec<_> = state.map(|(id, cursor)| (id, cursor.link, cursor.seq_no)).collect();
+        state.sort_by(|a, b| a.0.cmp(&b.0));
+        state
+    };
+    assert!(state(&subscriber) == state(&expected));
+}
+
+/// Shared bucket failing every send once `sends_left` sends have succeeded, and every receive while
+/// `recvs_fail` is set. Messages are confirmed at
+/// `CONFIRMED_AT`, every confirmation lookup is recorded and fails while `confirmations_fail` is set.
+#[cfg(all(test, not(feature = "async")))]
+#[derive(Clone, Default)]
+struct FaultyTransport {
+    inner: iota_streams_core::prelude::Rc<core::cell::RefCell<BucketTransport>>,
+    sends_left: iota_streams_core::prelude::Rc<core::cell::Cell<Option<usize>>>,
+    recvs_fail: iota_streams_core::prelude::Rc<core::cell::Cell<bool>>,
+    confirmations_fail: iota_streams_core::prelude::Rc<core::cell::Cell<bool>>,
+    confirmations: iota_streams_core::prelude::Rc<core::cell::RefCell<Vec<Address>>>,
+}
+
+#[cfg(all(test, not(feature = "async")))]
+const CONFIRMED_AT: u64 = 1_600_000_000_000;
+
+#[cfg(all(test, not(feature = "async")))]
+impl iota_streams_app::transport::TransportOptions for FaultyTransport {
+    type SendOptions = ();
+    fn get_send_options(&self) {}
+    fn set_send_options(&mut self, _opt: ()) {}
+
+    type RecvOptions = ();
+    fn get_recv_options(&self) {}
+    fn set_recv_options(&mut self, _opt: ()) {}
+}
+
+#[cfg(all(test, not(feature = "async")))]
+impl iota_streams_app::transport::TransportDetails<Address> for FaultyTransport {
+    type Details = ();
+    fn get_link_details(&mut self, _link: &Address) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, not(feature = "async")))]
+impl iota_streams_app::transport::Transport<Address, Message> for FaultyTransport {
+    fn send_message(&mut self, msg: &Message) -> Result<()> {
+        match self.sends_left.get() {
+            Some(0) => return iota_streams_core::err(ClientOperationFailure),
+            Some(n) => self.sends_left.set(Some(n - 1)),
+            None => {}
+        }
+        self.inner.send_message(msg)
+    }
+
+    fn recv_messages(&mut self, link: &Address) -> Result<Vec<Message>> {
+        if self.recvs_fail.get() {
+            return iota_streams_core::err(ClientOperationFailure);
+        }
+        self.inner.recv_messages(link)
+    }
+
+    fn confirmation_timestamp(&mut self, msg: &Message) -> Result<Option<u64>> {
+        self.confirmations.borrow_mut().push(msg.binary.link.clone());
+        if self.confirmations_fail.get() {
+            return iota_streams_core::err(ClientOperationFailure);
+        }
+        Ok(Some(CONFIRMED_AT))
+    }
+}
+
+/// Fetch errors tell a failure to retrieve the next message from messages which do not unwrap.
+#[test]
+#[cfg(not(feature = "async"))]
+fn classify_fetch_errors() {
+    let transport = FaultyTransport::default();
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    assert_eq!(subscriber.fetch_all_next_msgs().len(), 1);
+    let (packet_link, _) = author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+
+    // The next links of both the author and the subscriber cannot be retrieved
+    transport.recvs_fail.set(true);
+    let errors: Vec<FetchError> = subscriber
+        .try_fetch_next_msgs()
+        .into_iter()
+        .map(|result| result.err().unwrap())
+        .collect();
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().all(|e| e.kind == FetchErrorKind::Transport));
+    assert!(errors.iter().any(|e| e.link == packet_link));
+    assert!(format!("{:?}", errors[0]).contains("Transport"));
+    transport.recvs_fail.set(false);
+
+    // Only the announcement, replayed at the packet link, is found there
+    let mut published = transport.inner.clone();
+    let mut replayed = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    relay_after_junk(&mut published, &mut replayed, &announcement_link, &[]);
+    relay_after_junk(&mut published, &mut replayed, &keyload_link, &[]);
+    let announcement = published.recv_message(&announcement_link).unwrap().binary.body.bytes;
+    let replay = BinaryMessage::new(packet_link.clone(), Address::default(), announcement.into());
+    replayed.send_message(&Message::new(replay)).unwrap();
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", replayed);
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    assert_eq!(subscriber.fetch_all_next_msgs().len(), 1);
+    let results = subscriber.try_fetch_next_msgs();
+    assert_eq!(results.len(), 1);
+    let e = results.into_iter().next().unwrap().err().unwrap();
+    assert_eq!(e.kind, FetchErrorKind::Unwrap);
+    assert!(e.link == packet_link);
+}
+
+/// Fetched messages carry the timestamp of the milestone confirming them, looked up for the
+/// selected candidate only. A failed lookup leaves the message to be fetched again.
+#[test]
+#[cfg(not(feature = "async"))]
+fn confirm_messages_with_milestone_timestamps() {
+    let mut transport = FaultyTransport::default();
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    let (packet_link, _) = author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+    // The packet is published twice
+    let packet = transport.recv_message(&packet_link).unwrap();
+    transport.send_message(&packet).unwrap();
+
+    transport.confirmations_fail.set(true);
+    let results = subscriber.try_fetch_next_msgs();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+    transport.confirmations_fail.set(false);
+
+    let msgs = subscriber.fetch_all_next_msgs();
+    assert_eq!(
+        msgs.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![keyload_link.clone(), packet_link.clone()]
+    );
+    assert!(msgs.iter().all(|msg| msg
+        .body
+        .metadata
+        .map_or(false, |metadata| metadata.milestone_timestamp == Some(CONFIRMED_AT))));
+    assert_eq!(
+        *transport.confirmations.borrow(),
+        vec![keyload_link.clone(), keyload_link, packet_link]
+    );
+}
+
+/// Sent messages are timestamped with the clock of their publisher.
+#[test]
+#[cfg(not(feature = "async"))]
+fn timestamp_messages_with_publisher_clock() {
+    use core::time::Duration;
+    use iota_streams_core::prelude::Arc;
+
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", transport);
+    let clock = Arc::new(ManualClock::new(1_600_000_000_000));
+    author.set_clock(clock.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    clock.advance(Duration::from_secs(5));
+    author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+
+    let msgs = subscriber.fetch_all_next_msgs();
+    assert_eq!(
+        msgs.iter()
+            .map(|msg| msg
+                .body
+                .metadata
+                .map(|metadata| (metadata.timestamp, metadata.milestone_timestamp)))
+            .collect::<Vec<_>>(),
+        vec![Some((1_600_000_000_000, None)), Some((1_600_000_005_000, None))]
+    );
+}
+
+/// One seed owns a distinct channel per index, recovering an index restores the channel with that
+/// index.
+#[test]
+#[cfg(not(feature = "async"))]
+fn own_channels_by_index() {
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author0 = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let mut author1 = Author::new_with_index("AUTHOR9SEED", ChannelType::SingleBranch, 1, transport.clone());
+    let mut author2 = Author::new_with_index("AUTHOR9SEED", ChannelType::SingleBranch, 2, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    let announcement0_link = author0.send_announce().unwrap();
+    let announcement1_link = author1.send_announce().unwrap();
+    let announcement2_link = author2.send_announce().unwrap();
+    let channels = [
+        author0.channel_address().unwrap().clone(),
+        author1.channel_address().unwrap().clone(),
+        author2.channel_address().unwrap().clone(),
+    ];
+    assert!(channels[0] != channels[1] && channels[1] != channels[2] && channels[0] != channels[2]);
+    assert!(announcement1_link.appinst == channels[1]);
+
+    subscriber.receive_announcement(&announcement1_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement1_link).unwrap();
+    author1.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, _) = author1.send_keyload_for_everyone(&announcement1_link).unwrap();
+    let (packet_link, _) = author1.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+    assert_eq!(subscriber.fetch_all_next_msgs().len(), 2);
+
+    let recovered0 = Author::recover(
+        "AUTHOR9SEED",
+        &announcement0_link,
+        ChannelType::SingleBranch,
+        transport.clone(),
+    )
+    .unwrap();
+    assert!(recovered0.channel_address() == Some(&channels[0]));
+    let mut recovered1 = Author::recover_with_index(
+        "AUTHOR9SEED",
+        &announcement1_link,
+        ChannelType::SingleBranch,
+        1,
+        transport.clone(),
+    )
+    .unwrap();
+    assert!(recovered1.channel_address() == Some(&channels[1]));
+
+    // The recovered author keeps publishing in the channel the subscriber follows
+    let (reply_link, _) = recovered1.send_signed_packet(&packet_link, &payload, &payload).unwrap();
+    let msgs = subscriber.fetch_all_next_msgs();
+    assert_eq!(
+        msgs.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![reply_link]
+    );
+    assert!(matches!(
+        &msgs[0].body.content,
+        MessageContent::SignedPacket { masked_payload, .. } if *masked_payload == payload
+    ));
+}
+
+/// An announcement signed with a key the channel address has not been derived from is rejected.
+#[test]
+#[cfg(not(feature = "async"))]
+fn reject_announcement_not_bound_to_author_key() {
+    use iota_streams_app::message::LinkGenerator;
+
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let announcement_link = author.send_announce().unwrap();
+
+    // Another key announces the channel of the author, at the same link
+    let mut forged = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut forger = user::User::new("FORGER9SEED", ChannelType::SingleBranch, forged.clone());
+    forger.user.link_gen.reset(announcement_link.clone());
+    let announcement = forger.user.announce().unwrap();
+    assert!(announcement.message.link == announcement_link);
+    forged.send_message(&Message::new(announcement.message)).unwrap();
+
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", forged);
+    let e = subscriber.receive_announcement(&announcement_link).err().unwrap();
+    assert!(matches!(
+        e.downcast_ref::<iota_streams_core::Errors>(),
+        Some(ChannelAuthorMismatch(_))
+    ));
+    assert!(subscriber.channel_address().is_none());
+
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", transport);
+    subscriber.receive_announcement(&announcement_link).unwrap();
+}
+
+/// A subscriber pinning the key of an author refuses the announcements of any other author.
+#[test]
+#[cfg(not(feature = "async"))]
+fn pinned_author_key_refuses_other_authors() {
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let mut other = Author::new("OTHER9AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let announcement_link = author.send_announce().unwrap();
+    let other_announcement_link = other.send_announce().unwrap();
+
+    let mut subscriber =
+        Subscriber::new_with_author_key("SUBSCRIBERA9SEED", *author.get_public_key(), transport).unwrap();
+    let e = subscriber.receive_announcement(&other_announcement_link).err().unwrap();
+    assert!(matches!(
+        e.downcast_ref::<iota_streams_core::Errors>(),
+        Some(UntrustedAuthorKey(_, _))
+    ));
+    assert!(subscriber.channel_address().is_none());
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    assert!(subscriber.channel_address() == author.channel_address());
+
+    // The pin outlives the registration
+    subscriber.unregister();
+    let e = subscriber.receive_announcement(&other_announcement_link).err().unwrap();
+    assert!(matches!(
+        e.downcast_ref::<iota_streams_core::Errors>(),
+        Some(UntrustedAuthorKey(_, _))
+    ));
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    assert!(subscriber.channel_address() == author.channel_address());
+}
+
+/// Links following a keyload can only be found by its recipients, also after a state export.
+#[test]
+#[cfg(not(feature = "async"))]
+fn private_links_hidden_from_non_recipients() {
+    let transport = iota_streams_app::transport::new_shared_transport(crate::api::tangle::BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriberA = Subscriber::new("SUBSCRIBERA9SEED", transport.clone());
+    let mut subscriberB = Subscriber::new("SUBSCRIBERB9SEED", transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    author.set_private_links(true).unwrap();
+    let announcement_link = author.send_announce().unwrap();
+    assert!(author.set_private_links(false).is_err());
+    subscriberA.receive_announcement(&announcement_link).unwrap();
+    subscriberB.receive_announcement(&announcement_link).unwrap();
+    assert!(subscriberA.has_private_links() && subscriberB.has_private_links());
+    let subscribeA_link = subscriberA.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribeA_link).unwrap();
+    let subscribeB_link = subscriberB.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribeB_link).unwrap();
+
+    let (keyload_link, _) = author
+        .send_keyload(&announcement_link, &[Identifier::from(*subscriberA.get_public_key())])
+        .unwrap();
+    let (packet_link, _) = author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+
+    let msgsA = subscriberA.fetch_all_next_msgs();
+    assert_eq!(
+        msgsA.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![keyload_link.clone(), packet_link.clone()]
+    );
+    let msgsB = subscriberB.fetch_all_next_msgs();
+    assert_eq!(
+        msgsB.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![keyload_link]
+    );
+
+    let mut subscriberA = Subscriber::import(&subscriberA.export("PASSWORD").unwrap(), "PASSWORD", transport).unwrap();
+    let (reply_link, _) = subscriberA
+        .send_signed_packet(&packet_link, &payload, &payload)
+        .unwrap();
+    let msgs = author.fetch_all_next_msgs();
+    assert_eq!(
+        msgs.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![reply_link]
+    );
+    assert!(subscriberB.fetch_all_next_msgs().is_empty());
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn private_links_hidden_from_non_recipients() {
+    let transport = iota_streams_app::transport::new_shared_transport(crate::api::tangle::BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriberA = Subscriber::new("SUBSCRIBERA9SEED", transport.clone());
+    let mut subscriberB = Subscriber::new("SUBSCRIBERB9SEED", transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    smol::block_on(async {
+        author.set_private_links(true).unwrap();
+        let announcement_link = author.send_announce().await.unwrap();
+        subscriberA.receive_announcement(&announcement_link).await.unwrap();
+        subscriberB.receive_announcement(&announcement_link).await.unwrap();
+        let subscribeA_link = subscriberA.send_subscribe(&announcement_link).await.unwrap();
+        author.receive_subscribe(&subscribeA_link).await.unwrap();
+        let subscribeB_link = subscriberB.send_subscribe(&announcement_link).await.unwrap();
+        author.receive_subscribe(&subscribeB_link).await.unwrap();
+
+        let (keyload_link, _) = author
+            .send_keyload(&announcement_link, &[Identifier::from(*subscriberA.get_public_key())])
+            .await
+            .unwrap();
+        let (packet_link, _) = author
+            .send_signed_packet(&keyload_link, &payload, &payload)
+            .await
+            .unwrap();
+
+        let msgsA = subscriberA.fetch_all_next_msgs().await;
+        assert_eq!(
+            msgsA.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+            vec![keyload_link.clone(), packet_link.clone()]
+        );
+        let msgsB = subscriberB.fetch_all_next_msgs().await;
+        assert_eq!(
+            msgsB.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+            vec![keyload_link]
+        );
+
+        let mut subscriberA =
+            Subscriber::import(&subscriberA.export("PASSWORD").unwrap(), "PASSWORD", transport).unwrap();
+        let (reply_link, _) = subscriberA
+            .send_signed_packet(&packet_link, &payload, &payload)
+            .await
+            .unwrap();
+        let msgs = author.fetch_all_next_msgs().await;
+        assert_eq!(
+            msgs.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+            vec![reply_link]
+        );
+        assert!(subscriberB.fetch_all_next_msgs().await.is_empty());
+    });
+}
+
+/// Keyloads linked to the announcement are seen by everyone, each of them hides the messages linked
+/// to it from its own non-recipients, whichever keyload a user received last.
+#[test]
+#[cfg(not(feature = "async"))]
+fn private_links_keyed_to_their_keyload() {
+    let transport = iota_streams_app::transport::new_shared_transport(crate::api::tangle::BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let mut subscriberA = Subscriber::new("SUBSCRIBERA9SEED", transport.clone());
+    let mut subscriberB = Subscriber::new("SUBSCRIBERB9SEED", transport.clone());
+    let mut subscriberC = Subscriber::new("SUBSCRIBERC9SEED", transport);
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+    let links = |msgs: Vec<UnwrappedMessage>| msgs.into_iter().map(|msg| msg.link).collect::<Vec<_>>();
+
+    author.set_private_links(true).unwrap();
+    let announcement_link = author.send_announce().unwrap();
+    for subscriber in [&mut subscriberA, &mut subscriberB, &mut subscriberC].iter_mut() {
+        subscriber.receive_announcement(&announcement_link).unwrap();
+        let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+        author.receive_subscribe(&subscribe_link).unwrap();
+    }
+    let idA = Identifier::from(*subscriberA.get_public_key());
+    let idB = Identifier::from(*subscriberB.get_public_key());
+    let (keyload1_link, _) = author.send_keyload(&announcement_link, &[idA, idB]).unwrap();
+    let (keyload2_link, _) = author.send_keyload(&announcement_link, &[idA]).unwrap();
+
+    assert_eq!(
+        links(subscriberA.fetch_all_next_msgs()),
+        vec![keyload1_link.clone(), keyload2_link.clone()]
+    );
+    assert_eq!(
+        links(subscriberB.fetch_all_next_msgs()),
+        vec![keyload1_link.clone(), keyload2_link.clone()]
+    );
+    assert_eq!(
+        links(subscriberC.fetch_all_next_msgs()),
+        vec![keyload1_link.clone(), keyload2_link.clone()]
+    );
+
+    let (packetA_link, _) = subscriberA
+        .send_signed_packet(&keyload2_link, &payload, &payload)
+        .unwrap();
+    let (packetB_link, _) = subscriberB
+        .send_signed_packet(&keyload1_link, &payload, &payload)
+        .unwrap();
+
+    let mut found = links(author.fetch_all_next_msgs());
+    found.sort_by_key(|link| link.to_string());
+    let mut expected = vec![packetA_link.clone(), packetB_link.clone()];
+    expected.sort_by_key(|link| link.to_string());
+    assert_eq!(found, expected);
+    assert_eq!(links(subscriberA.fetch_all_next_msgs()), vec![packetB_link]);
+    assert!(subscriberB.fetch_all_next_msgs().is_empty());
+    assert!(subscriberC.fetch_all_next_msgs().is_empty());
+
+    // Receiving the older keyload again does not hide the messages linked to the newer one
+    subscriberA.receive_keyload(&keyload1_link).unwrap();
+    let (packet_link, _) = author.send_signed_packet(&packetA_link, &payload, &payload).unwrap();
+    assert_eq!(links(subscriberA.fetch_all_next_msgs()), vec![packet_link]);
+    assert!(subscriberB.fetch_all_next_msgs().is_empty());
+    assert!(subscriberC.fetch_all_next_msgs().is_empty());
+}
+
+#[test]
+#[cfg(all(not(feature = "async"), feature = "std"))]
+fn run_basic_scenario_over_files() {
+    let dir = std::env::temp_dir().join(format!("streams-file-transport-{}", std::process::id()));
+    let transport = iota_streams_app::transport::FileTransport::new(&dir).unwrap();
+    let r = example(transport);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(dbg!(r).is_ok());
+}
+
+#[test]
+#[cfg(all(feature = "async", feature = "std"))]
+fn run_basic_scenario_over_files() {
+    let dir = std::env::temp_dir().join(format!("streams-file-transport-async-{}", std::process::id()));
+    let transport = iota_streams_app::transport::FileTransport::new(&dir).unwrap();
+    let r = smol::block_on(example(transport));
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(dbg!(r).is_ok());
+}
+
+#[cfg(all(test, feature = "std"))]
+fn layered_transport(
+    metrics: iota_streams_core::prelude::Arc<iota_streams_app::transport::layer::BasicMetrics>,
+) -> impl Transport {
+    use core::time::Duration;
+    use iota_streams_app::transport::{
+        clock::SystemClock,
+        layer::*,
+    };
+    use iota_streams_core::prelude::Arc;
+    let retry_policy = RetryPolicy::new(2, Duration::from_millis(1), Duration::from_millis(10));
+    let layers = crate::api::tangle::BucketTransport::new()
+        .with_layer(CacheLayer::new(64, Duration::from_secs(10), Arc::new(SystemClock)))
+        .with_layer(RetryLayer::new(retry_policy))
+        .with_layer(MetricsLayer::new(metrics));
+    iota_streams_app::transport::new_shared_transport(layers)
+}
+
+#[test]
+#[cfg(all(not(feature = "async"), feature = "std"))]
+fn run_basic_scenario_over_layers() {
+    use iota_streams_app::transport::layer::*;
+    let metrics = iota_streams_core::prelude::Arc::new(BasicMetrics::new());
+    assert!(dbg!(example(layered_transport(metrics.clone()))).is_ok());
+    assert!(metrics.get(Operation::Send).calls() > 0);
+    assert!(metrics.get(Operation::Recv).calls() > 0);
+}
+
+#[test]
+#[cfg(all(feature = "async", feature = "std"))]
+fn run_basic_scenario_over_layers() {
+    use iota_streams_app::transport::layer::*;
+    let metrics = iota_streams_core::prelude::Arc::new(BasicMetrics::new());
+    assert!(dbg!(smol::block_on(example(layered_transport(metrics.clone())))).is_ok());
+    assert!(metrics.get(Operation::Send).calls() > 0);
+    assert!(metrics.get(Operation::Recv).calls() > 0);
+}
+
+/// Subscriber waits for a packet the author publishes while the subscriber is already waiting.
+#[cfg(all(test, feature = "async"))]
+async fn await_next_msgs_example<T: Transport + TransportSubscribe<Address>>(transport: T) -> Result<()> {
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", transport);
+
+    let announcement_link = author.send_announce().await?;
+    subscriber.receive_announcement(&announcement_link).await?;
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (msgs, sent) = futures::future::join(
+        subscriber.await_next_msgs(),
+        author.send_signed_packet(&announcement_link, &public_payload, &masked_payload),
+    )
+    .await;
+    let (signed_packet_link, _) = sent?;
+    let msgs = msgs?;
+    ensure!(
+        msgs.len() == 1 && msgs[0].link == signed_packet_link,
+        "bad awaited messages"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn run_await_next_msgs() {
+    let transport = iota_streams_app::transport::new_shared_transport(crate::api::tangle::BucketTransport::new());
+    assert!(dbg!(smol::block_on(await_next_msgs_example(transport))).is_ok());
+}
+
+/// Two mirrored buckets, merged reads are deduplicated.
+#[cfg(test)]
+fn multi_transport() -> crate::api::tangle::MultiTransport {
+    use iota_streams_app::transport::*;
+    crate::api::tangle::MultiTransport::new(WritePolicy::All, ReadPolicy::Merge)
+        .with_transport(new_shared_transport(crate::api::tangle::BucketTransport::new()))
+        .with_transport(new_shared_transport(crate::api::tangle::BucketTransport::new()))
+}
+
+#[test]
+#[cfg(not(feature = "async"))]
+fn run_basic_scenario_over_multi_transport() {
+    assert!(dbg!(example(multi_transport())).is_ok());
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn run_basic_scenario_over_multi_transport() {
+    assert!(dbg!(smol::block_on(example(multi_transport()))).is_ok());
+}
+
+/// Channel futures can be moved to and driven from another thread.
+#[test]
+#[cfg(all(feature = "async-send", feature = "std"))]
+fn run_basic_scenario_on_another_thread() {
+    let transport = iota_streams_app::transport::new_shared_transport(crate::api::tangle::BucketTransport::new());
+    let scenario = example(crate::api::tangle::Sendable(transport));
+    let r = std::thread::spawn(move || smol::block_on(scenario)).join().unwrap();
+    assert!(dbg!(r).is_ok());
+}
+
+/// A header announcing a previous message link of the wrong length is rejected without panicking.
+#[test]
+#[cfg(not(feature = "async"))]
+fn reject_malformed_previous_msg_link() {
+    use core::cell::RefCell;
+    use iota_streams_app::message::{
+        PreparedMessage,
+        HDF,
+    };
+    use iota_streams_ddml::link_store::EmptyLinkStore;
+
+    let mut transport = iota_streams_app::transport::new_shared_transport(crate::api::tangle::BucketTransport::new());
+    let link = Address::default();
+    let header = HDF::new(link.clone())
+        .with_content_type(crate::message::SIGNED_PACKET)
+        .unwrap()
+        .with_previous_msg_link(Bytes(vec![0; 3]));
+    let store = RefCell::new(EmptyLinkStore::<DefaultF, MsgId, ()>::default());
+    let sender_id = header.sender_id;
+    let wrapped = PreparedMessage::new(store.borrow(), header, sender_id).wrap().unwrap();
+    transport.send_message(&Message::new(wrapped.message)).unwrap();
+
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", transport);
+    let e = subscriber.fetch_prev_msg(&link).err().unwrap();
+    assert!(matches!(
+        e.downcast_ref::<iota_streams_core::Errors>(),
+        Some(LengthMismatch(52, 3))
+    ));
+}
+
+/// Messages with a malformed previous message link published beside the genuine message are skipped.
+#[test]
+#[cfg(not(feature = "async"))]
+fn skip_malformed_previous_msg_link_candidates() {
+    use core::cell::RefCell;
+    use iota_streams_app::message::{
+        PreparedMessage,
+        HDF,
+    };
+    use iota_streams_ddml::link_store::EmptyLinkStore;
+
+    let mut published = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, published.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+    let announcement_link = author.send_announce().unwrap();
+    let (prev_packet_link, _) = author
+        .send_signed_packet(&announcement_link, &payload, &payload)
+        .unwrap();
+    let (packet_link, _) = author
+        .send_signed_packet(&prev_packet_link, &payload, &payload)
+        .unwrap();
+
+    let header = HDF::new(packet_link.clone())
+        .with_content_type(crate::message::SIGNED_PACKET)
+        .unwrap()
+        .with_previous_msg_link(Bytes(vec![0; 3]));
+    let store = RefCell::new(EmptyLinkStore::<DefaultF, MsgId, ()>::default());
+    let sender_id = header.sender_id;
+    let junk = PreparedMessage::new(store.borrow(), header, sender_id).wrap().unwrap();
+    let mut transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    for link in [&announcement_link, &prev_packet_link].iter() {
+        relay_after_junk(&mut published, &mut transport, link, &[]);
+    }
+    relay_after_junk(&mut published, &mut transport, &packet_link, &[junk.message.body.bytes]);
+
+    let mut subscriber = Subscriber::new("SUBSCRIBERA9SEED", transport);
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let prev_msg = subscriber.fetch_prev_msg(&packet_link).unwrap();
+    assert!(prev_msg.link == prev_packet_link);
+}
+
+#[test]
+#[cfg(not(feature = "async"))]
+fn reject_packets_with_expired_timestamp() {
+    use core::time::Duration;
+    use iota_streams_core::prelude::Arc;
+
+    let transport = iota_streams_app::transport::new_shared_transport(crate::api::tangle::BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9SEED", transport);
+    let author_clock = Arc::new(ManualClock::new(1_600_000_000_000));
+    author.set_clock(author_clock);
+    author.set_packet_timestamps(true);
+    let subscriber_clock = Arc::new(ManualClock::new(1_600_000_000_000));
+    subscriber.set_clock(subscriber_clock.clone());
+    subscriber.set_freshness_policy(
+        FreshnessPolicy::new()
+            .with_required_timestamp(true)
+            .with_max_clock_skew(Duration::from_secs(1))
+            .with_max_age(Duration::from_secs(10)),
+    );
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    let (packet_link, _) = author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+
+    subscriber_clock.advance(Duration::from_secs(5));
+    let msgs = subscriber.fetch_all_next_msgs();
+    let published_at: Vec<_> = msgs
+        .iter()
+        .map(|msg| msg.body.metadata.as_ref().and_then(|metadata| metadata.published_at))
+        .collect();
+    assert_eq!(published_at, vec![None, Some(1_600_000_000_000)]);
+
+    author.send_tagged_packet(&packet_link, &payload, &payload).unwrap();
+    subscriber_clock.advance(Duration::from_secs(55));
+    let results = subscriber.try_fetch_next_msgs();
+    assert_eq!(results.len(), 1);
+    let e = results.into_iter().next().unwrap().err().unwrap();
+    assert!(matches!(
+        e.error.downcast_ref::<iota_streams_core::Errors>(),
+        Some(MsgTimestampExpired(1_600_000_000_000, 1_600_000_060_000))
+    ));
+}
+
+/// A packet rejected by the freshness policy is not committed: it is fetched again once the policy
+/// is relaxed. Untimestamped packets keep the layout of `STREAMS_1_VER`.
+#[test]
+#[cfg(not(feature = "async"))]
+fn rejected_packet_leaves_state_unchanged() {
+    use core::time::Duration;
+    use iota_streams_app::message::{
+        STREAMS_1_VER,
+        STREAMS_2_VER,
+    };
+    use iota_streams_core::prelude::Arc;
+
+    let state = |subscriber: &Subscriber<SharedBucket>| {
+        subscriber
+            .fetch_state()
+            .unwrap()
+            .into_iter()
+            .map(|(id, cursor)| (id, cursor.link, cursor.branch_no, cursor.seq_no))
+            .collect::<Vec<_>>()
+    };
+
+    let cases = vec![(ChannelType::SingleBranch, false), (ChannelType::MultiBranch, true)];
+    for (channel_type, packet_timestamps) in cases {
+        let mut transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+        let mut author = Author::new("AUTHOR9SEED", channel_type, transport.clone());
+        let mut subscriber = Subscriber::new("SUBSCRIBER9SEED", transport.clone());
+        author.set_clock(Arc::new(ManualClock::new(1_600_000_000_000)));
+        author.set_packet_timestamps(packet_timestamps);
+        let subscriber_clock = Arc::new(ManualClock::new(1_600_000_000_000));
+        subscriber.set_clock(subscriber_clock.clone());
+        subscriber.set_freshness_policy(
+            FreshnessPolicy::new()
+                .with_required_timestamp(true)
+                .with_max_age(Duration::from_secs(10)),
+        );
+        let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+        let announcement_link = author.send_announce().unwrap();
+        subscriber.receive_announcement(&announcement_link).unwrap();
+        let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+        author.receive_subscribe(&subscribe_link).unwrap();
+        let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+        assert_eq!(subscriber.fetch_all_next_msgs().len(), 1);
+        let (packet_link, _) = author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+        let version = transport
+            .recv_message(&packet_link)
+            .unwrap()
+            .binary
+            .parse_header()
+            .unwrap()
+            .header
+            .version;
+        let (expected_version, published_at) = match packet_timestamps {
+            true => (STREAMS_2_VER, Some(1_600_000_000_000)),
+            false => (STREAMS_1_VER, None),
+        };
+        assert!(version == expected_version);
+
+        subscriber_clock.advance(Duration::from_secs(60));
+        let before = state(&subscriber);
+        let is_stale = |e: &iota_streams_core::Error| {
+            matches!(
+                e.downcast_ref::<iota_streams_core::Errors>(),
+                Some(MissingMsgTimestamp) | Some(MsgTimestampExpired(1_600_000_000_000, 1_600_000_060_000))
+            )
+        };
+        assert!(is_stale(&subscriber.receive_signed_packet(&packet_link).err().unwrap()));
+        assert!(state(&subscriber) == before);
+        let results = subscriber.try_fetch_next_msgs();
+        assert_eq!(results.len(), 1);
+        assert!(is_stale(&results.into_iter().next().unwrap().err().unwrap().error));
+        assert!(state(&subscriber) == before);
+
+        subscriber.set_freshness_policy(FreshnessPolicy::default());
+        let msgs = subscriber.fetch_all_next_msgs();
+        assert_eq!(msgs.len(), 1);
+        assert!(msgs[0].link == packet_link);
+        assert_eq!(
+            msgs[0]
+                .body
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.published_at),
+            published_at
+        );
+    }
+}
+
+/// A failure to send the sequence message of a packet leaves the publisher state untouched and the
+/// packet staged: no other message can be sent until it is retried, which publishes the same
+/// packet rather than a second one at its link.
+#[test]
+#[cfg(not(feature = "async"))]
+fn keep_state_when_sequence_send_fails() {
+    use core::time::Duration;
+    use iota_streams_core::prelude::Arc;
+
+    let mut transport = FaultyTransport::default();
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9SEED", transport.clone());
+    let clock = Arc::new(ManualClock::new(1_600_000_000_000));
+    author.set_clock(clock.clone());
+    author.set_packet_timestamps(true);
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+
+    let state = |author: &Author<FaultyTransport>| {
+        author
+            .fetch_state()
+            .unwrap()
+            .into_iter()
+            .map(|(id, cursor)| (id, cursor.link, cursor.seq_no))
+            .collect::<Vec<_>>()
+    };
+    let before = state(&author);
+    // The packet is sent, its sequence message is not
+    transport.sends_left.set(Some(1));
+    assert!(author.send_signed_packet(&keyload_link, &payload, &payload).is_err());
+    assert_eq!(state(&author), before);
+    assert!(author.is_staged());
+
+    transport.sends_left.set(None);
+    clock.advance(Duration::from_secs(5));
+    let e = author
+        .send_tagged_packet(&keyload_link, &payload, &payload)
+        .err()
+        .unwrap();
+    assert!(matches!(
+        e.downcast_ref::<iota_streams_core::Errors>(),
+        Some(StagedMessagePending)
+    ));
+    assert_eq!(state(&author), before);
+
+    let (packet_link, seq_link) = author.retry_staged().unwrap();
+    assert!(seq_link.is_some());
+    assert!(!author.is_staged());
+    assert_eq!(transport.recv_messages(&packet_link).unwrap().len(), 1);
+    let msgs = subscriber.fetch_all_next_msgs();
+    assert_eq!(
+        msgs.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![keyload_link, packet_link]
+    );
+    assert_eq!(
+        msgs[1]
+            .body
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.published_at),
+        Some(1_600_000_000_000)
+    );
+    assert!(author.retry_staged().is_err());
+}
+
+/// A staged message none of whose frames has been sent can be discarded, the next message takes
+/// its link.
+#[test]
+#[cfg(not(feature = "async"))]
+fn discard_unsent_staged_message() {
+    let transport = FaultyTransport::default();
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::SingleBranch, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9SEED", transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+
+    transport.sends_left.set(Some(0));
+    assert!(author.send_signed_packet(&keyload_link, &payload, &payload).is_err());
+    assert!(author.discard_staged());
+    assert!(!author.discard_staged());
+
+    transport.sends_left.set(None);
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &payload, &payload).unwrap();
+    let msgs = subscriber.fetch_all_next_msgs();
+    assert_eq!(msgs.len(), 2);
+    assert!(msgs[1].link == packet_link);
+    assert!(matches!(msgs[1].body.content, MessageContent::TaggedPacket { .. }));
+}
+
+/// Messages sent while offline are committed and published later in order, also after the
+/// publisher state and outbox have been persisted.
+#[test]
+#[cfg(not(feature = "async"))]
+fn publish_outbox_after_going_online() {
+    use core::sync::atomic::{
+        AtomicU64,
+        Ordering,
+    };
+    use iota_streams_core::prelude::Arc;
+
+    let transport = FaultyTransport::default();
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9SEED", transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+
+    author.set_offline(true);
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    let (packet_link, _) = author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+    assert_eq!(author.outbox().len(), 4);
+    assert!(subscriber.fetch_all_next_msgs().is_empty());
+
+    // The outbox is exported along with the state
+    let state = author.export("PASSWORD").unwrap();
+    let mut author = Author::import(&state, "PASSWORD", transport.clone()).unwrap();
+    assert_eq!(author.outbox().len(), 4);
+    assert!(!author.is_offline());
+
+    let slept_ms = Arc::new(AtomicU64::new(0));
+    let recorded = slept_ms.clone();
+    author.set_retry_sleep(Arc::new(move |backoff| {
+        recorded.fetch_add(backoff.as_millis() as u64, Ordering::SeqCst);
+    }));
+    transport.sends_left.set(Some(1));
+    assert!(author.flush_outbox(RetryPolicy::default()).is_err());
+    assert_eq!(author.outbox().len(), 3);
+    assert_eq!(slept_ms.load(Ordering::SeqCst), 100 + 200 + 400);
+
+    transport.sends_left.set(None);
+    assert_eq!(author.flush_outbox(RetryPolicy::default()).unwrap(), 3);
+    assert!(author.outbox().is_empty());
+
+    let msgs = subscriber.fetch_all_next_msgs();
+    assert_eq!(
+        msgs.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![keyload_link, packet_link]
+    );
+}
+
+#[test]
+#[cfg(all(not(feature = "async"), feature = "std"))]
+fn resume_from_state_store_after_crash() {
+    use iota_streams_core::prelude::Box;
+
+    let path = std::env::temp_dir().join(format!("streams-state-{}.bin", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9SEED", transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+    // Every few operations the saved changes are replaced by a snapshot
+    let store = FileStateStore::open(&path, "PASSWORD")
+        .unwrap()
+        .with_snapshot_interval(3);
+    author.set_state_store(Box::new(store)).unwrap();
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    let (packet_link, _) = author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+    author.set_offline(true);
+    let (queued_packet_link, _) = author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+    drop(author);
+
+    // Resume without export: same seed, state and outbox restored from the store
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport);
+    let store = FileStateStore::open(&path, "PASSWORD").unwrap();
+    author.set_state_store(Box::new(store)).unwrap();
+    assert_eq!(author.outbox().len(), 2);
+    assert_eq!(author.flush_outbox(RetryPolicy::default()).unwrap(), 2);
+    let (next_packet_link, _) = author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+
+    let msgs = subscriber.fetch_all_next_msgs();
+    assert_eq!(
+        msgs.iter().map(|msg| msg.link.clone()).collect::<Vec<_>>(),
+        vec![keyload_link, packet_link, queued_packet_link, next_packet_link]
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+/// State of a user as (publisher, link, sequence number) triples, in any order.
+#[cfg(all(test, not(feature = "async"), feature = "std"))]
+fn same_state<Id: PartialEq>(a: &[(Id, Cursor<Address>)], b: &[(Id, Cursor<Address>)]) -> bool {
+    a.len() == b.len()
+        && a.iter().all(|(id, cursor)| {
+            b.iter()
+                .any(|(other_id, other)| id == other_id && cursor.link == other.link && cursor.seq_no == other.seq_no)
+        })
+}
+
+/// A save cut short by a crash is dropped, the state saved before it is restored.
+#[test]
+#[cfg(all(not(feature = "async"), feature = "std"))]
+fn recover_state_store_with_truncated_tail() {
+    use iota_streams_core::prelude::Box;
+
+    let path = std::env::temp_dir().join(format!("streams-state-truncated-{}.bin", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9SEED", transport.clone());
+    let payload = Bytes("PAYLOAD".as_bytes().to_vec());
+    let store = FileStateStore::open(&path, "PASSWORD").unwrap();
+    author.set_state_store(Box::new(store)).unwrap();
+
+    let announcement_link = author.send_announce().unwrap();
+    subscriber.receive_announcement(&announcement_link).unwrap();
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).unwrap();
+    author.receive_subscribe(&subscribe_link).unwrap();
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).unwrap();
+    let state = author.fetch_state().unwrap();
+    let saved_len = std::fs::metadata(&path).unwrap().len();
+    author.send_signed_packet(&keyload_link, &payload, &payload).unwrap();
+    drop(author);
+
+    // Crash while the packet was being saved
+    let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_len(saved_len + 10).unwrap();
+    drop(file);
+
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport);
+    let store = FileStateStore::open(&path, "PASSWORD").unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), saved_len);
+    author.set_state_store(Box::new(store)).unwrap();
+    assert!(same_state(&author.fetch_state().unwrap(), &state));
+    let _ = std::fs::remove_file(&path);
+}
+
+/// A record modified in the state file fails authentication, nothing is restored.
+#[test]
+#[cfg(all(not(feature = "async"), feature = "std"))]
+fn reject_tampered_state_store_record() {
+    use iota_streams_core::prelude::Box;
+
+    let path = std::env::temp_dir().join(format!("streams-state-tampered-{}.bin", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let store = FileStateStore::open(&path, "PASSWORD").unwrap();
+    author.set_state_store(Box::new(store)).unwrap();
+    author.send_announce().unwrap();
+    drop(author);
+
+    let mut bytes = std::fs::read(&path).unwrap();
+    let last = bytes.len() - 40;
+    bytes[last] ^= 1;
+    std::fs::write(&path, &bytes).unwrap();
+
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport);
+    let state = author.fetch_state().unwrap();
+    let store = FileStateStore::open(&path, "PASSWORD").unwrap();
+    assert!(author.set_state_store(Box::new(store)).is_err());
+    assert!(same_state(&author.fetch_state().unwrap(), &state));
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Records are chained, a record removed from the middle of the state file fails authentication.
+#[test]
+#[cfg(all(not(feature = "async"), feature = "std"))]
+fn reject_state_store_with_removed_record() {
+    use iota_streams_core::prelude::Box;
+
+    let path = std::env::temp_dir().join(format!("streams-state-removed-{}.bin", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let store = FileStateStore::open(&path, "PASSWORD").unwrap();
+    author.set_state_store(Box::new(store)).unwrap();
+    let announcement_link = author.send_announce().unwrap();
+    author.send_keyload_for_everyone(&announcement_link).unwrap();
+    drop(author);
+
+    // Drop the first record, which follows the 41 bytes header
+    let mut bytes = std::fs::read(&path).unwrap();
+    let mut len = [0_u8; 4];
+    len.copy_from_slice(&bytes[41..45]);
+    bytes.drain(41..45 + u32::from_be_bytes(len) as usize);
+    std::fs::write(&path, &bytes).unwrap();
+
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport);
+    let state = author.fetch_state().unwrap();
+    let store = FileStateStore::open(&path, "PASSWORD").unwrap();
+    assert!(author.set_state_store(Box::new(store)).is_err());
+    assert!(same_state(&author.fetch_state().unwrap(), &state));
+    let _ = std::fs::remove_file(&path);
+}
+
+/// The state saved by a user is not restored by a user created with another seed.
+#[test]
+#[cfg(all(not(feature = "async"), feature = "std"))]
+fn reject_state_store_of_another_seed() {
+    use iota_streams_core::prelude::Box;
+
+    let path = std::env::temp_dir().join(format!("streams-state-seed-{}.bin", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let transport = iota_streams_app::transport::new_shared_transport(BucketTransport::new());
+    let mut author = Author::new("AUTHOR9SEED", ChannelType::MultiBranch, transport.clone());
+    let store = FileStateStore::open(&path, "PASSWORD").unwrap();
+    author.set_state_store(Box::new(store)).unwrap();
+    author.send_announce().unwrap();
+    drop(author);
+
+    let mut author = Author::new("AUTHORB9SEED", ChannelType::MultiBranch, transport);
+    let state = author.fetch_state().unwrap();
+    let store = FileStateStore::open(&path, "PASSWORD").unwrap();
+    let e = author.set_state_store(Box::new(store)).err().unwrap();
+    assert!(matches!(
+        e.downcast_ref::<iota_streams_core::Errors>(),
+        Some(StateStoreSeedMismatch(_, _))
+    ));
+    assert!(same_state(&author.fetch_state().unwrap(), &state));
+    let _ = std::fs::remove_file(&path);
+}
@@ -1,43 +1,95 @@
+#[cfg(not(feature = "async"))]
+use iota_streams_app::transport::layer::Retry;
+#[cfg(feature = "async")]
+use iota_streams_app::transport::layer::{
+    is_retriable,
+    sleep,
+};
 use iota_streams_app::{
     identifier::Identifier,
-    message::{
-        HasLink as _,
-        LinkGenerator,
-    },
+    message::HasLink as _,
 };
 use iota_streams_core::{
     err,
-    prelude::Vec,
+    prelude::{
+        Arc,
+        Box,
+        Vec,
+    },
     prng,
     psk::{
         Psk,
         PskId,
     },
     try_or,
+    Error,
     Errors::{
+        self,
         ChannelDuplication,
         ChannelNotSingleDepth,
+        MessageContentsNotFound,
+        MessageLinkNotFound,
+        NoStagedMessage,
+        StagedMessagePending,
         UnknownMsgType,
         UserNotRegistered,
     },
     Result,
 };
 
-use super::*;
+#[cfg(feature = "async")]
+use iota_streams_core::Errors::SubscriptionClosed;
+
+use super::{
+    state_store::SavedState,
+    *,
+};
 use crate::{
     api,
     message,
 };
 
-type UserImp = api::user::User<DefaultF, Address, LinkGen, LinkStore, KeyStore>;
+pub(crate) type UserImp = api::user::User<DefaultF, Address, LinkGen, LinkStore, KeyStore>;
 
 const ENCODING: &str = "utf-8";
 const PAYLOAD_LENGTH: usize = 32_000;
 
+/// Message sent along with its sequence message, kept until both are sent so that a failed send can
+/// be retried with the identical bytes.
+struct StagedMessage {
+    /// Frames of the message followed by its sequence message, if any.
+    frames: Vec<BinaryMessage>,
+    /// Number of frames sent so far.
+    sent: usize,
+    wrapped: WrapState,
+    seq: WrappedSequence,
+    info: MsgInfo,
+}
+
 /// Baseline User api object. Contains the api user implementation as well as the transport object
 pub struct User<Trans> {
     pub user: UserImp,
     pub transport: Trans,
+    /// Source of the timestamps of sent messages and of the local time the publish timestamps of
+    /// received packets are checked against.
+    clock: Arc<dyn Clock>,
+    /// Whether sent signed and tagged packets carry an authenticated publish timestamp.
+    packet_timestamps: bool,
+    /// Requirements on the publish timestamps of received packets.
+    freshness: FreshnessPolicy,
+    /// Whether sent messages are queued in the outbox instead of being published.
+    offline: bool,
+    /// Committed messages waiting to be published.
+    outbox: Outbox,
+    /// Wait between the retries of `flush_outbox`, blocks the thread if not set.
+    #[cfg(not(feature = "async"))]
+    retry_sleep: Option<Sleep>,
+    /// Message whose send failed, see `retry_staged`.
+    staged: Option<StagedMessage>,
+    /// Backend persisting the changes of the user state.
+    state_store: Option<Box<dyn StateStore>>,
+    /// User state last saved to the state store.
+    saved_state: SavedState,
 }
 
 impl<Trans> User<Trans> {
@@ -56,13 +108,129 @@ impl<Trans> User<Trans> {
             ENCODING.as_bytes().to_vec(),
             PAYLOAD_LENGTH,
         );
-        Self { user, transport }
+        Self {
+            user,
+            transport,
+            clock: Arc::new(SystemClock),
+            packet_timestamps: false,
+            freshness: FreshnessPolicy::default(),
+            offline: false,
+            outbox: Outbox::new(),
+            #[cfg(not(feature = "async"))]
+            retry_sleep: None,
+            staged: None,
+            state_store: None,
+            saved_state: SavedState::default(),
+        }
     }
 
     pub fn get_transport(&self) -> &Trans {
         &self.transport
     }
 
+    /// Replace the system clock as source of the timestamps of sent messages.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Include the current time of the clock as authenticated publish timestamp in sent signed and
+    /// tagged packets.
+    pub fn set_packet_timestamps(&mut self, enabled: bool) {
+        self.packet_timestamps = enabled;
+    }
+
+    /// Set the requirements on the publish timestamps of received signed and tagged packets.
+    pub fn set_freshness_policy(&mut self, freshness: FreshnessPolicy) {
+        self.freshness = freshness;
+    }
+
+    /// Queue sent messages in the outbox instead of publishing them. Messages are committed as if
+    /// they were published, they can be published later with `flush_outbox`.
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Committed messages waiting to be published.
+    pub fn outbox(&self) -> &Outbox {
+        &self.outbox
+    }
+
+    /// Replace the outbox, eg. with one serialized with `Outbox::to_bytes`.
+    pub fn set_outbox(&mut self, outbox: Outbox) {
+        self.outbox = outbox;
+    }
+
+    /// Wait with `sleep` between the retries of `flush_outbox`, eg. a platform timer without `std`
+    /// or a recorder in tests.
+    #[cfg(not(feature = "async"))]
+    pub fn set_retry_sleep(&mut self, sleep: Sleep) {
+        self.retry_sleep = Some(sleep);
+    }
+
+    /// Persist the user state to `store` from now on. The changes made by each operation are
+    /// saved when it completes: committed messages, advanced cursors, stored keys, channel state
+    /// and outbox. Unlike `export`, the cost does not grow with the channel history.
+    ///
+    /// If the store holds the state saved by a previous instance of the user, eg. before a crash,
+    /// it is restored: the user must have been created with the same seed. Otherwise the whole
+    /// current state is saved to the store.
+    pub fn set_state_store(&mut self, mut store: Box<dyn StateStore>) -> Result<()> {
+        let mut saved_state = SavedState::default();
+        let updates = store.load()?;
+        if updates.is_empty() {
+            let updates = saved_state.changes(&self.user, &self.outbox, true)?;
+            store.save(&updates)?;
+            for update in &updates {
+                saved_state.record(update);
+            }
+        } else {
+            // Nothing is restored from the state of another user
+            for update in &updates {
+                if let StateUpdate::Channel(channel) = update {
+                    channel.check_owner(&self.user)?;
+                }
+            }
+            for update in &updates {
+                update.restore(&mut self.user, &mut self.outbox)?;
+                saved_state.record(update);
+            }
+        }
+        self.user.link_store.borrow_mut().set_journaling(true);
+        self.state_store = Some(store);
+        self.saved_state = saved_state;
+        Ok(())
+    }
+
+    /// Save the changes of the user state since the last save to the state store, then replace
+    /// the saved changes by a snapshot if the store asks for it. Does nothing without a state store.
+    fn save_state(&mut self) -> Result<()> {
+        if let Some(store) = &mut self.state_store {
+            let updates = self.saved_state.changes(&self.user, &self.outbox, false)?;
+            store.save(&updates)?;
+            for update in &updates {
+                self.saved_state.record(update);
+            }
+            self.user.link_store.borrow_mut().clear_journal();
+            if store.wants_snapshot() {
+                let snapshot = SavedState::default().changes(&self.user, &self.outbox, true)?;
+                store.snapshot(&snapshot)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn packet_timestamp(&self) -> Option<u64> {
+        if self.packet_timestamps {
+            Some(self.clock.now())
+        } else {
+            None
+        }
+    }
+
     // Attributes
 
     /// Fetch the Address (application instance) of the channel.
@@ -75,6 +243,28 @@ impl<Trans> User<Trans> {
         self.user.author_public_key()
     }
 
+    /// Channel metadata, if the announcement carries any
+    pub fn channel_metadata(&self) -> Option<&ChannelMetadata> {
+        self.user.channel_metadata()
+    }
+
+    /// Set channel metadata to be published in the announcement, fails once the channel is announced
+    pub fn set_channel_metadata(&mut self, metadata: Option<ChannelMetadata>) -> Result<()> {
+        self.user.set_channel_metadata(metadata)?;
+        self.save_state()
+    }
+
+    /// Whether links of the channel are only known to the readers of the message they link to
+    pub fn has_private_links(&self) -> bool {
+        self.user.has_private_links()
+    }
+
+    /// Announce the channel with private links, fails once the channel is announced
+    pub fn set_private_links(&mut self, enabled: bool) -> Result<()> {
+        self.user.set_private_links(enabled)?;
+        self.save_state()
+    }
+
     /// Return boolean representing the sequencing nature of the channel
     pub fn is_multi_branching(&self) -> bool {
         self.user.is_multi_branching()
@@ -96,7 +286,9 @@ impl<Trans> User<Trans> {
 
     pub fn unregister(&mut self) {
         self.user.appinst = None;
-        self.user.author_sig_pk = None;
+        if !self.user.author_sig_pk_pinned {
+            self.user.author_sig_pk = None;
+        }
     }
 
     // Utility
@@ -110,7 +302,8 @@ impl<Trans> User<Trans> {
     ///   * `link` - Address link to be stored in internal sequence state mapping
     pub fn store_state(&mut self, id: Identifier, link: &Address) -> Result<()> {
         // TODO: assert!(link.appinst == self.appinst.unwrap());
-        self.user.store_state(id, link.msgid.clone())
+        self.user.store_state(id, link.msgid.clone())?;
+        self.save_state()
     }
 
     /// Stores the provided link and sequence number to the internal sequencing state for all participants
@@ -122,7 +315,8 @@ impl<Trans> User<Trans> {
     ///   * `seq_num` - New sequence state to be stored in internal sequence state mapping
     pub fn store_state_for_all(&mut self, link: &Address, seq_num: u32) -> Result<()> {
         // TODO: assert!(link.appinst == self.appinst.unwrap());
-        self.user.store_state_for_all(link.msgid.clone(), seq_num)
+        self.user.store_state_for_all(link.msgid.clone(), seq_num)?;
+        self.save_state()
     }
 
     /// Fetches the latest PublicKey -> Cursor state mapping from the implementation, allowing the
@@ -136,7 +330,8 @@ impl<Trans> User<Trans> {
     /// from scratch
     /// [Subscriber]
     pub fn reset_state(&mut self) -> Result<()> {
-        self.user.reset_state()
+        self.user.reset_state()?;
+        self.save_state()
     }
 
     /// Generate a vector containing the next sequenced message identifier for each publishing
@@ -159,28 +354,299 @@ impl<Trans> User<Trans> {
         self.user.commit_wrapped(wrapped, info)
     }
 
+    /// Advance the own cursor past a sent message and commit its sequence message, if any. Returns
+    /// the link of the sequence message.
+    fn commit_sequence(&mut self, wrapped_sequence: WrappedSequence) -> Result<Option<Address>> {
+        match wrapped_sequence {
+            WrappedSequence::MultiBranch(cursor, WrappedMessage { wrapped, .. }) => {
+                self.user.commit_sequence(cursor, wrapped, MsgInfo::Sequence)
+            }
+            WrappedSequence::SingleBranch(cursor) | WrappedSequence::SingleDepth(cursor) => {
+                self.user.commit_sequence_to_all(cursor)?;
+                Ok(None)
+            }
+            WrappedSequence::None => Ok(None),
+        }
+    }
+
+    /// Stage a wrapped message and its sequence message for sending, no other message may be staged.
+    fn stage(&mut self, msg: WrappedMessage, seq: WrappedSequence, info: MsgInfo) -> Result<()> {
+        try_or!(self.staged.is_none(), StagedMessagePending)?;
+        let mut frames = self.user.split_frames(msg.message)?;
+        if let WrappedSequence::MultiBranch(_, WrappedMessage { message, .. }) = &seq {
+            frames.push(message.clone());
+        }
+        self.staged = Some(StagedMessage {
+            frames,
+            sent: 0,
+            wrapped: msg.wrapped,
+            seq,
+            info,
+        });
+        Ok(())
+    }
+
+    /// Whether a message whose send failed is staged, see `retry_staged`.
+    pub fn is_staged(&self) -> bool {
+        self.staged.is_some()
+    }
+
+    /// Drop the message whose send failed without committing it and return whether one was staged.
+    /// The next message is wrapped at the same link: discard only a message none of whose frames
+    /// has reached the transport, readers would otherwise find both messages at the link.
+    pub fn discard_staged(&mut self) -> bool {
+        self.staged.take().is_some()
+    }
+
+    /// Export the user state encrypted with `pwd`, followed by the outbox serialized with
+    /// `Outbox::to_bytes`. The queued messages are kept as they are to be published, they are not
+    /// encrypted again.
+    #[cfg(feature = "std")]
     pub fn export(&self, flag: u8, pwd: &str) -> Result<Vec<u8>> {
-        self.user.export(flag, pwd)
+        let mut bytes = self.user.export(flag, pwd)?;
+        bytes.extend_from_slice(&self.outbox.to_bytes());
+        Ok(bytes)
+    }
+    #[cfg(feature = "std")]
+    pub fn export_with_kdf(&self, flag: u8, pwd: &str, kdf: &KdfParams) -> Result<Vec<u8>> {
+        let mut bytes = self.user.export_with_kdf(flag, pwd, kdf)?;
+        bytes.extend_from_slice(&self.outbox.to_bytes());
+        Ok(bytes)
+    }
+    pub fn export_with_rng<R>(&self, flag: u8, pwd: &str, kdf: &KdfParams, rng: &mut R) -> Result<Vec<u8>>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut bytes = self.user.export_with_rng(flag, pwd, kdf, rng)?;
+        bytes.extend_from_slice(&self.outbox.to_bytes());
+        Ok(bytes)
     }
     pub fn import(bytes: &[u8], flag: u8, pwd: &str, tsp: Trans) -> Result<Self> {
-        UserImp::import(bytes, flag, pwd).map(|u| Self {
-            user: u,
+        Self::import_with_limits(bytes, flag, pwd, &KdfLimits::default(), tsp)
+    }
+    /// Import a user exported with `export`, the outbox is recovered from the bytes following the
+    /// user state, an export without them recovers an empty outbox.
+    pub fn import_with_limits(bytes: &[u8], flag: u8, pwd: &str, limits: &KdfLimits, tsp: Trans) -> Result<Self> {
+        let (user, state_len) = UserImp::import_prefix_with_limits(bytes, flag, pwd, limits)?;
+        Ok(Self {
+            user,
             transport: tsp,
+            clock: Arc::new(SystemClock),
+            packet_timestamps: false,
+            freshness: FreshnessPolicy::default(),
+            offline: false,
+            outbox: Outbox::from_bytes(&bytes[state_len..])?,
+            #[cfg(not(feature = "async"))]
+            retry_sleep: None,
+            staged: None,
+            state_store: None,
+            saved_state: SavedState::default(),
         })
     }
 
     pub fn store_psk(&mut self, pskid: PskId, psk: Psk, use_psk: bool) -> Result<()> {
-        self.user.store_psk(pskid, psk, use_psk)
+        self.user.store_psk(pskid, psk, use_psk)?;
+        self.save_state()
+    }
+
+    /// Remove a subscriber from the key store, it will not be included in subsequent keyloads
+    /// [Author]
+    ///
+    ///   # Arguments
+    ///   * `pk` - ed25519 Public Key of the subscriber to be removed
+    pub fn remove_subscriber(&mut self, pk: &PublicKey) -> Result<()> {
+        self.user.remove_subscriber(pk)?;
+        self.save_state()
+    }
+
+    /// Remove a pre shared key from the key store, it will not be included in subsequent keyloads
+    /// [Author]
+    ///
+    ///   # Arguments
+    ///   * `pskid` - An identifier representing a pre shared key
+    pub fn remove_psk(&mut self, pskid: PskId) -> Result<()> {
+        self.user.remove_psk(pskid)?;
+        self.save_state()
+    }
+
+    /// Handle the candidate selected among messages published at the same link, see
+    /// `select_candidate`. The other candidates are not committed.
+    fn handle_candidates<T>(
+        &mut self,
+        msgs: Vec<Message>,
+        handle: impl FnOnce(&mut UserImp, Message) -> Result<T>,
+    ) -> Result<T> {
+        let msg = select_candidate(&self.user, msgs)?;
+        let t = handle(&mut self.user, msg)?;
+        self.save_state()?;
+        Ok(t)
+    }
+}
+
+/// Pick the candidate to handle among messages published at the same link without committing any:
+/// the first one that authenticates, else the first one that unwraps without authenticating, such
+/// as a keyload the user has no key in. The other candidates (spam or colliding messages) are
+/// dropped.
+fn select_candidate(user: &UserImp, msgs: Vec<Message>) -> Result<Message> {
+    let mut unauthenticated = None;
+    let mut last_err = None;
+    for msg in msgs {
+        match user.authenticate(&msg.binary) {
+            Ok(true) => return Ok(msg),
+            Ok(false) => {
+                unauthenticated.get_or_insert(msg);
+            }
+            Err(e) => last_err = Some(e),
+        }
+    }
+    match unauthenticated {
+        Some(msg) => Ok(msg),
+        None => last_err.map_or_else(|| err!(MessageContentsNotFound), Err),
     }
+}
 
-    /// Consume a binary sequence message and return the derived message link
-    fn process_sequence(&mut self, msg: BinaryMessage, store: bool) -> Result<Address> {
-        let unwrapped = self.user.handle_sequence(msg, MsgInfo::Sequence, store)?;
-        let msg_link = self.user.link_gen.link_from(
-            unwrapped.body.id.to_bytes(),
-            Cursor::new_at(&unwrapped.body.ref_link, 0, unwrapped.body.seq_num.0 as u32),
+/// Message announced by a sequence message.
+struct Sequenced {
+    /// Link of the announced message.
+    link: Address,
+    /// Link the announced message is attached to.
+    prev_link: Address,
+    /// Publisher of the announced message.
+    sender: Identifier,
+    /// Sequence number of the announced message.
+    seq_num: u64,
+}
+
+impl Sequenced {
+    fn new(user: &UserImp, seq: &message::sequence::ContentUnwrap<Address>) -> Result<Self> {
+        let appinst = match &user.appinst {
+            Some(appinst) => appinst,
+            None => return err!(UserNotRegistered),
+        };
+        let link = user.link_from(
+            seq.id.to_bytes(),
+            Cursor::new_at(&seq.ref_link, 0, seq.seq_num.0 as u32),
         );
-        Ok(msg_link)
+        Ok(Self {
+            link,
+            prev_link: Address::from_base_rel(appinst.base(), &seq.ref_link),
+            sender: seq.id,
+            seq_num: seq.seq_num.0,
+        })
+    }
+
+    /// Whether the header of a candidate published at the announced link matches the sequence
+    /// message, this drops messages replayed there before any is unwrapped.
+    fn announces(&self, msg: &Message) -> bool {
+        match msg.binary.parse_header() {
+            Ok(preparsed) => preparsed.header.sender_id == self.sender && preparsed.header.seq_num.0 == self.seq_num,
+            Err(_) => false,
+        }
+    }
+
+    /// Message reported in place of an announced packet the user has no access to. The headers of
+    /// the candidates published at its link are not authenticated, only what the sequence message
+    /// tells is reported.
+    fn unreadable(self) -> UnwrappedMessage {
+        UnwrappedMessage::new(self.link, self.prev_link, UnwrappedBody::unreadable())
+    }
+}
+
+/// Read the message announced by a binary sequence message without committing it
+fn read_sequence(user: &UserImp, msg: &BinaryMessage) -> Result<Sequenced> {
+    let seq = user.unwrap_sequence(msg.parse_header()?)?;
+    Sequenced::new(user, &seq.pcf.content)
+}
+
+/// Consume a binary sequence message and return the message it announces
+fn process_sequence(user: &mut UserImp, msg: BinaryMessage, store: bool) -> Result<Sequenced> {
+    let seq = user.handle_sequence(msg, MsgInfo::Sequence, store)?.body;
+    Sequenced::new(user, &seq)
+}
+
+/// Consume the binary sequence messages leading to a message, in order
+fn process_sequences(user: &mut UserImp, msgs: Vec<Message>, store: bool) -> Result<()> {
+    for msg in msgs {
+        process_sequence(user, msg.binary, store)?;
+    }
+    Ok(())
+}
+
+/// Keep the candidates published at the link announced by a sequence message which match it and
+/// return whether one of them authenticates, see `User::select_message`.
+fn authenticate_announced(
+    user: &UserImp,
+    sequenced: &Sequenced,
+    mut msgs: Vec<Message>,
+) -> Result<(bool, Option<Vec<Message>>)> {
+    msgs.retain(|msg| sequenced.announces(msg));
+    try_or!(!msgs.is_empty(), MessageContentsNotFound)?;
+    let authenticated = msgs.iter().any(|msg| user.authenticate(&msg.binary).unwrap_or(false));
+    Ok((authenticated, Some(msgs)))
+}
+
+/// Handle a message of any content type but sequence, confirmed by a milestone at
+/// `milestone_timestamp` if any.
+fn handle_content(user: &mut UserImp, msg: Message, milestone_timestamp: Option<u64>) -> Result<UnwrappedMessage> {
+    let preparsed = msg.binary.parse_header()?;
+    let link = preparsed.header.link.clone();
+    let prev_link = TangleAddress::from_bytes(&preparsed.header.previous_msg_link.0)?;
+    let mut metadata = MessageMetadata::new(&preparsed.header, msg.timestamp, milestone_timestamp);
+    let msg = msg.binary;
+    let content = match metadata.content_type {
+        message::SIGNED_PACKET => {
+            let (pk, public, masked, timestamp) = user.handle_signed_packet(msg, MsgInfo::SignedPacket)?.body;
+            metadata.published_at = timestamp;
+            MessageContent::new_signed_packet(pk, public, masked)
+        }
+        message::TAGGED_PACKET => {
+            let (public, masked, timestamp) = user.handle_tagged_packet(msg, MsgInfo::TaggedPacket)?.body;
+            metadata.published_at = timestamp;
+            MessageContent::new_tagged_packet(public, masked)
+        }
+        message::KEYLOAD => {
+            // So long as the unwrap has not failed, we will return a blank object to
+            // inform the user that a message was present, even if the use wasn't part of
+            // the keyload itself. This is to prevent sequencing failures
+            let (_allowed, recipients) = user.handle_keyload(msg, MsgInfo::Keyload)?.body;
+            // TODO: Verify content, whether user is allowed or not!
+            MessageContent::new_keyload(recipients)
+        }
+        message::UNSUBSCRIBE => {
+            user.handle_unsubscribe(msg, MsgInfo::Unsubscribe)?;
+            MessageContent::new_unsubscribe()
+        }
+        unknown_content => return err!(UnknownMsgType(unknown_content)),
+    };
+    Ok(UnwrappedMessage::new(
+        link,
+        prev_link,
+        UnwrappedBody::new(metadata, content),
+    ))
+}
+
+/// Whether the transport has reported that nothing has been published at a link.
+fn is_link_not_found(e: &Error) -> bool {
+    matches!(e.downcast_ref::<Errors>(), Some(MessageLinkNotFound(_)))
+}
+
+/// Check the publish timestamp of a signed or tagged packet against the `freshness` policy at local
+/// time `now` without committing the packet, other messages pass.
+fn check_freshness(user: &UserImp, msg: &BinaryMessage, freshness: &FreshnessPolicy, now: u64) -> Result<()> {
+    match msg.parse_header()?.header.content_type {
+        message::SIGNED_PACKET | message::TAGGED_PACKET if *freshness != FreshnessPolicy::default() => {
+            freshness.check(user.packet_timestamp(msg)?, now)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn fetch_error(id: Identifier, link: &Address, kind: FetchErrorKind, error: Error) -> FetchError {
+    FetchError {
+        id,
+        link: link.clone(),
+        kind,
+        error,
     }
 }
 
@@ -188,39 +654,97 @@ impl<Trans> User<Trans> {
 impl<Trans: Transport + Clone> User<Trans> {
     // Send
 
-    /// Send a message with sequencing logic. If channel is single-branched, then no secondary
-    /// sequence message is sent and None is returned for the address.
-    fn send_sequence(&mut self, wrapped_sequence: WrappedSequence) -> Result<Option<Address>> {
-        match wrapped_sequence {
-            WrappedSequence::MultiBranch(
-                cursor,
-                WrappedMessage {
-                    wrapped: wrapped_state,
-                    message,
-                },
-            ) => {
-                self.transport.send_message(&Message::new(message))?;
-                self.user.commit_sequence(cursor, wrapped_state, MsgInfo::Sequence)
-            }
-            WrappedSequence::SingleBranch(cursor) => {
-                self.user.commit_sequence_to_all(cursor)?;
-                Ok(None)
+    /// Send a message without using sequencing logic. Reserved for Announce and Subscribe messages
+    fn send_message(&mut self, msg: WrappedMessage, info: MsgInfo) -> Result<Address> {
+        try_or!(self.staged.is_none(), StagedMessagePending)?;
+        self.publish(Message::with_clock(msg.message, &*self.clock))?;
+        let link = self.commit_wrapped(msg.wrapped, info)?;
+        self.save_state()?;
+        Ok(link)
+    }
+
+    /// Send the frames of the staged message not sent yet, then commit it along with its sequence
+    /// message and return their links. On failure the message stays staged: a new send returns
+    /// `StagedMessagePending` until it is sent with this method or dropped with `discard_staged`.
+    pub fn retry_staged(&mut self) -> Result<(Address, Option<Address>)> {
+        let mut staged = match self.staged.take() {
+            Some(staged) => staged,
+            None => return err!(NoStagedMessage),
+        };
+        while staged.sent < staged.frames.len() {
+            let frame = Message::with_clock(staged.frames[staged.sent].clone(), &*self.clock);
+            if let Err(e) = self.publish(frame) {
+                self.staged = Some(staged);
+                return Err(e);
             }
-            WrappedSequence::SingleDepth(cursor) => {
-                self.user.commit_sequence_to_all(cursor)?;
-                Ok(None)
+            staged.sent += 1;
+        }
+        let msg_link = self.commit_wrapped(staged.wrapped, staged.info)?;
+        let seq_link = self.commit_sequence(staged.seq)?;
+        self.save_state()?;
+        Ok((msg_link, seq_link))
+    }
+
+    /// Send a message to the transport, or queue it in the outbox while offline or while older
+    /// messages are waiting to be published.
+    fn publish(&mut self, msg: Message) -> Result<()> {
+        if self.offline || !self.outbox.is_empty() {
+            self.outbox.push(msg);
+            Ok(())
+        } else {
+            self.transport.send_message(&msg)
+        }
+    }
+
+    /// Publish the messages queued in the outbox in order, retrying failed sends according to
+    /// `policy`. Stops at the first message which cannot be sent, it and the following messages
+    /// are kept in the outbox. Returns the number of published messages.
+    pub fn flush_outbox(&mut self, policy: RetryPolicy) -> Result<usize> {
+        let mut transport = Retry::new(self.transport.clone(), policy);
+        if let Some(sleep) = &self.retry_sleep {
+            transport = transport.with_sleep(sleep.clone());
+        }
+        let mut published = 0;
+        let mut result = Ok(());
+        while let Some(msg) = self.outbox.front() {
+            result = transport.send_message(msg);
+            if result.is_err() {
+                break;
             }
-            WrappedSequence::None => Ok(None),
+            self.outbox.pop_front();
+            published += 1;
         }
+        self.save_state()?;
+        result.map(|_| published)
     }
 
-    /// Send a message without using sequencing logic. Reserved for Announce and Subscribe messages
-    fn send_message(&mut self, msg: WrappedMessage, info: MsgInfo) -> Result<Address> {
-        self.transport.send_message(&Message::new(msg.message))?;
-        self.commit_wrapped(msg.wrapped, info)
+    /// Receive every message published at `link`, each joined back with its continuation frames.
+    /// Frame links are only queried as far as a candidate needs them and until one of them is
+    /// empty, see `UserImp::join_frames` for how frames are selected. Candidates which cannot be
+    /// parsed or whose frames are missing are dropped.
+    fn recv_candidates(&mut self, link: &Address) -> Result<Vec<Message>> {
+        let mut frames: Vec<Vec<Message>> = Vec::new();
+        let mut candidates = Vec::new();
+        for mut msg in self.transport.recv_messages(link)? {
+            let frame_count = match msg.binary.parse_header() {
+                Ok(preparsed) => preparsed.header.payload_frame_count,
+                Err(_) => continue,
+            };
+            while frames.len() + 1 < frame_count as usize && !matches!(frames.last(), Some(found) if found.is_empty()) {
+                let frame_link = self.user.frame_link(link, frames.len() as u32 + 2);
+                frames.push(self.transport.recv_messages(&frame_link).unwrap_or_default());
+            }
+            let found = frames.iter().map(|found| found.iter().map(|frame| &frame.binary));
+            if self.user.join_frames(&mut msg.binary, frame_count, found).is_ok() {
+                candidates.push(msg);
+            }
+        }
+        Ok(candidates)
     }
 
-    /// Send a message using sequencing logic.
+    /// Send a message using sequencing logic. The message and its sequence message are both sent
+    /// before any state is committed: if either send fails, the own cursor and the link store are
+    /// left as they were and the message stays staged, see `retry_staged`.
     ///
     /// # Arguments
     /// * `msg` - Wrapped Message ready for sending
@@ -232,14 +756,12 @@ impl<Trans: Transport + Clone> User<Trans> {
         ref_link: &MsgId,
         info: MsgInfo,
     ) -> Result<(Address, Option<Address>)> {
-        // Send & commit original message
-        self.transport.send_message(&Message::new(msg.message))?;
-        let msg_link = self.commit_wrapped(msg.wrapped, info)?;
-
-        // Send & commit associated sequence message
+        // Stage the associated sequence message, it only depends on the state preceding the message
         let seq = self.user.wrap_sequence(ref_link)?;
-        let seq_link = self.send_sequence(seq)?;
-        Ok((msg_link, seq_link))
+        self.stage(msg, seq, info)?;
+
+        // Send both messages, then commit them
+        self.retry_staged()
     }
 
     /// Send an announcement message, generating a channel [Author].
@@ -264,7 +786,10 @@ impl<Trans: Transport + Clone> User<Trans> {
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<(Address, Option<Address>)> {
-        let msg = self.user.sign_packet(link_to, public_payload, masked_payload)?;
+        let timestamp = self.packet_timestamp();
+        let msg = self
+            .user
+            .sign_packet(link_to, public_payload, masked_payload, timestamp)?;
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::SignedPacket)
     }
 
@@ -280,7 +805,10 @@ impl<Trans: Transport + Clone> User<Trans> {
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<(Address, Option<Address>)> {
-        let msg = self.user.tag_packet(link_to, public_payload, masked_payload)?;
+        let timestamp = self.packet_timestamp();
+        let msg = self
+            .user
+            .tag_packet(link_to, public_payload, masked_payload, timestamp)?;
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::TaggedPacket)
     }
 
@@ -315,6 +843,15 @@ impl<Trans: Transport + Clone> User<Trans> {
         self.send_message(msg, MsgInfo::Subscribe)
     }
 
+    /// Create and Send an Unsubscribe message to a Channel app instance [Subscriber].
+    ///
+    /// # Arguments
+    /// * `link_to` - Address of the Subscribe message previously sent by the Subscriber
+    pub fn send_unsubscribe(&mut self, link_to: &Address) -> Result<Address> {
+        let msg = self.user.unsubscribe(link_to)?;
+        self.send_message(msg, MsgInfo::Unsubscribe)
+    }
+
     // Receive
 
     /// Receive and process a sequence message [Author, Subscriber].
@@ -322,15 +859,12 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link` - Address of the message to be processed
     pub fn receive_sequence(&mut self, link: &Address) -> Result<Address> {
-        let msg = self.transport.recv_message(link)?;
+        let msgs = self.recv_candidates(link)?;
         if let Some(_addr) = &self.user.appinst {
-            let seq_msg = self.user.handle_sequence(msg.binary, MsgInfo::Sequence, true)?.body;
-            let msg_id = self.user.link_gen.link_from(
-                seq_msg.id.to_bytes(),
-                Cursor::new_at(&seq_msg.ref_link, 0, seq_msg.seq_num.0 as u32),
-            );
-
-            Ok(msg_id)
+            let (msg, _) = self.select_message(msgs)?;
+            let link = process_sequence(&mut self.user, msg.binary, true)?.link;
+            self.save_state()?;
+            Ok(link)
         } else {
             err!(UserNotRegistered)
         }
@@ -341,10 +875,15 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link` - Address of the message to be processed
     pub fn receive_signed_packet(&mut self, link: &Address) -> Result<(PublicKey, Bytes, Bytes)> {
-        let msg = self.transport.recv_message(link)?;
+        let msgs = self.recv_candidates(link)?;
         // TODO: msg.timestamp is lost
-        let m = self.user.handle_signed_packet(msg.binary, MsgInfo::SignedPacket)?;
-        Ok(m.body)
+        let (freshness, now) = (self.freshness, self.clock.now());
+        let m = self.handle_candidates(msgs, |user, msg| {
+            check_freshness(user, &msg.binary, &freshness, now)?;
+            user.handle_signed_packet(msg.binary, MsgInfo::SignedPacket)
+        })?;
+        let (pk, public, masked, _) = m.body;
+        Ok((pk, public, masked))
     }
 
     /// Receive and process a tagged packet message [Author, Subscriber].
@@ -352,9 +891,14 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link` - Address of the message to be processed
     pub fn receive_tagged_packet(&mut self, link: &Address) -> Result<(Bytes, Bytes)> {
-        let msg = self.transport.recv_message(link)?;
-        let m = self.user.handle_tagged_packet(msg.binary, MsgInfo::TaggedPacket)?;
-        Ok(m.body)
+        let msgs = self.recv_candidates(link)?;
+        let (freshness, now) = (self.freshness, self.clock.now());
+        let m = self.handle_candidates(msgs, |user, msg| {
+            check_freshness(user, &msg.binary, &freshness, now)?;
+            user.handle_tagged_packet(msg.binary, MsgInfo::TaggedPacket)
+        })?;
+        let (public, masked, _) = m.body;
+        Ok((public, masked))
     }
 
     /// Receive and process a subscribe message [Author].
@@ -362,9 +906,20 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link` - Address of the message to be processed
     pub fn receive_subscribe(&mut self, link: &Address) -> Result<()> {
-        let msg = self.transport.recv_message(link)?;
+        let msgs = self.recv_candidates(link)?;
         // TODO: Timestamp is lost.
-        self.user.handle_subscribe(msg.binary, MsgInfo::Subscribe)
+        self.handle_candidates(msgs, |user, msg| user.handle_subscribe(msg.binary, MsgInfo::Subscribe))
+    }
+
+    /// Receive and process an unsubscribe message [Author].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub fn receive_unsubscribe(&mut self, link: &Address) -> Result<()> {
+        let msgs = self.recv_candidates(link)?;
+        self.handle_candidates(msgs, |user, msg| {
+            user.handle_unsubscribe(msg.binary, MsgInfo::Unsubscribe)
+        })
     }
 
     /// Receive and Process an announcement message [Subscriber].
@@ -372,8 +927,10 @@ impl<Trans: Transport + Clone> User<Trans> {
     /// # Arguments
     /// * `link_to` - Address of the Channel Announcement message
     pub fn receive_announcement(&mut self, link: &Address) -> Result<()> {
-        let msg = self.transport.recv_message(link)?;
-        self.user.handle_announcement(msg.binary, MsgInfo::Announce)
+        let msgs = self.recv_candidates(link)?;
+        self.handle_candidates(msgs, |user, msg| {
+            user.handle_announcement(msg.binary, MsgInfo::Announce)
+        })
     }
 
     /// Receive and process a keyload message [Subscriber].
@@ -381,9 +938,9 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link` - Address of the message to be processed
     pub fn receive_keyload(&mut self, link: &Address) -> Result<bool> {
-        let msg = self.transport.recv_message(link)?;
-        let m = self.user.handle_keyload(msg.binary, MsgInfo::Keyload)?;
-        Ok(m.body)
+        let msgs = self.recv_candidates(link)?;
+        let m = self.handle_candidates(msgs, |user, msg| user.handle_keyload(msg.binary, MsgInfo::Keyload))?;
+        Ok(m.body.0)
     }
 
     /// Receive and process a message of unknown type. Message will be handled appropriately and
@@ -392,33 +949,48 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///   # Arguments
     ///   * `link` - Address of the message to be processed
     pub fn receive_message(&mut self, link: &Address) -> Result<UnwrappedMessage> {
-        let msg = self.transport.recv_message(link)?;
-        self.handle_message(msg, true)
+        let msgs = self.recv_candidates(link)?;
+        self.handle_messages(msgs, true)
     }
 
     /// Retrieves the next message for each user (if present in transport layer) and returns them [Author, Subscriber]
     pub fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
+        self.try_fetch_next_msgs()
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .collect()
+    }
+
+    /// Retrieves the next message for each user and returns the result per publisher, publishers
+    /// with no new message are left out [Author, Subscriber]
+    pub fn try_fetch_next_msgs(&mut self) -> Vec<FetchResult> {
         let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
-        let mut msgs = Vec::new();
+        let mut results = Vec::new();
 
-        for (
-            _pk,
-            Cursor {
-                link,
-                branch_no: _,
-                seq_no: _,
-            },
-        ) in ids
-        {
-            let msg = self.transport.recv_message(&link);
-
-            if let Ok(msg) = msg {
-                if let Ok(msg) = self.handle_message(msg, true) {
-                    msgs.push(msg);
-                }
+        for (id, cursor) in ids {
+            if let Some(result) = self.fetch_next_msg(id, &cursor.link) {
+                results.push(result);
             }
         }
-        msgs
+        results
+    }
+
+    /// Retrieves the message expected at the next message link of a publisher [Author, Subscriber].
+    /// Returns `None` if nothing has been published at that link yet.
+    ///
+    /// # Arguments
+    /// * `id` - Identifier of the publisher
+    /// * `link` - Next message link of the publisher
+    pub fn fetch_next_msg(&mut self, id: Identifier, link: &Address) -> Option<FetchResult> {
+        let candidates = match self.recv_candidates(link) {
+            Ok(candidates) => candidates,
+            Err(e) if is_link_not_found(&e) => return None,
+            Err(e) => return Some(Err(fetch_error(id, link, FetchErrorKind::Transport, e))),
+        };
+        Some(
+            self.handle_messages(candidates, true)
+                .map_err(|e| fetch_error(id, link, FetchErrorKind::Unwrap, e)),
+        )
     }
 
     /// Retrieves the previous message from the message specified (provided the user has access to it) [Author,
@@ -427,14 +999,32 @@ impl<Trans: Transport + Clone> User<Trans> {
     /// # Arguments
     /// * `link` - Address of message to act as root of previous message fetching
     pub fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
-        let msg = self.transport.recv_message(link)?;
-        let header = msg.binary.parse_header()?.header;
-
-        let prev_msg_link = Address::from_bytes(&header.previous_msg_link.0);
-        let prev_msg = self.transport.recv_message(&prev_msg_link)?;
-        let unwrapped = self.handle_message(prev_msg, false)?;
+        let mut prev_msg_links = Vec::new();
+        let mut last_err = None;
+        for msg in self.recv_candidates(link)? {
+            // Malformed candidates published at the link do not hide the genuine message
+            let prev_msg_link = msg
+                .binary
+                .parse_header()
+                .and_then(|preparsed| Address::from_bytes(&preparsed.header.previous_msg_link.0));
+            match prev_msg_link {
+                Ok(prev_msg_link) if !prev_msg_links.contains(&prev_msg_link) => prev_msg_links.push(prev_msg_link),
+                Ok(_) => {}
+                Err(e) => last_err = Some(e),
+            }
+        }
 
-        Ok(unwrapped)
+        for prev_msg_link in prev_msg_links {
+            let unwrapped = match self.recv_candidates(&prev_msg_link) {
+                Ok(prev_msgs) => self.handle_messages(prev_msgs, false),
+                Err(e) => Err(e),
+            };
+            match unwrapped {
+                Ok(unwrapped) => return Ok(unwrapped),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        last_err.map_or_else(|| err!(MessageContentsNotFound), Err)
     }
 
     /// Retrieves a specified number of previous messages from an original specified messsage link [Author, Subscriber]
@@ -443,25 +1033,15 @@ impl<Trans: Transport + Clone> User<Trans> {
     /// * `link` - Address of message to act as root of previous message fetching
     /// * `max` - The number of msgs to try and parse
     pub fn fetch_prev_msgs(&mut self, link: &Address, max: usize) -> Result<Vec<UnwrappedMessage>> {
-        let mut msg_info: (Address, u8, Message) = self.parse_msg_info(link)?;
-        let mut to_process = Vec::new();
         let mut msgs = Vec::new();
-
+        let mut link = link.clone();
         for _ in 0..max {
-            msg_info = self.parse_msg_info(&msg_info.0)?;
-            if msg_info.1 == message::SEQUENCE {
-                let msg_link = self.process_sequence(msg_info.2.binary, false)?;
-                msg_info = self.parse_msg_info(&msg_link)?;
-            }
-            to_process.push(msg_info.2);
-        }
-
-        to_process.reverse();
-        for msg in to_process {
-            let unwrapped = self.handle_message(msg, false)?;
+            // Only the selected previous message leads further back
+            let unwrapped = self.fetch_prev_msg(&link)?;
+            link = unwrapped.link.clone();
             msgs.push(unwrapped);
         }
-
+        msgs.reverse();
         Ok(msgs)
     }
 
@@ -470,58 +1050,74 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///
     /// # Arguments
     /// * `msg` - Binary message of unknown type
-    /// * `pk` - Optional ed25519 Public Key of the sending participant. None if unknown
-    pub fn handle_message(&mut self, mut msg0: Message, store: bool) -> Result<UnwrappedMessage> {
-        let mut sequenced = false;
-        loop {
-            // Forget TangleMessage and timestamp
-            let msg = msg0.binary;
-            let preparsed = msg.parse_header()?;
-            let link = preparsed.header.link.clone();
-            let prev_link = TangleAddress::from_bytes(&preparsed.header.previous_msg_link.0);
-            match preparsed.header.content_type {
-                message::SIGNED_PACKET => match self.user.handle_signed_packet(msg, MsgInfo::SignedPacket) {
-                    Ok(m) => {
-                        return Ok(m.map(|(id, public, masked)| MessageContent::new_signed_packet(id, public, masked)))
-                    }
-                    Err(e) => match sequenced {
-                        true => return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable())),
-                        false => return Err(e),
-                    },
-                },
-                message::TAGGED_PACKET => match self.user.handle_tagged_packet(msg, MsgInfo::TaggedPacket) {
-                    Ok(m) => return Ok(m.map(|(public, masked)| MessageContent::new_tagged_packet(public, masked))),
-                    Err(e) => match sequenced {
-                        true => return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable())),
-                        false => return Err(e),
-                    },
-                },
-                message::KEYLOAD => {
-                    // So long as the unwrap has not failed, we will return a blank object to
-                    // inform the user that a message was present, even if the use wasn't part of
-                    // the keyload itself. This is to prevent sequencing failures
-                    let m = self.user.handle_keyload(msg, MsgInfo::Keyload)?;
-                    // TODO: Verify content, whether user is allowed or not!
-                    let u = m.map(|_allowed| MessageContent::new_keyload());
-                    return Ok(u);
+    pub fn handle_message(&mut self, msg: Message, store: bool) -> Result<UnwrappedMessage> {
+        self.handle_messages(vec![msg], store)
+    }
+
+    /// Handle the candidate selected among messages of unknown type published at the same link,
+    /// see `select_message`. A selected sequence message is committed along with the message it
+    /// announces, which is reported as unreadable if none of its candidates unwraps.
+    ///
+    /// Packets are checked against the freshness policy and the milestone confirming the message is
+    /// looked up before anything is committed, a rejected packet or a failed lookup leaves the user
+    /// state unchanged.
+    fn handle_messages(&mut self, msgs: Vec<Message>, store: bool) -> Result<UnwrappedMessage> {
+        let (mut msg, mut announced) = self.select_message(msgs)?;
+        let mut sequences = Vec::new();
+        while let Some(msgs) = announced {
+            let sequenced = read_sequence(&self.user, &msg.binary)?;
+            sequences.push(msg);
+            match self.select_message(msgs) {
+                Ok(selected) => {
+                    msg = selected.0;
+                    announced = selected.1;
                 }
-                message::SEQUENCE => {
-                    let msg_link = self.process_sequence(msg, store)?;
-                    let msg = self.transport.recv_message(&msg_link)?;
-                    sequenced = true;
-                    msg0 = msg;
+                // Sequenced packets the user has no access to are reported as unreadable
+                Err(_) => {
+                    process_sequences(&mut self.user, sequences, store)?;
+                    self.save_state()?;
+                    return Ok(sequenced.unreadable());
                 }
-                unknown_content => return err!(UnknownMsgType(unknown_content)),
             }
         }
+        check_freshness(&self.user, &msg.binary, &self.freshness, self.clock.now())?;
+        let milestone_timestamp = self.transport.confirmation_timestamp(&msg)?;
+        process_sequences(&mut self.user, sequences, store)?;
+        let unwrapped = handle_content(&mut self.user, msg, milestone_timestamp)?;
+        self.save_state()?;
+        Ok(unwrapped)
     }
 
-    // Get the previous msg link and msg type from header of message
-    fn parse_msg_info(&mut self, link: &Address) -> Result<(Address, u8, Message)> {
-        let msg = self.transport.recv_message(link)?;
-        let header = msg.binary.parse_header()?.header;
-        let link = Address::from_bytes(&header.previous_msg_link.0);
-        Ok((link, header.content_type, msg))
+    /// Pick the message to handle among candidates of unknown type published at the same link
+    /// without committing any, like `select_candidate`. A sequence message authenticates as far as
+    /// the message it announces does: the candidates published at the announced link which match
+    /// the sequence message are returned along.
+    fn select_message(&mut self, msgs: Vec<Message>) -> Result<(Message, Option<Vec<Message>>)> {
+        let mut unauthenticated = None;
+        let mut last_err = None;
+        for msg in msgs {
+            match self.authenticate_message(&msg) {
+                Ok((true, announced)) => return Ok((msg, announced)),
+                Ok((false, announced)) => {
+                    unauthenticated.get_or_insert((msg, announced));
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match unauthenticated {
+            Some(selected) => Ok(selected),
+            None => last_err.map_or_else(|| err!(MessageContentsNotFound), Err),
+        }
+    }
+
+    /// Unwrap a candidate message of unknown type without committing it, see `select_message`.
+    fn authenticate_message(&mut self, msg: &Message) -> Result<(bool, Option<Vec<Message>>)> {
+        if msg.binary.parse_header()?.header.content_type != message::SEQUENCE {
+            return Ok((self.user.authenticate(&msg.binary)?, None));
+        }
+        let sequenced = read_sequence(&self.user, &msg.binary)?;
+        let msgs = self.recv_candidates(&sequenced.link)?;
+        authenticate_announced(&self.user, &sequenced, msgs)
     }
 
     /// Receive and process a message with a known anchor link and message number. This can only
@@ -538,9 +1134,9 @@ impl<Trans: Transport + Clone> User<Trans> {
             Some(pk) => {
                 let seq_no = self.user.fetch_anchor()?.seq_no;
                 let cursor = Cursor::new_at(anchor_link.rel(), 0, msg_num + seq_no);
-                let link = self.user.link_gen.link_from(pk.as_ref(), cursor);
-                let msg = self.transport.recv_message(&link)?;
-                self.handle_message(msg, false)
+                let link = self.user.link_from(pk.as_ref(), cursor);
+                let msgs = self.recv_candidates(&link)?;
+                self.handle_messages(msgs, false)
             }
             None => err(UserNotRegistered),
         }
@@ -551,42 +1147,104 @@ impl<Trans: Transport + Clone> User<Trans> {
 impl<Trans: Transport + Clone> User<Trans> {
     // Send
 
-    /// Send a message with sequencing logic. If channel is single-branched, then no secondary
-    /// sequence message is sent and None is returned for the address.
-    ///
-    /// # Arguments
-    /// * `wrapped` - A wrapped sequence object containing the sequence message and state
-    async fn send_sequence(&mut self, wrapped_sequence: WrappedSequence) -> Result<Option<Address>> {
-        match wrapped_sequence {
-            WrappedSequence::MultiBranch(
-                cursor,
-                WrappedMessage {
-                    message,
-                    wrapped: wrapped_state,
-                },
-            ) => {
-                self.transport.send_message(&Message::new(message)).await?;
-                self.user.commit_sequence(cursor, wrapped_state, MsgInfo::Sequence)
-            }
-            WrappedSequence::SingleBranch(cursor) => {
-                self.user.commit_sequence_to_all(cursor)?;
-                Ok(None)
+    /// Send a message without using sequencing logic. Reserved for Announce and Subscribe messages
+    async fn send_message(&mut self, msg: WrappedMessage, info: MsgInfo) -> Result<Address> {
+        try_or!(self.staged.is_none(), StagedMessagePending)?;
+        self.publish(Message::with_clock(msg.message, &*self.clock)).await?;
+        let link = self.commit_wrapped(msg.wrapped, info)?;
+        self.save_state()?;
+        Ok(link)
+    }
+
+    /// Send the frames of the staged message not sent yet, then commit it along with its sequence
+    /// message and return their links. On failure the message stays staged: a new send returns
+    /// `StagedMessagePending` until it is sent with this method or dropped with `discard_staged`.
+    pub async fn retry_staged(&mut self) -> Result<(Address, Option<Address>)> {
+        let mut staged = match self.staged.take() {
+            Some(staged) => staged,
+            None => return err!(NoStagedMessage),
+        };
+        while staged.sent < staged.frames.len() {
+            let frame = Message::with_clock(staged.frames[staged.sent].clone(), &*self.clock);
+            if let Err(e) = self.publish(frame).await {
+                self.staged = Some(staged);
+                return Err(e);
             }
-            WrappedSequence::SingleDepth(cursor) => {
-                self.user.commit_sequence_to_all(cursor)?;
-                Ok(None)
+            staged.sent += 1;
+        }
+        let msg_link = self.commit_wrapped(staged.wrapped, staged.info)?;
+        let seq_link = self.commit_sequence(staged.seq)?;
+        self.save_state()?;
+        Ok((msg_link, seq_link))
+    }
+
+    /// Send a message to the transport, or queue it in the outbox while offline or while older
+    /// messages are waiting to be published.
+    async fn publish(&mut self, msg: Message) -> Result<()> {
+        if self.offline || !self.outbox.is_empty() {
+            self.outbox.push(msg);
+            Ok(())
+        } else {
+            self.transport.send_message(&msg).await
+        }
+    }
+
+    /// Publish the messages queued in the outbox in order, retrying failed sends according to
+    /// `policy`. Stops at the first message which cannot be sent, it and the following messages
+    /// are kept in the outbox. Returns the number of published messages.
+    ///
+    /// Sends are retried in place as `Retry` only wraps transports of the app layer.
+    pub async fn flush_outbox(&mut self, policy: RetryPolicy) -> Result<usize> {
+        let mut published = 0;
+        let mut result = Ok(());
+        while let Some(msg) = self.outbox.front() {
+            let mut backoffs = policy.backoffs();
+            result = loop {
+                match self.transport.send_message(msg).await {
+                    Err(e) if is_retriable(&e) => match backoffs.next() {
+                        Some(backoff) => sleep(backoff).await,
+                        None => break Err(e),
+                    },
+                    r => break r,
+                }
+            };
+            if result.is_err() {
+                break;
             }
-            WrappedSequence::None => Ok(None),
+            self.outbox.pop_front();
+            published += 1;
         }
+        self.save_state()?;
+        result.map(|_| published)
     }
 
-    /// Send a message without using sequencing logic. Reserved for Announce and Subscribe messages
-    async fn send_message(&mut self, msg: WrappedMessage, info: MsgInfo) -> Result<Address> {
-        self.transport.send_message(&Message::new(msg.message)).await?;
-        self.commit_wrapped(msg.wrapped, info)
+    /// Receive every message published at `link`, each joined back with its continuation frames.
+    /// Frame links are only queried as far as a candidate needs them and until one of them is
+    /// empty, see `UserImp::join_frames` for how frames are selected. Candidates which cannot be
+    /// parsed or whose frames are missing are dropped.
+    async fn recv_candidates(&mut self, link: &Address) -> Result<Vec<Message>> {
+        let mut frames: Vec<Vec<Message>> = Vec::new();
+        let mut candidates = Vec::new();
+        for mut msg in self.transport.recv_messages(link).await? {
+            let frame_count = match msg.binary.parse_header() {
+                Ok(preparsed) => preparsed.header.payload_frame_count,
+                Err(_) => continue,
+            };
+            while frames.len() + 1 < frame_count as usize && !matches!(frames.last(), Some(found) if found.is_empty()) {
+                let frame_link = self.user.frame_link(link, frames.len() as u32 + 2);
+                frames.push(self.transport.recv_messages(&frame_link).await.unwrap_or_default());
+            }
+            let found = frames.iter().map(|found| found.iter().map(|frame| &frame.binary));
+            if self.user.join_frames(&mut msg.binary, frame_count, found).is_ok() {
+                candidates.push(msg);
+            }
+        }
+        Ok(candidates)
     }
 
-    /// Send a message using sequencing logic.
+    /// Send a message using sequencing logic. The message and its sequence message are both sent
+    /// before any state is committed: if either send fails, the own cursor and the link store are
+    /// left as they were and the message stays staged, see `retry_staged`.
     ///
     /// # Arguments
     /// * `msg` - Wrapped Message ready for sending
@@ -598,14 +1256,12 @@ impl<Trans: Transport + Clone> User<Trans> {
         ref_link: &MsgId,
         info: MsgInfo,
     ) -> Result<(Address, Option<Address>)> {
-        // Send & commit original message
-        self.transport.send_message(&Message::new(msg.message)).await?;
-        let msg_link = self.commit_wrapped(msg.wrapped, info)?;
-
-        // Send & commit associated sequence message
+        // Stage the associated sequence message, it only depends on the state preceding the message
         let seq = self.user.wrap_sequence(ref_link)?;
-        let seq_link = self.send_sequence(seq).await?;
-        Ok((msg_link, seq_link))
+        self.stage(msg, seq, info)?;
+
+        // Send both messages, then commit them
+        self.retry_staged().await
     }
 
     /// Send an announcement message, generating a channel [Author].
@@ -630,7 +1286,10 @@ impl<Trans: Transport + Clone> User<Trans> {
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<(Address, Option<Address>)> {
-        let msg = self.user.sign_packet(link_to, public_payload, masked_payload)?;
+        let timestamp = self.packet_timestamp();
+        let msg = self
+            .user
+            .sign_packet(link_to, public_payload, masked_payload, timestamp)?;
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::SignedPacket)
             .await
     }
@@ -647,7 +1306,10 @@ impl<Trans: Transport + Clone> User<Trans> {
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<(Address, Option<Address>)> {
-        let msg = self.user.tag_packet(link_to, public_payload, masked_payload)?;
+        let timestamp = self.packet_timestamp();
+        let msg = self
+            .user
+            .tag_packet(link_to, public_payload, masked_payload, timestamp)?;
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::TaggedPacket)
             .await
     }
@@ -683,6 +1345,15 @@ impl<Trans: Transport + Clone> User<Trans> {
         self.send_message(msg, MsgInfo::Subscribe).await
     }
 
+    /// Create and Send an Unsubscribe message to a Channel app instance [Subscriber].
+    ///
+    /// # Arguments
+    /// * `link_to` - Address of the Subscribe message previously sent by the Subscriber
+    pub async fn send_unsubscribe(&mut self, link_to: &Address) -> Result<Address> {
+        let msg = self.user.unsubscribe(link_to)?;
+        self.send_message(msg, MsgInfo::Unsubscribe).await
+    }
+
     // Receive
 
     /// Receive and process a sequence message [Author, Subscriber].
@@ -690,15 +1361,12 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link` - Address of the message to be processed
     pub async fn receive_sequence(&mut self, link: &Address) -> Result<Address> {
-        let msg = self.transport.recv_message(link).await?;
+        let msgs = self.recv_candidates(link).await?;
         if let Some(_addr) = &self.user.appinst {
-            let seq_msg = self.user.handle_sequence(msg.binary, MsgInfo::Sequence, true)?.body;
-            let msg_id = self.user.link_gen.link_from(
-                seq_msg.id.to_bytes(),
-                Cursor::new_at(&seq_msg.ref_link, 0, seq_msg.seq_num.0 as u32),
-            );
-
-            Ok(msg_id)
+            let (msg, _) = self.select_message(msgs).await?;
+            let link = process_sequence(&mut self.user, msg.binary, true)?.link;
+            self.save_state()?;
+            Ok(link)
         } else {
             err!(UserNotRegistered)
         }
@@ -709,10 +1377,15 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link` - Address of the message to be processed
     pub async fn receive_signed_packet(&mut self, link: &Address) -> Result<(PublicKey, Bytes, Bytes)> {
-        let msg = self.transport.recv_message(link).await?;
+        let msgs = self.recv_candidates(link).await?;
         // TODO: msg.timestamp is lost
-        let m = self.user.handle_signed_packet(msg.binary, MsgInfo::SignedPacket)?;
-        Ok(m.body)
+        let (freshness, now) = (self.freshness, self.clock.now());
+        let m = self.handle_candidates(msgs, |user, msg| {
+            check_freshness(user, &msg.binary, &freshness, now)?;
+            user.handle_signed_packet(msg.binary, MsgInfo::SignedPacket)
+        })?;
+        let (pk, public, masked, _) = m.body;
+        Ok((pk, public, masked))
     }
 
     /// Receive and process a tagged packet message [Author, Subscriber].
@@ -720,9 +1393,14 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link` - Address of the message to be processed
     pub async fn receive_tagged_packet(&mut self, link: &Address) -> Result<(Bytes, Bytes)> {
-        let msg = self.transport.recv_message(link).await?;
-        let m = self.user.handle_tagged_packet(msg.binary, MsgInfo::TaggedPacket)?;
-        Ok(m.body)
+        let msgs = self.recv_candidates(link).await?;
+        let (freshness, now) = (self.freshness, self.clock.now());
+        let m = self.handle_candidates(msgs, |user, msg| {
+            check_freshness(user, &msg.binary, &freshness, now)?;
+            user.handle_tagged_packet(msg.binary, MsgInfo::TaggedPacket)
+        })?;
+        let (public, masked, _) = m.body;
+        Ok((public, masked))
     }
 
     /// Receive and process a subscribe message [Author].
@@ -730,9 +1408,20 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link` - Address of the message to be processed
     pub async fn receive_subscribe(&mut self, link: &Address) -> Result<()> {
-        let msg = self.transport.recv_message(link).await?;
+        let msgs = self.recv_candidates(link).await?;
         // TODO: Timestamp is lost.
-        self.user.handle_subscribe(msg.binary, MsgInfo::Subscribe)
+        self.handle_candidates(msgs, |user, msg| user.handle_subscribe(msg.binary, MsgInfo::Subscribe))
+    }
+
+    /// Receive and process an unsubscribe message [Author].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    pub async fn receive_unsubscribe(&mut self, link: &Address) -> Result<()> {
+        let msgs = self.recv_candidates(link).await?;
+        self.handle_candidates(msgs, |user, msg| {
+            user.handle_unsubscribe(msg.binary, MsgInfo::Unsubscribe)
+        })
     }
 
     /// Receive and Process an announcement message [Subscriber].
@@ -740,8 +1429,10 @@ impl<Trans: Transport + Clone> User<Trans> {
     /// # Arguments
     /// * `link_to` - Address of the Channel Announcement message
     pub async fn receive_announcement(&mut self, link: &Address) -> Result<()> {
-        let msg = self.transport.recv_message(link).await?;
-        self.user.handle_announcement(msg.binary, MsgInfo::Announce)
+        let msgs = self.recv_candidates(link).await?;
+        self.handle_candidates(msgs, |user, msg| {
+            user.handle_announcement(msg.binary, MsgInfo::Announce)
+        })
     }
 
     /// Receive and process a keyload message [Subscriber].
@@ -749,9 +1440,9 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///  # Arguments
     ///  * `link` - Address of the message to be processed
     pub async fn receive_keyload(&mut self, link: &Address) -> Result<bool> {
-        let msg = self.transport.recv_message(link).await?;
-        let m = self.user.handle_keyload(msg.binary, MsgInfo::Keyload)?;
-        Ok(m.body)
+        let msgs = self.recv_candidates(link).await?;
+        let m = self.handle_candidates(msgs, |user, msg| user.handle_keyload(msg.binary, MsgInfo::Keyload))?;
+        Ok(m.body.0)
     }
 
     /// Receive and process a message of unknown type. Message will be handled appropriately and
@@ -761,33 +1452,50 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///   * `link` - Address of the message to be processed
     ///   * `pk` - Optional ed25519 Public Key of the sending participant. None if unknown
     pub async fn receive_message(&mut self, link: &Address) -> Result<UnwrappedMessage> {
-        let msg = self.transport.recv_message(link).await?;
-        self.handle_message(msg, true).await
+        let msgs = self.recv_candidates(link).await?;
+        self.handle_messages(msgs, true).await
     }
 
     /// Retrieves the next message for each user (if present in transport layer) and returns them [Author, Subscriber]
     pub async fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
+        self.try_fetch_next_msgs()
+            .await
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .collect()
+    }
+
+    /// Retrieves the next message for each user and returns the result per publisher, publishers
+    /// with no new message are left out [Author, Subscriber]
+    pub async fn try_fetch_next_msgs(&mut self) -> Vec<FetchResult> {
         let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
-        let mut msgs = Vec::new();
+        let mut results = Vec::new();
 
-        for (
-            _pk,
-            Cursor {
-                link,
-                branch_no: _,
-                seq_no: _,
-            },
-        ) in ids
-        {
-            let msg = self.transport.recv_message(&link).await;
-
-            if let Ok(msg) = msg {
-                if let Ok(msg) = self.handle_message(msg, true).await {
-                    msgs.push(msg);
-                }
+        for (id, cursor) in ids {
+            if let Some(result) = self.fetch_next_msg(id, &cursor.link).await {
+                results.push(result);
             }
         }
-        msgs
+        results
+    }
+
+    /// Retrieves the message expected at the next message link of a publisher [Author, Subscriber].
+    /// Returns `None` if nothing has been published at that link yet.
+    ///
+    /// # Arguments
+    /// * `id` - Identifier of the publisher
+    /// * `link` - Next message link of the publisher
+    pub async fn fetch_next_msg(&mut self, id: Identifier, link: &Address) -> Option<FetchResult> {
+        let candidates = match self.recv_candidates(link).await {
+            Ok(candidates) => candidates,
+            Err(e) if is_link_not_found(&e) => return None,
+            Err(e) => return Some(Err(fetch_error(id, link, FetchErrorKind::Transport, e))),
+        };
+        Some(
+            self.handle_messages(candidates, true)
+                .await
+                .map_err(|e| fetch_error(id, link, FetchErrorKind::Unwrap, e)),
+        )
     }
 
     /// Retrieves the previous message from the message specified (provided the user has access to it) [Author,
@@ -796,39 +1504,49 @@ impl<Trans: Transport + Clone> User<Trans> {
     /// # Arguments
     /// * `link` - Address of message to act as root of previous message fetching
     pub async fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
-        let msg = self.transport.recv_message(link).await?;
-        let header = msg.binary.parse_header()?.header;
+        let mut prev_msg_links = Vec::new();
+        let mut last_err = None;
+        for msg in self.recv_candidates(link).await? {
+            // Malformed candidates published at the link do not hide the genuine message
+            let prev_msg_link = msg
+                .binary
+                .parse_header()
+                .and_then(|preparsed| Address::from_bytes(&preparsed.header.previous_msg_link.0));
+            match prev_msg_link {
+                Ok(prev_msg_link) if !prev_msg_links.contains(&prev_msg_link) => prev_msg_links.push(prev_msg_link),
+                Ok(_) => {}
+                Err(e) => last_err = Some(e),
+            }
+        }
 
-        let prev_msg_link = Address::from_bytes(&header.previous_msg_link.0);
-        let prev_msg = self.transport.recv_message(&prev_msg_link).await?;
-        let unwrapped = self.handle_message(prev_msg, false).await?;
-        Ok(unwrapped)
+        for prev_msg_link in prev_msg_links {
+            let unwrapped = match self.recv_candidates(&prev_msg_link).await {
+                Ok(prev_msgs) => self.handle_messages(prev_msgs, false).await,
+                Err(e) => Err(e),
+            };
+            match unwrapped {
+                Ok(unwrapped) => return Ok(unwrapped),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        last_err.map_or_else(|| err!(MessageContentsNotFound), Err)
     }
 
     /// Retrieves a specified number of previous messages from an original specified messsage link [Author, Subscriber]
+    ///
     /// # Arguments
     /// * `link` - Address of message to act as root of previous message fetching
     /// * `max` - The number of msgs to try and parse
     pub async fn fetch_prev_msgs(&mut self, link: &Address, max: usize) -> Result<Vec<UnwrappedMessage>> {
-        let mut msg_info: (Address, u8, Message) = self.parse_msg_info(link).await?;
-        let mut to_process = Vec::new();
         let mut msgs = Vec::new();
-
+        let mut link = link.clone();
         for _ in 0..max {
-            msg_info = self.parse_msg_info(&msg_info.0).await?;
-            if msg_info.1 == message::SEQUENCE {
-                let msg_link = self.process_sequence(msg_info.2.binary, false)?;
-                msg_info = self.parse_msg_info(&msg_link).await?;
-            }
-            to_process.push(msg_info.2);
-        }
-
-        to_process.reverse();
-        for msg in to_process {
-            let unwrapped = self.handle_message(msg, false).await?;
+            // Only the selected previous message leads further back
+            let unwrapped = self.fetch_prev_msg(&link).await?;
+            link = unwrapped.link.clone();
             msgs.push(unwrapped);
         }
-
+        msgs.reverse();
         Ok(msgs)
     }
 
@@ -837,58 +1555,74 @@ impl<Trans: Transport + Clone> User<Trans> {
     ///
     /// # Arguments
     /// * `msg` - Binary message of unknown type
-    pub async fn handle_message(&mut self, mut msg0: Message, store: bool) -> Result<UnwrappedMessage> {
-        let mut sequenced = false;
-        loop {
-            // Forget TangleMessage and timestamp
-            let msg = msg0.binary;
-            let preparsed = msg.parse_header()?;
-            let link = preparsed.header.link.clone();
-            let prev_link = TangleAddress::from_bytes(&preparsed.header.previous_msg_link.0);
-            match preparsed.header.content_type {
-                message::SIGNED_PACKET => match self.user.handle_signed_packet(msg, MsgInfo::SignedPacket) {
-                    Ok(m) => {
-                        return Ok(m.map(|(pk, public, masked)| MessageContent::new_signed_packet(pk, public, masked)))
-                    }
-                    Err(e) => match sequenced {
-                        true => return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable())),
-                        false => return Err(e),
-                    },
-                },
-                message::TAGGED_PACKET => match self.user.handle_tagged_packet(msg, MsgInfo::TaggedPacket) {
-                    Ok(m) => return Ok(m.map(|(public, masked)| MessageContent::new_tagged_packet(public, masked))),
-                    Err(e) => match sequenced {
-                        true => return Ok(UnwrappedMessage::new(link, prev_link, MessageContent::unreadable())),
-                        false => return Err(e),
-                    },
-                },
-                message::KEYLOAD => {
-                    // So long as the unwrap has not failed, we will return a blank object to
-                    // inform the user that a message was present, even if the use wasn't part of
-                    // the keyload itself. This is to prevent sequencing failures
-                    let m = self.user.handle_keyload(msg, MsgInfo::Keyload)?;
-                    // TODO: Verify content, whether user is allowed or not!
-                    let u = m.map(|_allowed| MessageContent::new_keyload());
-                    return Ok(u);
+    pub async fn handle_message(&mut self, msg: Message, store: bool) -> Result<UnwrappedMessage> {
+        self.handle_messages(vec![msg], store).await
+    }
+
+    /// Handle the candidate selected among messages of unknown type published at the same link,
+    /// see `select_message`. A selected sequence message is committed along with the message it
+    /// announces, which is reported as unreadable if none of its candidates unwraps.
+    ///
+    /// Packets are checked against the freshness policy and the milestone confirming the message is
+    /// looked up before anything is committed, a rejected packet or a failed lookup leaves the user
+    /// state unchanged.
+    async fn handle_messages(&mut self, msgs: Vec<Message>, store: bool) -> Result<UnwrappedMessage> {
+        let (mut msg, mut announced) = self.select_message(msgs).await?;
+        let mut sequences = Vec::new();
+        while let Some(msgs) = announced {
+            let sequenced = read_sequence(&self.user, &msg.binary)?;
+            sequences.push(msg);
+            match self.select_message(msgs).await {
+                Ok(selected) => {
+                    msg = selected.0;
+                    announced = selected.1;
                 }
-                message::SEQUENCE => {
-                    let msg_link = self.process_sequence(msg, store)?;
-                    let msg = self.transport.recv_message(&msg_link).await?;
-                    sequenced = true;
-                    msg0 = msg;
+                // Sequenced packets the user has no access to are reported as unreadable
+                Err(_) => {
+                    process_sequences(&mut self.user, sequences, store)?;
+                    self.save_state()?;
+                    return Ok(sequenced.unreadable());
                 }
-                unknown_content => return err!(UnknownMsgType(unknown_content)),
             }
         }
+        check_freshness(&self.user, &msg.binary, &self.freshness, self.clock.now())?;
+        let milestone_timestamp = self.transport.confirmation_timestamp(&msg).await?;
+        process_sequences(&mut self.user, sequences, store)?;
+        let unwrapped = handle_content(&mut self.user, msg, milestone_timestamp)?;
+        self.save_state()?;
+        Ok(unwrapped)
     }
 
-    /// Get the previous msg link and msg type from header of message and return in a tuple alongside
-    /// the message itself
-    async fn parse_msg_info(&mut self, link: &Address) -> Result<(Address, u8, Message)> {
-        let msg = self.transport.recv_message(link).await?;
-        let header = msg.binary.parse_header()?.header;
-        let link = Address::from_bytes(&header.previous_msg_link.0);
-        Ok((link, header.content_type, msg))
+    /// Pick the message to handle among candidates of unknown type published at the same link
+    /// without committing any, like `select_candidate`. A sequence message authenticates as far as
+    /// the message it announces does: the candidates published at the announced link which match
+    /// the sequence message are returned along.
+    async fn select_message(&mut self, msgs: Vec<Message>) -> Result<(Message, Option<Vec<Message>>)> {
+        let mut unauthenticated = None;
+        let mut last_err = None;
+        for msg in msgs {
+            match self.authenticate_message(&msg).await {
+                Ok((true, announced)) => return Ok((msg, announced)),
+                Ok((false, announced)) => {
+                    unauthenticated.get_or_insert((msg, announced));
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match unauthenticated {
+            Some(selected) => Ok(selected),
+            None => last_err.map_or_else(|| err!(MessageContentsNotFound), Err),
+        }
+    }
+
+    /// Unwrap a candidate message of unknown type without committing it, see `select_message`.
+    async fn authenticate_message(&mut self, msg: &Message) -> Result<(bool, Option<Vec<Message>>)> {
+        if msg.binary.parse_header()?.header.content_type != message::SEQUENCE {
+            return Ok((self.user.authenticate(&msg.binary)?, None));
+        }
+        let sequenced = read_sequence(&self.user, &msg.binary)?;
+        let msgs = self.recv_candidates(&sequenced.link).await?;
+        authenticate_announced(&self.user, &sequenced, msgs)
     }
 
     /// Receive and process a message with a known anchor link and message number. This can only
@@ -909,11 +1643,38 @@ impl<Trans: Transport + Clone> User<Trans> {
             Some(pk) => {
                 let seq_no = self.user.fetch_anchor()?.seq_no;
                 let cursor = Cursor::new_at(anchor_link.rel(), 0, msg_num + seq_no);
-                let link = self.user.link_gen.link_from(pk.as_ref(), cursor);
-                let msg = self.transport.recv_message(&link).await?;
-                self.handle_message(msg, false).await
+                let link = self.user.link_from(pk.as_ref(), cursor);
+                let msgs = self.recv_candidates(&link).await?;
+                self.handle_messages(msgs, false).await
             }
             None => err(UserNotRegistered),
         }
     }
 }
+
+#[cfg(feature = "async")]
+impl<Trans: Transport + TransportSubscribe<Address>> User<Trans> {
+    /// Waits until something is published at the next message link of a known publisher and
+    /// returns the new messages, without polling the transport [Author, Subscriber].
+    ///
+    /// Links holding messages which fail to unwrap are watched until something else is published
+    /// at them.
+    pub async fn await_next_msgs(&mut self) -> Result<Vec<UnwrappedMessage>> {
+        loop {
+            // Subscribe before fetching so that messages published in between are not missed
+            let links = self
+                .user
+                .gen_next_msg_ids(self.user.is_multi_branching())
+                .into_iter()
+                .map(|(_, cursor)| cursor.link)
+                .collect();
+            let subscription = self.transport.subscribe(links)?;
+            let msgs = self.fetch_next_msgs().await;
+            if !msgs.is_empty() {
+                return Ok(msgs);
+            }
+            let notified = subscription.await.is_ok();
+            try_or!(notified, SubscriptionClosed)?;
+        }
+    }
+}
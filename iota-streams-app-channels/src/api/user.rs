@@ -64,6 +64,19 @@ const ANN_MESSAGE_NUM: u32 = 0;
 const SUB_MESSAGE_NUM: u32 = 0;
 const SEQ_MESSAGE_NUM: u32 = 1;
 
+/// Version of the exported user state format.
+///
+/// * `0` -- initial format, encrypted with a key derived directly from the password.
+/// * `1` -- derives the encryption key with Argon2id from the password and a random salt, adds pre-shared keys,
+///   `use_psk`, `anchor` and channel metadata.
+const EXPORT_VERSION: u8 = 1;
+
+/// Domain separator of the export encryption key.
+const EXPORT_KEY_DOMAIN: &str = "IOTA Streams Channels app";
+
+/// Domain separator of the link secret derived from the state of a message.
+const LINK_SECRET_DOMAIN: &str = "IOTA Streams Channels link secret";
+
 /// Sequence wrapping object
 ///
 /// When using multibranch mode, this wrapping object contains the (wrapped) sequence message ([`WrappedMessage`]) to be
@@ -123,6 +136,10 @@ where
     /// Author's Ed25519 public key.
     pub(crate) author_sig_pk: Option<ed25519::PublicKey>,
 
+    /// Whether `author_sig_pk` has been pinned before the announcement was received, a pinned key
+    /// is kept when the user unregisters.
+    pub(crate) author_sig_pk_pinned: bool,
+
     /// Link generator.
     pub(crate) link_gen: LG,
 
@@ -144,6 +161,9 @@ where
 
     /// Anchor message for the channel (can either be an announcement or keyload) - For single depth
     pub anchor: Option<Cursor<Link>>,
+
+    /// Channel metadata published in (Author) or received with (Subscriber) the announcement.
+    pub(crate) channel_metadata: Option<announce::ChannelMetadata>,
 }
 
 impl<F, Link, LG, LS, Keys> Default for User<F, Link, LG, LS, Keys>
@@ -168,6 +188,7 @@ where
 
             key_store: Keys::default(),
             author_sig_pk: None,
+            author_sig_pk_pinned: false,
             link_gen: LG::default(),
             link_store: RefCell::new(LS::default()),
             appinst: None,
@@ -176,6 +197,7 @@ where
             uniform_payload_length: 0,
             use_psk: false,
             anchor: None,
+            channel_metadata: None,
         }
     }
 }
@@ -183,6 +205,11 @@ where
 // Alias needed to reduce complexity of `unwrap_keyload` return value
 type KeyloadContentUnwrap<'a, F, Link, User> = keyload::ContentUnwrap<'a, F, Link, &'a User, &'a User>;
 
+// Aliases needed to reduce complexity of `handle_signed_packet` and `handle_tagged_packet` return
+// values: the payloads along with the optional publish timestamp
+type SignedPacketBody = (ed25519::PublicKey, Bytes, Bytes, Option<u64>);
+type TaggedPacketBody = (Bytes, Bytes, Option<u64>);
+
 impl<F, Link, LG, LS, Keys> User<F, Link, LG, LS, Keys>
 where
     F: PRP,
@@ -217,6 +244,7 @@ where
 
             key_store: Keys::default(),
             author_sig_pk: None,
+            author_sig_pk_pinned: false,
             link_gen: LG::default(),
             link_store: RefCell::new(LS::default()),
             appinst: None,
@@ -225,6 +253,7 @@ where
             uniform_payload_length,
             use_psk: false,
             anchor: None,
+            channel_metadata: None,
         }
     }
 
@@ -252,6 +281,53 @@ where
         self.author_sig_pk.as_ref()
     }
 
+    /// Pin the Author's signature public key before the channel announcement is received,
+    /// announcements signed with any other key are rejected.
+    pub fn pin_author_public_key(&mut self, pk: ed25519::PublicKey) -> Result<()> {
+        if let Some(appinst) = &self.appinst {
+            return err!(UserAlreadyRegistered(appinst.base().to_string()));
+        }
+        self.author_sig_pk = Some(pk);
+        self.author_sig_pk_pinned = true;
+        Ok(())
+    }
+
+    /// Channel metadata, if the announcement carries any.
+    pub fn channel_metadata(&self) -> Option<&announce::ChannelMetadata> {
+        self.channel_metadata.as_ref()
+    }
+
+    /// Set channel metadata to be signed and published in the announcement, fails once the
+    /// announcement has been committed.
+    pub fn set_channel_metadata(&mut self, metadata: Option<announce::ChannelMetadata>) -> Result<()> {
+        if let Some(appinst) = &self.appinst {
+            let announced = self.link_store.borrow().lookup(appinst.rel()).is_ok();
+            try_or!(!announced, ChannelAlreadyAnnounced(appinst.base().to_string()))?;
+        }
+        self.channel_metadata = metadata;
+        Ok(())
+    }
+
+    /// Whether links of the channel are derived from the secret state of the message they link to,
+    /// see `link_from`.
+    pub fn has_private_links(&self) -> bool {
+        (self.flags & announce::FLAG_PRIVATE_LINKS_MASK) != 0
+    }
+
+    /// Announce the channel with private links, fails once the announcement has been committed.
+    pub fn set_private_links(&mut self, enabled: bool) -> Result<()> {
+        if let Some(appinst) = &self.appinst {
+            let announced = self.link_store.borrow().lookup(appinst.rel()).is_ok();
+            try_or!(!announced, ChannelAlreadyAnnounced(appinst.base().to_string()))?;
+        }
+        if enabled {
+            self.flags |= announce::FLAG_PRIVATE_LINKS_MASK;
+        } else {
+            self.flags &= !announce::FLAG_PRIVATE_LINKS_MASK;
+        }
+        Ok(())
+    }
+
     /// Reset link store and key store to original state
     pub fn reset_state(&mut self) -> Result<()> {
         match &self.appinst {
@@ -287,7 +363,8 @@ where
             .with_payload_length(1)?
             .with_seq_num(ANN_MESSAGE_NUM)
             .with_identifier(&self.sig_kp.public.into());
-        let content = announce::ContentWrap::new(&self.sig_kp, self.flags);
+        let content =
+            announce::ContentWrap::new(&self.sig_kp, self.flags).with_metadata(self.channel_metadata.as_ref());
         Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
     }
 
@@ -327,14 +404,23 @@ where
 
         let unwrapped = self.unwrap_announcement(preparsed)?;
         let link = unwrapped.link.clone();
-        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
-        // TODO: check commit after message is done / before joined
 
-        // TODO: Verify trust to Author's public key?
-        // At the moment the Author is trusted unconditionally.
+        // The Author is trusted unconditionally unless its public key has been pinned beforehand.
+        let sig_pk = &unwrapped.pcf.content.sig_pk;
+        if let Some(trusted_pk) = &self.author_sig_pk {
+            try_or!(
+                trusted_pk == sig_pk,
+                UntrustedAuthorKey(hex::encode(trusted_pk.as_bytes()), hex::encode(sig_pk.as_bytes()))
+            )?;
+        }
+        // Channel address must be tied to the Author's public key.
+        try_or!(
+            self.link_gen.is_channel_of(&link, sig_pk),
+            ChannelAuthorMismatch(link.base().to_string())
+        )?;
 
-        // TODO: Verify appinst (address) == public key.
-        // At the moment the Author is free to choose any address, not tied to PK.
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        // TODO: check commit after message is done / before joined
 
         let cursor = Cursor::new_at(link.rel().clone(), 0, 2_u32);
         self.key_store
@@ -347,6 +433,7 @@ where
         self.appinst = Some(link);
         self.author_sig_pk = Some(content.sig_pk);
         self.flags = content.flags.0;
+        self.channel_metadata = content.metadata;
         Ok(())
     }
 
@@ -358,9 +445,7 @@ where
         if let Some(author_sig_pk) = &self.author_sig_pk {
             let identifier = Identifier::EdPubKey(ed25519::PublicKeyWrap(*author_sig_pk));
             if let Some(author_ke_pk) = self.key_store.get_ke_pk(&identifier) {
-                let msg_link = self
-                    .link_gen
-                    .link_from(self.sig_kp.public, Cursor::new_at(link_to.rel(), 0, SUB_MESSAGE_NUM));
+                let msg_link = self.link_from(self.sig_kp.public, Cursor::new_at(link_to.rel(), 0, SUB_MESSAGE_NUM));
                 let header = HDF::new(msg_link)
                     .with_previous_msg_link(Bytes(link_to.to_bytes()))
                     .with_content_type(SUBSCRIBE)?
@@ -421,6 +506,75 @@ where
         Ok(())
     }
 
+    /// Prepare Unsubscribe message.
+    pub fn prepare_unsubscribe<'a>(
+        &'a mut self,
+        link_to: &'a Link,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, unsubscribe::ContentWrap<'a, F, Link>>> {
+        try_or!(self.appinst.is_some(), UserNotRegistered)?;
+        let msg_link = self.link_from(self.sig_kp.public, Cursor::new_at(link_to.rel(), 0, SUB_MESSAGE_NUM));
+        let header = HDF::new(msg_link)
+            .with_previous_msg_link(Bytes(link_to.to_bytes()))
+            .with_content_type(UNSUBSCRIBE)?
+            .with_payload_length(1)?
+            .with_seq_num(SUB_MESSAGE_NUM)
+            .with_identifier(&self.sig_kp.public.into());
+        let content = unsubscribe::ContentWrap {
+            link: link_to.rel(),
+            _phantom: core::marker::PhantomData,
+        };
+        Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+    }
+
+    /// Unsubscribe from the channel. `link_to` must be the link of own Subscribe message.
+    pub fn unsubscribe(&mut self, link_to: &Link) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_unsubscribe(link_to)?.wrap()
+    }
+
+    pub fn unwrap_unsubscribe(
+        &self,
+        preparsed: PreparsedMessage<'_, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, unsubscribe::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        let content = unsubscribe::ContentUnwrap::default();
+        preparsed.unwrap(&*self.link_store.borrow(), content)
+    }
+
+    /// Verify MAC of the Unsubscribe message and forget the subscriber.
+    pub fn handle_unsubscribe(
+        &mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<()> {
+        let preparsed = msg.parse_header()?;
+        try_or!(
+            preparsed.content_type() == UNSUBSCRIBE,
+            NotUnsubscribe(preparsed.content_type())
+        )?;
+        let appinst_rel = match &self.appinst {
+            Some(appinst) => appinst.rel().clone(),
+            None => return err(UserNotRegistered),
+        };
+        let sender_id = preparsed.header.sender_id;
+        let unwrapped = self.unwrap_unsubscribe(preparsed)?;
+
+        // Subscribe message link is derived from the subscriber's public key, this makes sure
+        // a subscriber can only unsubscribe itself.
+        let pk = match &sender_id {
+            Identifier::EdPubKey(pk) => pk.0,
+            Identifier::PskId(_) => return err(BadIdentifier),
+        };
+        let subscribe_link = self.link_from(pk, Cursor::new_at(&appinst_rel, 0, SUB_MESSAGE_NUM));
+        try_or!(
+            subscribe_link.rel() == &unwrapped.pcf.content.link,
+            UnsubscribeLinkMismatch(hex::encode(&sender_id.to_bytes()))
+        )?;
+
+        unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        self.key_store.remove(&sender_id);
+        Ok(())
+    }
+
     fn do_prepare_keyload<'a>(
         &'a self,
         header: HDF<Link>,
@@ -450,9 +604,7 @@ where
     {
         match self.get_seq_no() {
             Some(seq_no) => {
-                let msg_link = self
-                    .link_gen
-                    .link_from(self.sig_kp.public, Cursor::new_at(link_to.rel(), 0, seq_no));
+                let msg_link = self.link_from(self.sig_kp.public, Cursor::new_at(link_to.rel(), 0, seq_no));
                 let header = HDF::new(msg_link)
                     .with_previous_msg_link(Bytes(link_to.to_bytes()))
                     .with_content_type(KEYLOAD)?
@@ -472,9 +624,7 @@ where
     ) -> Result<PreparedMessage<'a, F, Link, LS, keyload::ContentWrap<'a, F, Link>>> {
         match self.get_seq_no() {
             Some(seq_no) => {
-                let msg_link = self
-                    .link_gen
-                    .link_from(self.sig_kp.public, Cursor::new_at(link_to.rel(), 0, seq_no));
+                let msg_link = self.link_from(self.sig_kp.public, Cursor::new_at(link_to.rel(), 0, seq_no));
                 let header = hdf::HDF::new(msg_link)
                     .with_previous_msg_link(Bytes(link_to.to_bytes()))
                     .with_content_type(KEYLOAD)?
@@ -518,13 +668,14 @@ where
     }
 
     /// Try unwrapping session key from keyload using Subscriber's pre-shared key or Ed25519 private key (if any).
+    /// Returns whether the user is allowed along with the identifiers of all the keyload recipients.
     pub fn handle_keyload(
         &mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
-    ) -> Result<GenericMessage<Link, bool>> {
+    ) -> Result<GenericMessage<Link, (bool, Vec<Identifier>)>> {
         let preparsed = msg.parse_header()?;
-        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
+        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0)?;
         let seq_no = preparsed.header.seq_num;
         let unwrapped = self.unwrap_keyload(preparsed)?;
         let processed;
@@ -537,18 +688,20 @@ where
             // Unwrapped nonce and key in content are not used explicitly.
             // The resulting spongos state is joined into a protected message state.
             // Store any unknown publishers
+            let key_ids = content.key_ids;
             if let Some(appinst) = &self.appinst {
-                for identifier in content.key_ids {
-                    if !self.key_store.contains(&identifier) {
+                for identifier in &key_ids {
+                    if !self.key_store.contains(identifier) {
                         // Store at state 2 since 0 and 1 are reserved states
                         self.key_store
-                            .insert_cursor(identifier, Cursor::new_at(appinst.rel().clone(), 0, 2))?;
+                            .insert_cursor(*identifier, Cursor::new_at(appinst.rel().clone(), 0, 2))?;
                     }
                 }
             }
-            processed = GenericMessage::new(msg.link.clone(), prev_link, true);
+            processed = GenericMessage::new(msg.link.clone(), prev_link, (true, key_ids));
         } else {
-            processed = GenericMessage::new(msg.link.clone(), prev_link, false);
+            let key_ids = unwrapped.pcf.content.key_ids;
+            processed = GenericMessage::new(msg.link.clone(), prev_link, (false, key_ids));
         }
         if !self.is_multi_branching() {
             self.store_state_for_all(msg.link.rel().clone(), seq_no.0 as u32 + 1)?;
@@ -560,49 +713,52 @@ where
         Ok(processed)
     }
 
-    /// Prepare SignedPacket message.
+    /// Prepare SignedPacket message, optionally carrying an authenticated publish `timestamp`.
     pub fn prepare_signed_packet<'a>(
         &'a mut self,
         link_to: &'a Link,
         public_payload: &'a Bytes,
         masked_payload: &'a Bytes,
+        timestamp: Option<u64>,
     ) -> Result<PreparedMessage<'a, F, Link, LS, signed_packet::ContentWrap<'a, F, Link>>> {
         if self.use_psk {
             return err(MessageBuildFailure);
         }
         match self.get_seq_no() {
             Some(seq_no) => {
-                let msg_link = self
-                    .link_gen
-                    .link_from(self.sig_kp.public, Cursor::new_at(link_to.rel(), 0, seq_no));
-                let header = HDF::new(msg_link)
-                    .with_previous_msg_link(Bytes(link_to.to_bytes()))
-                    .with_content_type(SIGNED_PACKET)?
-                    .with_payload_length(1)?
-                    .with_seq_num(seq_no)
-                    .with_identifier(&self.sig_kp.public.into());
+                let msg_link = self.link_from(self.sig_kp.public, Cursor::new_at(link_to.rel(), 0, seq_no));
                 let content = signed_packet::ContentWrap {
                     link: link_to.rel(),
                     public_payload,
                     masked_payload,
                     sig_kp: &self.sig_kp,
+                    timestamp,
                     _phantom: core::marker::PhantomData,
                 };
+                let header = HDF::new(msg_link)
+                    .with_version(content.version())
+                    .with_previous_msg_link(Bytes(link_to.to_bytes()))
+                    .with_content_type(SIGNED_PACKET)?
+                    .with_payload_length(1)?
+                    .with_seq_num(seq_no)
+                    .with_identifier(&self.sig_kp.public.into());
                 Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
             }
             None => err!(SeqNumRetrievalFailure),
         }
     }
 
-    /// Create a signed message with public and masked payload.
+    /// Create a signed message with public and masked payload and optional publish timestamp.
     pub fn sign_packet(
         &mut self,
         link_to: &Link,
         public_payload: &Bytes,
         masked_payload: &Bytes,
+        timestamp: Option<u64>,
     ) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_signed_packet(link_to, public_payload, masked_payload)?
-            .wrap()
+        let frame_size = self.uniform_payload_length;
+        self.prepare_signed_packet(link_to, public_payload, masked_payload, timestamp)?
+            .wrap_frames(frame_size)
     }
 
     pub fn unwrap_signed_packet<'a>(
@@ -610,7 +766,10 @@ where
         preparsed: PreparsedMessage<'a, F, Link>,
     ) -> Result<UnwrappedMessage<F, Link, signed_packet::ContentUnwrap<F, Link>>> {
         self.ensure_appinst(&preparsed)?;
-        let content = signed_packet::ContentUnwrap::default();
+        let content = signed_packet::ContentUnwrap {
+            version: preparsed.header.version,
+            ..signed_packet::ContentUnwrap::default()
+        };
         preparsed.unwrap(&*self.link_store.borrow(), content)
     }
 
@@ -619,10 +778,10 @@ where
         &'_ mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
-    ) -> Result<GenericMessage<Link, (ed25519::PublicKey, Bytes, Bytes)>> {
+    ) -> Result<GenericMessage<Link, SignedPacketBody>> {
         // TODO: pass author_pk to unwrap
         let preparsed = msg.parse_header()?;
-        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
+        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0)?;
         let seq_no = preparsed.header.seq_num;
         let content = self
             .unwrap_signed_packet(preparsed)?
@@ -636,35 +795,41 @@ where
             self.store_state_for_all(link, seq_no.0 as u32 + 1)?;
         }
 
-        let body = (content.sig_pk, content.public_payload, content.masked_payload);
+        let body = (
+            content.sig_pk,
+            content.public_payload,
+            content.masked_payload,
+            content.timestamp,
+        );
         Ok(GenericMessage::new(msg.link, prev_link, body))
     }
 
-    /// Prepare TaggedPacket message.
+    /// Prepare TaggedPacket message, optionally carrying an authenticated publish `timestamp`.
     pub fn prepare_tagged_packet<'a>(
         &'a mut self,
         link_to: &'a Link,
         public_payload: &'a Bytes,
         masked_payload: &'a Bytes,
+        timestamp: Option<u64>,
     ) -> Result<PreparedMessage<'a, F, Link, LS, tagged_packet::ContentWrap<'a, F, Link>>> {
         let identifier = self.get_identifier()?;
         match self.get_seq_no() {
             Some(seq_no) => {
-                let msg_link = self
-                    .link_gen
-                    .link_from(identifier.to_bytes(), Cursor::new_at(link_to.rel(), 0, seq_no));
-                let header = HDF::new(msg_link)
-                    .with_previous_msg_link(Bytes(link_to.to_bytes()))
-                    .with_content_type(TAGGED_PACKET)?
-                    .with_payload_length(1)?
-                    .with_seq_num(seq_no)
-                    .with_identifier(&identifier);
+                let msg_link = self.link_from(identifier.to_bytes(), Cursor::new_at(link_to.rel(), 0, seq_no));
                 let content = tagged_packet::ContentWrap {
                     link: link_to.rel(),
                     public_payload,
                     masked_payload,
+                    timestamp,
                     _phantom: core::marker::PhantomData,
                 };
+                let header = HDF::new(msg_link)
+                    .with_version(content.version())
+                    .with_previous_msg_link(Bytes(link_to.to_bytes()))
+                    .with_content_type(TAGGED_PACKET)?
+                    .with_payload_length(1)?
+                    .with_seq_num(seq_no)
+                    .with_identifier(&identifier);
                 Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
             }
             None => err!(SeqNumRetrievalFailure),
@@ -682,16 +847,18 @@ where
         }
     }
 
-    /// Create a tagged (ie. MACed) message with public and masked payload.
+    /// Create a tagged (ie. MACed) message with public and masked payload and optional publish timestamp.
     /// Tagged messages must be linked to a secret spongos state, ie. keyload or a message linked to keyload.
     pub fn tag_packet(
         &mut self,
         link_to: &Link,
         public_payload: &Bytes,
         masked_payload: &Bytes,
+        timestamp: Option<u64>,
     ) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_tagged_packet(link_to, public_payload, masked_payload)?
-            .wrap()
+        let frame_size = self.uniform_payload_length;
+        self.prepare_tagged_packet(link_to, public_payload, masked_payload, timestamp)?
+            .wrap_frames(frame_size)
     }
 
     pub fn unwrap_tagged_packet(
@@ -699,7 +866,10 @@ where
         preparsed: PreparsedMessage<'_, F, Link>,
     ) -> Result<UnwrappedMessage<F, Link, tagged_packet::ContentUnwrap<F, Link>>> {
         self.ensure_appinst(&preparsed)?;
-        let content = tagged_packet::ContentUnwrap::default();
+        let content = tagged_packet::ContentUnwrap {
+            version: preparsed.header.version,
+            ..tagged_packet::ContentUnwrap::default()
+        };
         preparsed.unwrap(&*self.link_store.borrow(), content)
     }
 
@@ -708,9 +878,9 @@ where
         &mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
-    ) -> Result<GenericMessage<Link, (Bytes, Bytes)>> {
+    ) -> Result<GenericMessage<Link, TaggedPacketBody>> {
         let preparsed = msg.parse_header()?;
-        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
+        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0)?;
         let seq_no = preparsed.header.seq_num;
         let content = self
             .unwrap_tagged_packet(preparsed)?
@@ -724,10 +894,103 @@ where
             self.store_state_for_all(link, seq_no.0 as u32 + 1)?;
         }
 
-        let body = (content.public_payload, content.masked_payload);
+        let body = (content.public_payload, content.masked_payload, content.timestamp);
         Ok(GenericMessage::new(msg.link, prev_link, body))
     }
 
+    /// Secret known only to the readers of the message at `link`, derived from its committed
+    /// state. The state of a keyload, and of every message following it, depends on the session
+    /// key: messages linked to them get links only the keyload recipients can derive.
+    fn link_secret(&self, link: &<Link as HasLink>::Rel) -> Option<NBytes<U32>> {
+        let (mut s, _info) = self.link_store.borrow().lookup(link).ok()?;
+        s.absorb(LINK_SECRET_DOMAIN.as_bytes());
+        s.commit();
+        let mut secret = NBytes::<U32>::default();
+        s.squeeze(secret.as_mut());
+        Some(secret)
+    }
+
+    /// Link of the message of publisher `id` at `cursor`. In channels with private links it is
+    /// derived with the secret of the message at `cursor.link` if the user has read it.
+    pub fn link_from<T: AsRef<[u8]>>(&self, id: T, cursor: Cursor<&<Link as HasLink>::Rel>) -> Link {
+        let secret = if self.has_private_links() {
+            self.link_secret(cursor.link)
+        } else {
+            None
+        };
+        match secret {
+            Some(secret) => self.link_gen.link_from_secret(id, cursor, secret.as_ref()),
+            None => self.link_gen.link_from(id, cursor),
+        }
+    }
+
+    /// Link of continuation frame number `num` of a message published at `link`.
+    pub fn frame_link(&self, link: &Link, num: u32) -> Link {
+        self.link_gen.uniform_link_from(Cursor::new_at(link.rel(), 0, num))
+    }
+
+    /// Links of the continuation frames of a message published at `link` in `frame_count` frames.
+    pub fn frame_links(&self, link: &Link, frame_count: u32) -> Vec<Link> {
+        (2..=frame_count).map(|num| self.frame_link(link, num)).collect()
+    }
+
+    /// Split a wrapped message into frames of at most `uniform_payload_length` bytes. The first
+    /// frame keeps the message link, continuation frames are linked to it.
+    pub fn split_frames(&self, msg: BinaryMessage<F, Link>) -> Result<Vec<BinaryMessage<F, Link>>> {
+        let BinaryMessage { link, prev_link, body } = msg;
+        let bodies = fragment::split_body::<F>(body.bytes, self.uniform_payload_length)?;
+        let frame_links = self
+            .frame_links(&link, bodies.len() as u32)
+            .into_iter()
+            .map(|frame_link| (frame_link, link.clone()));
+        let frames = core::iter::once((link.clone(), prev_link))
+            .chain(frame_links)
+            .zip(bodies)
+            .map(|((link, prev_link), frame)| BinaryMessage::new(link, prev_link, frame.into()))
+            .collect();
+        Ok(frames)
+    }
+
+    /// Join continuation frames back to the first frame of a message split in `frame_count` frames.
+    /// `frames` yields the candidates found at each frame link in turn: the one matching the hash
+    /// committed to by the frame before it is joined, the others are dropped.
+    pub fn join_frames<'a, I, J>(&self, msg: &mut BinaryMessage<F, Link>, frame_count: u32, frames: I) -> Result<()>
+    where
+        I: IntoIterator<Item = J>,
+        J: IntoIterator<Item = &'a BinaryMessage<F, Link>>,
+        F: 'a,
+        Link: 'a,
+    {
+        if frame_count < 2 {
+            return Ok(());
+        }
+        let frame_size = msg.body.bytes.len();
+        let mut hash = fragment::split_first_frame(&mut msg.body.bytes, frame_count)?;
+        let mut frames = frames.into_iter();
+        for num in 2..=frame_count {
+            let frame = frames.next().and_then(|found| {
+                found
+                    .into_iter()
+                    .find(|frame| fragment::frame_hash::<F>(&frame.body.bytes) == hash)
+            });
+            let frame = match frame {
+                Some(frame) => frame,
+                None => return err!(FrameHashMismatch(num)),
+            };
+            if let Some(next_hash) = fragment::join_frame::<F>(
+                &mut msg.body.bytes,
+                &frame.body.bytes,
+                num,
+                frame_count,
+                frame_size,
+                &hash,
+            )? {
+                hash = next_hash;
+            }
+        }
+        Ok(())
+    }
+
     pub fn prepare_sequence<'a>(
         &'a mut self,
         link_to: &'a Link,
@@ -735,9 +998,7 @@ where
         ref_link: &'a <Link as HasLink>::Rel,
     ) -> Result<PreparedMessage<'a, F, Link, LS, sequence::ContentWrap<'a, Link>>> {
         let identifier = self.get_identifier()?;
-        let msg_link = self
-            .link_gen
-            .link_from(identifier.to_bytes(), Cursor::new_at(link_to.rel(), 0, SEQ_MESSAGE_NUM));
+        let msg_link = self.link_from(identifier.to_bytes(), Cursor::new_at(link_to.rel(), 0, SEQ_MESSAGE_NUM));
         let header = HDF::new(msg_link)
             .with_previous_msg_link(Bytes(link_to.to_bytes()))
             .with_content_type(SEQUENCE)?
@@ -761,9 +1022,8 @@ where
             Some(cursor) => {
                 let mut cursor = cursor.clone();
                 if (self.flags & FLAG_BRANCHING_MASK) != 0 {
-                    let msg_link = self
-                        .link_gen
-                        .link_from(identifier.to_bytes(), Cursor::new_at(&cursor.link, 0, SEQ_MESSAGE_NUM));
+                    let msg_link =
+                        self.link_from(identifier.to_bytes(), Cursor::new_at(&cursor.link, 0, SEQ_MESSAGE_NUM));
                     let previous_msg_link = Link::from_base_rel(self.appinst.as_ref().unwrap().base(), &cursor.link);
                     let header = HDF::new(msg_link)
                         .with_previous_msg_link(Bytes(previous_msg_link.to_bytes()))
@@ -788,9 +1048,8 @@ where
                 } else if self.is_single_depth() {
                     Ok(WrappedSequence::SingleDepth(cursor))
                 } else {
-                    let msg_link = self
-                        .link_gen
-                        .link_from(self.sig_kp.public, Cursor::new_at(&ref_link.clone(), 0, cursor.seq_no));
+                    let msg_link =
+                        self.link_from(self.sig_kp.public, Cursor::new_at(&ref_link.clone(), 0, cursor.seq_no));
                     cursor.link = msg_link.rel().clone();
                     Ok(WrappedSequence::single_branch(cursor))
                 }
@@ -837,7 +1096,7 @@ where
     ) -> Result<GenericMessage<Link, sequence::ContentUnwrap<Link>>> {
         let preparsed = msg.parse_header()?;
         let sender_id = preparsed.header.sender_id;
-        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0);
+        let prev_link = Link::from_bytes(&preparsed.header.previous_msg_link.0)?;
         let content = self
             .unwrap_sequence(preparsed)?
             .commit(self.link_store.borrow_mut(), info)?;
@@ -847,12 +1106,42 @@ where
         Ok(GenericMessage::new(msg.link, prev_link, content))
     }
 
+    /// Unwrap a message of any content type without committing it and return whether it
+    /// authenticates. A keyload the user has no key in unwraps without being authenticated: it
+    /// cannot be told apart from a forged one.
+    pub fn authenticate(&self, msg: &BinaryMessage<F, Link>) -> Result<bool> {
+        let preparsed = msg.parse_header()?;
+        match preparsed.content_type() {
+            ANNOUNCE => self.unwrap_announcement(preparsed).map(|_| true),
+            SUBSCRIBE => self.unwrap_subscribe(preparsed).map(|_| true),
+            UNSUBSCRIBE => self.unwrap_unsubscribe(preparsed).map(|_| true),
+            KEYLOAD => self
+                .unwrap_keyload(preparsed)
+                .map(|unwrapped| unwrapped.pcf.content.key.is_some()),
+            SIGNED_PACKET => self.unwrap_signed_packet(preparsed).map(|_| true),
+            TAGGED_PACKET => self.unwrap_tagged_packet(preparsed).map(|_| true),
+            SEQUENCE => self.unwrap_sequence(preparsed).map(|_| true),
+            unknown_content => err!(UnknownMsgType(unknown_content)),
+        }
+    }
+
+    /// Unwrap a signed or tagged packet without committing it and return its authenticated publish
+    /// timestamp, `None` for other messages.
+    pub fn packet_timestamp(&self, msg: &BinaryMessage<F, Link>) -> Result<Option<u64>> {
+        let preparsed = msg.parse_header()?;
+        match preparsed.content_type() {
+            SIGNED_PACKET => Ok(self.unwrap_signed_packet(preparsed)?.pcf.content.timestamp),
+            TAGGED_PACKET => Ok(self.unwrap_tagged_packet(preparsed)?.pcf.content.timestamp),
+            _ => Ok(None),
+        }
+    }
+
     pub fn is_multi_branching(&self) -> bool {
         (self.flags & FLAG_BRANCHING_MASK) != 0
     }
 
     pub fn is_single_depth(&self) -> bool {
-        self.flags == 2
+        (self.flags & !announce::FLAG_PRIVATE_LINKS_MASK) == 2
     }
 
     // TODO: own seq_no should be stored outside of pk_store to avoid lookup and Option
@@ -897,9 +1186,26 @@ where
         }
     }
 
+    /// Forget a subscriber, it will not be included in subsequent keyloads.
+    pub fn remove_subscriber(&mut self, pk: &ed25519::PublicKey) -> Result<()> {
+        let identifier = Identifier::EdPubKey((*pk).into());
+        try_or!(*pk != self.sig_kp.public, BadIdentifier)?;
+        try_or!(
+            self.key_store.remove(&identifier),
+            SubscriberNotFound(hex::encode(&identifier.to_bytes()))
+        )?;
+        Ok(())
+    }
+
+    /// Forget a pre-shared key, it will not be included in subsequent keyloads.
+    pub fn remove_psk(&mut self, pskid: PskId) -> Result<()> {
+        try_or!(self.key_store.remove(&pskid.into()), PskNotFound)?;
+        Ok(())
+    }
+
     fn gen_next_msg_id(
+        &self,
         ids: &mut Vec<(Identifier, Cursor<Link>)>,
-        link_gen: &LG,
         pk_info: (&Identifier, &Cursor<<Link as HasLink>::Rel>),
         branching: bool,
     ) {
@@ -913,10 +1219,10 @@ where
         ) = pk_info;
 
         if branching {
-            let msg_id = link_gen.link_from(id.to_bytes(), Cursor::new_at(&*seq_link, 0, 1));
+            let msg_id = self.link_from(id.to_bytes(), Cursor::new_at(&*seq_link, 0, 1));
             ids.push((*id, Cursor::new_at(msg_id, 0, 1)));
         } else {
-            let msg_id = link_gen.link_from(id.to_bytes(), Cursor::new_at(&*seq_link, 0, *seq_no));
+            let msg_id = self.link_from(id.to_bytes(), Cursor::new_at(&*seq_link, 0, *seq_no));
             ids.push((*id, Cursor::new_at(msg_id, 0, *seq_no)));
         }
     }
@@ -927,7 +1233,7 @@ where
 
         // TODO: Do the same for self.sig_kp.public
         for pk_info in self.key_store.iter() {
-            Self::gen_next_msg_id(&mut ids, &self.link_gen, pk_info, branching);
+            self.gen_next_msg_id(&mut ids, pk_info, branching);
         }
         ids
     }
@@ -984,6 +1290,206 @@ where
     }
 }
 
+/// Part of the user state identifying the channel: its address and the author's public key.
+/// Shared by the exported user state and the state store.
+#[derive(Clone, Default)]
+pub(crate) struct ChannelIdentity<Link> {
+    pub(crate) appinst: Option<Link>,
+    pub(crate) author_sig_pk: Option<ed25519::PublicKey>,
+    pub(crate) author_sig_pk_pinned: bool,
+}
+
+impl<F: PRP, Link: AbsorbFallback<F>> ContentSizeof<F> for ChannelIdentity<Link> {
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        let oneof_appinst = Uint8(if self.appinst.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_appinst)?;
+        if let Some(ref appinst) = self.appinst {
+            ctx.absorb(<&Fallback<Link>>::from(appinst))?;
+        }
+
+        // A pinned key is told apart from a received one
+        let oneof_author_sig_pk = Uint8(match (&self.author_sig_pk, self.author_sig_pk_pinned) {
+            (None, _) => 0,
+            (Some(_), false) => 1,
+            (Some(_), true) => 2,
+        });
+        ctx.absorb(&oneof_author_sig_pk)?;
+        if let Some(ref author_sig_pk) = self.author_sig_pk {
+            ctx.absorb(author_sig_pk)?;
+        }
+        Ok(ctx)
+    }
+}
+
+impl<F: PRP, Link: AbsorbFallback<F>, Store> ContentWrap<F, Store> for ChannelIdentity<Link> {
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        _store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        let oneof_appinst = Uint8(if self.appinst.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_appinst)?;
+        if let Some(ref appinst) = self.appinst {
+            ctx.absorb(<&Fallback<Link>>::from(appinst))?;
+        }
+
+        // A pinned key is told apart from a received one
+        let oneof_author_sig_pk = Uint8(match (&self.author_sig_pk, self.author_sig_pk_pinned) {
+            (None, _) => 0,
+            (Some(_), false) => 1,
+            (Some(_), true) => 2,
+        });
+        ctx.absorb(&oneof_author_sig_pk)?;
+        if let Some(ref author_sig_pk) = self.author_sig_pk {
+            ctx.absorb(author_sig_pk)?;
+        }
+        Ok(ctx)
+    }
+}
+
+impl<F: PRP, Link: AbsorbFallback<F> + Default, Store> ContentUnwrap<F, Store> for ChannelIdentity<Link> {
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        _store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        let mut oneof_appinst = Uint8(0);
+        ctx.absorb(&mut oneof_appinst)?
+            .guard(oneof_appinst.0 < 2, AppInstRecoveryFailure(oneof_appinst.0))?;
+        self.appinst = if oneof_appinst.0 == 1 {
+            let mut appinst = Link::default();
+            ctx.absorb(<&mut Fallback<Link>>::from(&mut appinst))?;
+            Some(appinst)
+        } else {
+            None
+        };
+
+        let mut oneof_author_sig_pk = Uint8(0);
+        ctx.absorb(&mut oneof_author_sig_pk)?.guard(
+            oneof_author_sig_pk.0 < 3,
+            AuthorSigPkRecoveryFailure(oneof_author_sig_pk.0),
+        )?;
+        self.author_sig_pk_pinned = oneof_author_sig_pk.0 == 2;
+        self.author_sig_pk = if oneof_author_sig_pk.0 != 0 {
+            let mut author_sig_pk = ed25519::PublicKey::default();
+            ctx.absorb(&mut author_sig_pk)?;
+            Some(author_sig_pk)
+        } else {
+            None
+        };
+        Ok(ctx)
+    }
+}
+
+/// Part of the user state describing the channel: the anchor of a single depth channel and the
+/// channel metadata. Shared by the exported user state and the state store.
+#[derive(Clone, Default)]
+pub(crate) struct ChannelDescription<Link> {
+    pub(crate) anchor: Option<Cursor<Link>>,
+    pub(crate) channel_metadata: Option<announce::ChannelMetadata>,
+}
+
+impl<F: PRP, Link: AbsorbFallback<F>> ContentSizeof<F> for ChannelDescription<Link> {
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        let oneof_anchor = Uint8(if self.anchor.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_anchor)?;
+        if let Some(ref anchor) = self.anchor {
+            ctx.absorb(<&Fallback<Link>>::from(&anchor.link))?
+                .absorb(Uint32(anchor.branch_no))?
+                .absorb(Uint32(anchor.seq_no))?;
+        }
+
+        let oneof_channel_metadata = Uint8(if self.channel_metadata.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_channel_metadata)?;
+        if let Some(ref channel_metadata) = self.channel_metadata {
+            channel_metadata.sizeof(ctx)?;
+        }
+        Ok(ctx)
+    }
+}
+
+impl<F: PRP, Link: AbsorbFallback<F>, Store> ContentWrap<F, Store> for ChannelDescription<Link> {
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        let oneof_anchor = Uint8(if self.anchor.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_anchor)?;
+        if let Some(ref anchor) = self.anchor {
+            ctx.absorb(<&Fallback<Link>>::from(&anchor.link))?
+                .absorb(Uint32(anchor.branch_no))?
+                .absorb(Uint32(anchor.seq_no))?;
+        }
+
+        let oneof_channel_metadata = Uint8(if self.channel_metadata.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_channel_metadata)?;
+        if let Some(ref channel_metadata) = self.channel_metadata {
+            channel_metadata.wrap(store, ctx)?;
+        }
+        Ok(ctx)
+    }
+}
+
+impl<F: PRP, Link: AbsorbFallback<F> + Default, Store> ContentUnwrap<F, Store> for ChannelDescription<Link> {
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        let mut oneof_anchor = Uint8(0);
+        ctx.absorb(&mut oneof_anchor)?
+            .guard(oneof_anchor.0 < 2, AnchorRecoveryFailure(oneof_anchor.0))?;
+        self.anchor = if oneof_anchor.0 == 1 {
+            let mut link = Link::default();
+            let mut branch_no = Uint32(0);
+            let mut seq_no = Uint32(0);
+            ctx.absorb(<&mut Fallback<Link>>::from(&mut link))?
+                .absorb(&mut branch_no)?
+                .absorb(&mut seq_no)?;
+            Some(Cursor::new_at(link, branch_no.0, seq_no.0))
+        } else {
+            None
+        };
+
+        let mut oneof_channel_metadata = Uint8(0);
+        ctx.absorb(&mut oneof_channel_metadata)?.guard(
+            oneof_channel_metadata.0 < 2,
+            ChannelMetadataRecoveryFailure(oneof_channel_metadata.0),
+        )?;
+        self.channel_metadata = if oneof_channel_metadata.0 == 1 {
+            let mut channel_metadata = announce::ChannelMetadata::default();
+            channel_metadata.unwrap(store, ctx)?;
+            Some(channel_metadata)
+        } else {
+            None
+        };
+        Ok(ctx)
+    }
+}
+
+impl<F, Link, LG, LS, Keys> User<F, Link, LG, LS, Keys>
+where
+    F: PRP,
+    Link: HasLink,
+    Keys: KeyStore<Cursor<<Link as HasLink>::Rel>, F>,
+{
+    pub(crate) fn channel_identity(&self) -> ChannelIdentity<Link> {
+        ChannelIdentity {
+            appinst: self.appinst.clone(),
+            author_sig_pk: self.author_sig_pk,
+            author_sig_pk_pinned: self.author_sig_pk_pinned,
+        }
+    }
+
+    pub(crate) fn channel_description(&self) -> ChannelDescription<Link> {
+        ChannelDescription {
+            anchor: self.anchor.clone(),
+            channel_metadata: self.channel_metadata.clone(),
+        }
+    }
+}
+
 impl<F, Link, LG, LS, Keys> ContentSizeof<F> for User<F, Link, LG, LS, Keys>
 where
     F: PRP,
@@ -1001,17 +1507,7 @@ where
             .absorb(<&Bytes>::from(&self.message_encoding))?
             .absorb(Uint64(self.uniform_payload_length as u64))?;
 
-        let oneof_appinst = Uint8(if self.appinst.is_some() { 1 } else { 0 });
-        ctx.absorb(&oneof_appinst)?;
-        if let Some(ref appinst) = self.appinst {
-            ctx.absorb(<&Fallback<Link>>::from(appinst))?;
-        }
-
-        let oneof_author_sig_pk = Uint8(if self.author_sig_pk.is_some() { 1 } else { 0 });
-        ctx.absorb(&oneof_author_sig_pk)?;
-        if let Some(ref author_sig_pk) = self.author_sig_pk {
-            ctx.absorb(author_sig_pk)?;
-        }
+        self.channel_identity().sizeof(ctx)?;
 
         let link_store = self.link_store.borrow();
         let links = link_store.iter();
@@ -1033,10 +1529,20 @@ where
                 ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(&cursor.link))?
                     .absorb(Uint32(cursor.branch_no))?
                     .absorb(Uint32(cursor.seq_no))?;
+                if let Identifier::PskId(_) = id {
+                    let psk = self.key_store.get_psk(id);
+                    let oneof_psk = Uint8(if psk.is_some() { 1 } else { 0 });
+                    ctx.absorb(&oneof_psk)?;
+                    if let Some(psk) = psk {
+                        ctx.mask(<&NBytes<psk::PskSize>>::from(&psk))?;
+                    }
+                }
                 Ok(ctx)
             })?
-            .commit()?
-            .squeeze(Mac(32))?;
+            .absorb(Uint8(if self.use_psk { 1 } else { 0 }))?;
+        self.channel_description().sizeof(ctx)?;
+
+        ctx.commit()?.squeeze(Mac(32))?;
         Ok(ctx)
     }
 }
@@ -1055,7 +1561,7 @@ where
 {
     fn wrap<'c, OS: io::OStream>(
         &self,
-        _store: &Store,
+        store: &Store,
         ctx: &'c mut wrap::Context<F, OS>,
     ) -> Result<&'c mut wrap::Context<F, OS>> {
         ctx.mask(<&NBytes<U32>>::from(&self.sig_kp.secret.as_bytes()[..]))?
@@ -1063,17 +1569,7 @@ where
             .absorb(<&Bytes>::from(&self.message_encoding))?
             .absorb(Uint64(self.uniform_payload_length as u64))?;
 
-        let oneof_appinst = Uint8(if self.appinst.is_some() { 1 } else { 0 });
-        ctx.absorb(&oneof_appinst)?;
-        if let Some(ref appinst) = self.appinst {
-            ctx.absorb(<&Fallback<Link>>::from(appinst))?;
-        }
-
-        let oneof_author_sig_pk = Uint8(if self.author_sig_pk.is_some() { 1 } else { 0 });
-        ctx.absorb(&oneof_author_sig_pk)?;
-        if let Some(ref author_sig_pk) = self.author_sig_pk {
-            ctx.absorb(author_sig_pk)?;
-        }
+        self.channel_identity().wrap(store, ctx)?;
 
         let link_store = self.link_store.borrow();
         let links = link_store.iter();
@@ -1091,14 +1587,24 @@ where
             })?
             .absorb(repeated_keys)?
             .repeated(keys.into_iter(), |ctx, (id, cursor)| {
-                let ctx = id.wrap(_store, ctx)?;
+                let ctx = id.wrap(store, ctx)?;
                 ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(&cursor.link))?
                     .absorb(Uint32(cursor.branch_no))?
                     .absorb(Uint32(cursor.seq_no))?;
+                if let Identifier::PskId(_) = id {
+                    let psk = self.key_store.get_psk(id);
+                    let oneof_psk = Uint8(if psk.is_some() { 1 } else { 0 });
+                    ctx.absorb(&oneof_psk)?;
+                    if let Some(psk) = psk {
+                        ctx.mask(<&NBytes<psk::PskSize>>::from(&psk))?;
+                    }
+                }
                 Ok(ctx)
             })?
-            .commit()?
-            .squeeze(Mac(32))?;
+            .absorb(Uint8(if self.use_psk { 1 } else { 0 }))?;
+        self.channel_description().wrap(store, ctx)?;
+
+        ctx.commit()?.squeeze(Mac(32))?;
         Ok(ctx)
     }
 }
@@ -1117,9 +1623,35 @@ where
 {
     fn unwrap<'c, IS: io::IStream>(
         &mut self,
-        _store: &Store,
+        store: &Store,
         ctx: &'c mut unwrap::Context<F, IS>,
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        self.unwrap_versioned(EXPORT_VERSION, store, ctx)
+    }
+}
+
+impl<F, Link, LG, LS, Keys> User<F, Link, LG, LS, Keys>
+where
+    F: PRP,
+    Link: HasLink + AbsorbExternalFallback<F> + AbsorbFallback<F>,
+    <Link as HasLink>::Base: Eq + fmt::Debug + fmt::Display,
+    <Link as HasLink>::Rel: Eq + fmt::Debug + SkipFallback<F> + AbsorbFallback<F>,
+    LG: LinkGenerator<Link>,
+    LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
+    <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: Default + AbsorbFallback<F>,
+    Keys: KeyStore<Cursor<<Link as HasLink>::Rel>, F> + Default,
+{
+    /// Unwrap user state exported with the given format `version`.
+    fn unwrap_versioned<'c, Store, IS>(
+        &mut self,
+        version: u8,
+        store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>>
+    where
+        Store: LinkStore<F, <Link as HasLink>::Rel>,
+        IS: io::IStream,
+    {
         let mut sig_sk_bytes = NBytes::<U32>::default();
         let mut flags = Uint8(0);
         let mut message_encoding = Bytes::new();
@@ -1131,31 +1663,13 @@ where
             .absorb(&mut message_encoding)?
             .absorb(&mut uniform_payload_length)?;
 
-        let mut oneof_appinst = Uint8(0);
-        ctx.absorb(&mut oneof_appinst)?
-            .guard(oneof_appinst.0 < 2, AppInstRecoveryFailure(oneof_appinst.0))?;
-
-        let appinst = if oneof_appinst.0 == 1 {
-            let mut appinst = Link::default();
-            ctx.absorb(<&mut Fallback<Link>>::from(&mut appinst))?;
-            Some(appinst)
-        } else {
-            None
-        };
-
-        let mut oneof_author_sig_pk = Uint8(0);
-        ctx.absorb(&mut oneof_author_sig_pk)?.guard(
-            oneof_author_sig_pk.0 < 2,
-            AuthorSigPkRecoveryFailure(oneof_author_sig_pk.0),
-        )?;
-
-        let author_sig_pk = if oneof_author_sig_pk.0 == 1 {
-            let mut author_sig_pk = ed25519::PublicKey::default();
-            ctx.absorb(&mut author_sig_pk)?;
-            Some(author_sig_pk)
-        } else {
-            None
-        };
+        let mut identity = ChannelIdentity::<Link>::default();
+        identity.unwrap(store, ctx)?;
+        let ChannelIdentity {
+            appinst,
+            author_sig_pk,
+            author_sig_pk_pinned,
+        } = identity;
 
         let mut repeated_links = Size(0);
         let mut link_store = LS::default();
@@ -1171,18 +1685,48 @@ where
 
         let mut repeated_keys = Size(0);
         let mut key_store = Keys::default();
-        ctx.absorb(&mut repeated_keys)?
-            .repeated(repeated_keys, |ctx| {
-                let mut link = Fallback(<Link as HasLink>::Rel::default());
-                let mut branch_no = Uint32(0);
-                let mut seq_no = Uint32(0);
-                let (id, ctx) = Identifier::unwrap_new(_store, ctx)?;
-                ctx.absorb(&mut link)?.absorb(&mut branch_no)?.absorb(&mut seq_no)?;
-                key_store.insert_cursor(id, Cursor::new_at(link.0, branch_no.0, seq_no.0))?;
-                Ok(ctx)
-            })?
-            .commit()?
-            .squeeze(Mac(32))?;
+        ctx.absorb(&mut repeated_keys)?.repeated(repeated_keys, |ctx| {
+            let mut link = Fallback(<Link as HasLink>::Rel::default());
+            let mut branch_no = Uint32(0);
+            let mut seq_no = Uint32(0);
+            let (id, ctx) = Identifier::unwrap_new(store, ctx)?;
+            ctx.absorb(&mut link)?.absorb(&mut branch_no)?.absorb(&mut seq_no)?;
+            let cursor = Cursor::new_at(link.0, branch_no.0, seq_no.0);
+            match id {
+                // Version 0 did not export pre-shared keys
+                Identifier::PskId(_) if version > 0 => {
+                    let mut oneof_psk = Uint8(0);
+                    ctx.absorb(&mut oneof_psk)?
+                        .guard(oneof_psk.0 < 2, PskRecoveryFailure(oneof_psk.0))?;
+                    let psk = if oneof_psk.0 == 1 {
+                        let mut psk = NBytes::<psk::PskSize>::default();
+                        ctx.mask(&mut psk)?;
+                        Some(psk.into())
+                    } else {
+                        None
+                    };
+                    key_store.insert_psk(id, psk, cursor)?;
+                }
+                _ => key_store.insert_cursor(id, cursor)?,
+            }
+            Ok(ctx)
+        })?;
+
+        let mut use_psk = Uint8(0);
+        let (anchor, channel_metadata) = if version > 0 {
+            let mut description = ChannelDescription::<Link>::default();
+            ctx.absorb(&mut use_psk)?;
+            description.unwrap(store, ctx)?;
+            (description.anchor, description.channel_metadata)
+        } else {
+            // Version 0 did not export the anchor, the best guess is the channel announcement.
+            let anchor = appinst
+                .as_ref()
+                .map(|appinst| Cursor::new_at(appinst.clone(), 0, 2_u32));
+            (anchor, None)
+        };
+
+        ctx.commit()?.squeeze(Mac(32))?;
 
         let sig_sk = ed25519::SecretKey::from_bytes(sig_sk_bytes.as_ref()).unwrap();
         let sig_pk = ed25519::PublicKey::from(&sig_sk);
@@ -1194,6 +1738,7 @@ where
         self.link_store = RefCell::new(link_store);
         self.key_store = key_store;
         self.author_sig_pk = author_sig_pk;
+        self.author_sig_pk_pinned = author_sig_pk_pinned;
         if let Some(ref seed) = appinst {
             self.link_gen.reset(seed.clone());
         }
@@ -1201,6 +1746,9 @@ where
         self.flags = flags.0;
         self.message_encoding = message_encoding.0;
         self.uniform_payload_length = uniform_payload_length.0 as usize;
+        self.use_psk = use_psk.0 != 0;
+        self.anchor = anchor;
+        self.channel_metadata = channel_metadata;
         Ok(ctx)
     }
 }
@@ -1216,11 +1764,45 @@ where
     <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: AbsorbFallback<F>,
     Keys: KeyStore<Cursor<<Link as HasLink>::Rel>, F>,
 {
+    /// Export user state encrypted with a key derived from `pwd` with default KDF parameters.
+    #[cfg(feature = "std")]
     pub fn export(&self, flag: u8, pwd: &str) -> Result<Vec<u8>> {
-        const VERSION: u8 = 0;
+        self.export_with_kdf(flag, pwd, &prng::KdfParams::default())
+    }
+
+    /// Export user state encrypted with a key derived from `pwd` with the given KDF parameters,
+    /// salted from the thread RNG.
+    #[cfg(feature = "std")]
+    pub fn export_with_kdf(&self, flag: u8, pwd: &str, kdf: &prng::KdfParams) -> Result<Vec<u8>> {
+        self.export_with_salt(flag, pwd, kdf, NBytes::<U32>(prng::random_key()))
+    }
+
+    /// Export user state encrypted with a key derived from `pwd` with the given KDF parameters,
+    /// salted from `rng`. Available without `std`.
+    pub fn export_with_rng<R>(&self, flag: u8, pwd: &str, kdf: &prng::KdfParams, rng: &mut R) -> Result<Vec<u8>>
+    where
+        R: prng::RngCore + prng::CryptoRng,
+    {
+        self.export_with_salt(flag, pwd, kdf, NBytes::<U32>(prng::random_bytes(rng)))
+    }
+
+    /// The salt and the KDF parameters are stored in the clear in the header, so that `import` can
+    /// derive the same key.
+    fn export_with_salt(&self, flag: u8, pwd: &str, kdf: &prng::KdfParams, salt: NBytes<U32>) -> Result<Vec<u8>> {
+        let memory_kib = Uint32(kdf.memory_kib);
+        let iterations = Uint32(kdf.iterations);
+        let key = NBytes::<U32>(
+            prng::from_password::<F>(EXPORT_KEY_DOMAIN, pwd, salt.as_slice(), kdf)?.gen_arr("user export key"),
+        );
+
         let buf_size = {
             let mut ctx = sizeof::Context::<F>::new();
-            ctx.absorb(Uint8(VERSION))?.absorb(Uint8(flag))?;
+            ctx.absorb(Uint8(EXPORT_VERSION))?
+                .absorb(Uint8(flag))?
+                .absorb(&salt)?
+                .absorb(memory_kib)?
+                .absorb(iterations)?
+                .absorb(External(&key))?;
             self.sizeof(&mut ctx)?;
             ctx.get_size()
         };
@@ -1229,10 +1811,11 @@ where
 
         {
             let mut ctx = wrap::Context::new(&mut buf[..]);
-            let prng = prng::from_seed::<F>("IOTA Streams Channels app", pwd);
-            let key = NBytes::<U32>(prng.gen_arr("user export key"));
-            ctx.absorb(Uint8(VERSION))?
+            ctx.absorb(Uint8(EXPORT_VERSION))?
                 .absorb(Uint8(flag))?
+                .absorb(&salt)?
+                .absorb(memory_kib)?
+                .absorb(iterations)?
                 .absorb(External(&key))?;
             let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
             self.wrap(&store, &mut ctx)?;
@@ -1254,25 +1837,61 @@ where
     <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: Default + AbsorbFallback<F>,
     Keys: KeyStore<Cursor<<Link as HasLink>::Rel>, F> + Default,
 {
+    /// Import user state exported with the current or any older format version, with a KDF cost
+    /// within the default [`prng::KdfLimits`].
     pub fn import(bytes: &[u8], flag: u8, pwd: &str) -> Result<Self> {
-        const VERSION: u8 = 0;
+        Self::import_with_limits(bytes, flag, pwd, &prng::KdfLimits::default())
+    }
 
+    /// Import user state exported with the current or any older format version. State whose KDF
+    /// cost exceeds `limits` is rejected before deriving the key.
+    pub fn import_with_limits(bytes: &[u8], flag: u8, pwd: &str, limits: &prng::KdfLimits) -> Result<Self> {
+        let (user, len) = Self::import_prefix_with_limits(bytes, flag, pwd, limits)?;
+        try_or!(len == bytes.len(), InputStreamNotFullyConsumed(bytes.len() - len))?;
+        Ok(user)
+    }
+
+    /// Import user state exported at the start of `bytes`, like `import_with_limits`, and return
+    /// it along with its length: the bytes following it are left to the caller.
+    pub fn import_prefix_with_limits(
+        bytes: &[u8],
+        flag: u8,
+        pwd: &str,
+        limits: &prng::KdfLimits,
+    ) -> Result<(Self, usize)> {
         let mut ctx = unwrap::Context::new(bytes);
-        let prng = prng::from_seed::<F>("IOTA Streams Channels app", pwd);
-        let key = NBytes::<U32>(prng.gen_arr("user export key"));
         let mut version = Uint8(0);
         let mut flag2 = Uint8(0);
         ctx.absorb(&mut version)?
-            .guard(version.0 == VERSION, UserVersionRecoveryFailure(VERSION, version.0))?
+            .guard(
+                version.0 <= EXPORT_VERSION,
+                UserVersionRecoveryFailure(EXPORT_VERSION, version.0),
+            )?
             .absorb(&mut flag2)?
-            .guard(flag2.0 == flag, UserFlagRecoveryFailure(flag, flag2.0))?
-            .absorb(External(&key))?;
+            .guard(flag2.0 == flag, UserFlagRecoveryFailure(flag, flag2.0))?;
+
+        let key = if version.0 == 0 {
+            // Version 0 used an unsalted key derived directly from the password.
+            let prng = prng::from_seed::<F>(EXPORT_KEY_DOMAIN, pwd);
+            NBytes::<U32>(prng.gen_arr("user export key"))
+        } else {
+            let mut salt = NBytes::<U32>::default();
+            let mut memory_kib = Uint32(0);
+            let mut iterations = Uint32(0);
+            ctx.absorb(&mut salt)?
+                .absorb(&mut memory_kib)?
+                .absorb(&mut iterations)?;
+            let kdf = prng::KdfParams::new(memory_kib.0, iterations.0);
+            try_or!(kdf.is_within(limits), KdfCostAboveLimit(kdf.memory_kib, kdf.iterations))?;
+            let prng = prng::from_password::<F>(EXPORT_KEY_DOMAIN, pwd, salt.as_slice(), &kdf)?;
+            NBytes::<U32>(prng.gen_arr("user export key"))
+        };
+        ctx.absorb(External(&key))?;
 
         let mut user = User::default();
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
-        user.unwrap(&store, &mut ctx)?;
-        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
-        Ok(user)
+        user.unwrap_versioned(version.0, &store, &mut ctx)?;
+        Ok((user, bytes.len() - ctx.stream.len()))
     }
 }
 
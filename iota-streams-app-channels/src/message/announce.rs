@@ -7,21 +7,52 @@
 //! ```ddml
 //! message Announce {
 //!     absorb u8 ed25519pk[32];
+//!     absorb u8 flags;
+//!     oneof {
+//!         null;
+//!         Metadata metadata;
+//!     }
 //!     commit;
 //!     squeeze external u8 tag[32];
 //!     ed25519(tag) sig;
 //! }
+//!
+//! Metadata {
+//!     absorb bytes name;
+//!     absorb bytes description;
+//!     absorb bytes content_schema;
+//!     absorb size_t n;
+//!     repeated(n) {
+//!         absorb bytes key;
+//!         absorb bytes value;
+//!     }
+//! }
 //! ```
 //!
 //! # Fields
 //!
 //! * `ed25519pk` -- channel owner's Ed25519 public key.
 //!
+//! * `flags` -- channel flags, `FLAG_METADATA_MASK` bit selects the `metadata` oneof branch, `FLAG_PRIVATE_LINKS_MASK`
+//!   bit marks a channel deriving links from message secrets.
+//!
+//! * `metadata` -- optional UTF-8 encoded channel description: name, description, content schema identifier and
+//!   free-form key/value properties.
+//!
 //! * `tag` -- hash-value to be signed.
 //!
 //! * `sig` -- signature of `tag` field produced with the Ed25519 private key corresponding to ed25519pk`.
 
-use iota_streams_core::Result;
+use iota_streams_core::{
+    prelude::{
+        String,
+        Vec,
+    },
+    wrapped_err,
+    Errors::InvalidUtf8String,
+    Result,
+    WrappedError,
+};
 
 use iota_streams_app::message;
 use iota_streams_core::sponge::prp::PRP;
@@ -35,9 +66,100 @@ use iota_streams_ddml::{
     types::*,
 };
 
+/// Flags bit marking the presence of channel metadata in the announcement.
+pub const FLAG_METADATA_MASK: u8 = 0x80;
+
+/// Flags bit marking a channel whose message links are derived from the secret state of the
+/// message they link to, hiding them from the users who can't read it.
+pub const FLAG_PRIVATE_LINKS_MASK: u8 = 0x40;
+
+/// Descriptive channel metadata, signed by the channel owner as part of the announcement.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ChannelMetadata {
+    /// Human readable channel name.
+    pub name: String,
+    /// Free-form channel description.
+    pub description: String,
+    /// Identifier of the schema of the channel message payloads.
+    pub content_schema: String,
+    /// Free-form key/value properties.
+    pub properties: Vec<(String, String)>,
+}
+
+fn to_bytes(s: &str) -> Bytes {
+    Bytes(s.as_bytes().to_vec())
+}
+
+fn to_string(bytes: Bytes) -> Result<String> {
+    String::from_utf8(bytes.0).map_err(|e| wrapped_err!(InvalidUtf8String, WrappedError(e)))
+}
+
+impl<F: PRP> message::ContentSizeof<F> for ChannelMetadata {
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        ctx.absorb(&to_bytes(&self.name))?
+            .absorb(&to_bytes(&self.description))?
+            .absorb(&to_bytes(&self.content_schema))?
+            .absorb(Size(self.properties.len()))?
+            .repeated(self.properties.iter(), |ctx, (key, value)| {
+                ctx.absorb(&to_bytes(key))?.absorb(&to_bytes(value))?;
+                Ok(ctx)
+            })?;
+        Ok(ctx)
+    }
+}
+
+impl<F: PRP, Store> message::ContentWrap<F, Store> for ChannelMetadata {
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        _store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        ctx.absorb(&to_bytes(&self.name))?
+            .absorb(&to_bytes(&self.description))?
+            .absorb(&to_bytes(&self.content_schema))?
+            .absorb(Size(self.properties.len()))?
+            .repeated(self.properties.iter(), |ctx, (key, value)| {
+                ctx.absorb(&to_bytes(key))?.absorb(&to_bytes(value))?;
+                Ok(ctx)
+            })?;
+        Ok(ctx)
+    }
+}
+
+impl<F: PRP, Store> message::ContentUnwrap<F, Store> for ChannelMetadata {
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        _store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        let mut name = Bytes::default();
+        let mut description = Bytes::default();
+        let mut content_schema = Bytes::default();
+        let mut repeated_properties = Size(0);
+        let mut properties = Vec::new();
+        ctx.absorb(&mut name)?
+            .absorb(&mut description)?
+            .absorb(&mut content_schema)?
+            .absorb(&mut repeated_properties)?
+            .repeated(repeated_properties, |ctx| {
+                let mut key = Bytes::default();
+                let mut value = Bytes::default();
+                ctx.absorb(&mut key)?.absorb(&mut value)?;
+                properties.push((to_string(key)?, to_string(value)?));
+                Ok(ctx)
+            })?;
+        self.name = to_string(name)?;
+        self.description = to_string(description)?;
+        self.content_schema = to_string(content_schema)?;
+        self.properties = properties;
+        Ok(ctx)
+    }
+}
+
 pub struct ContentWrap<'a, F> {
     sig_kp: &'a ed25519::Keypair,
     flags: Uint8,
+    metadata: Option<&'a ChannelMetadata>,
     _phantom: core::marker::PhantomData<F>,
 }
 
@@ -45,16 +167,28 @@ impl<'a, F> ContentWrap<'a, F> {
     pub fn new(sig_kp: &'a ed25519::Keypair, flags: u8) -> Self {
         Self {
             sig_kp,
-            flags: Uint8(flags),
+            flags: Uint8(flags & !FLAG_METADATA_MASK),
+            metadata: None,
             _phantom: core::marker::PhantomData,
         }
     }
+
+    pub fn with_metadata(mut self, metadata: Option<&'a ChannelMetadata>) -> Self {
+        if metadata.is_some() {
+            self.flags.0 |= FLAG_METADATA_MASK;
+        }
+        self.metadata = metadata;
+        self
+    }
 }
 
 impl<'a, F: PRP> message::ContentSizeof<F> for ContentWrap<'a, F> {
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
         ctx.absorb(&self.sig_kp.public)?;
         ctx.absorb(&self.flags)?;
+        if let Some(metadata) = self.metadata {
+            metadata.sizeof(ctx)?;
+        }
         ctx.ed25519(self.sig_kp, HashSig)?;
         Ok(ctx)
     }
@@ -63,11 +197,14 @@ impl<'a, F: PRP> message::ContentSizeof<F> for ContentWrap<'a, F> {
 impl<'a, F: PRP, Store> message::ContentWrap<F, Store> for ContentWrap<'a, F> {
     fn wrap<'c, OS: io::OStream>(
         &self,
-        _store: &Store,
+        store: &Store,
         ctx: &'c mut wrap::Context<F, OS>,
     ) -> Result<&'c mut wrap::Context<F, OS>> {
         ctx.absorb(&self.sig_kp.public)?;
         ctx.absorb(&self.flags)?;
+        if let Some(metadata) = self.metadata {
+            metadata.wrap(store, ctx)?;
+        }
         ctx.ed25519(self.sig_kp, HashSig)?;
         Ok(ctx)
     }
@@ -78,7 +215,9 @@ pub struct ContentUnwrap<F> {
 
     #[allow(dead_code)]
     pub(crate) ke_pk: x25519::PublicKey,
+    /// Channel flags with the `FLAG_METADATA_MASK` bit cleared.
     pub(crate) flags: Uint8,
+    pub(crate) metadata: Option<ChannelMetadata>,
     _phantom: core::marker::PhantomData<F>,
 }
 
@@ -92,6 +231,7 @@ impl<F> Default for ContentUnwrap<F> {
             sig_pk,
             ke_pk,
             flags,
+            metadata: None,
             _phantom: core::marker::PhantomData,
         }
     }
@@ -103,12 +243,18 @@ where
 {
     fn unwrap<'c, IS: io::IStream>(
         &mut self,
-        _store: &Store,
+        store: &Store,
         ctx: &'c mut unwrap::Context<F, IS>,
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
         ctx.absorb(&mut self.sig_pk)?;
         self.ke_pk = x25519::public_from_ed25519(&self.sig_pk)?;
         ctx.absorb(&mut self.flags)?;
+        if self.flags.0 & FLAG_METADATA_MASK != 0 {
+            self.flags.0 &= !FLAG_METADATA_MASK;
+            let mut metadata = ChannelMetadata::default();
+            metadata.unwrap(store, ctx)?;
+            self.metadata = Some(metadata);
+        }
         ctx.ed25519(&self.sig_pk, HashSig)?;
         Ok(ctx)
     }
@@ -10,6 +10,13 @@
 //!     join link msgid;
 //!     absorb bytes public_payload;
 //!     mask bytes masked_payload;
+//!     if version >= STREAMS_2_VER {
+//!         absorb u8 flags;
+//!         oneof {
+//!             null;
+//!             absorb u64 timestamp;
+//!         }
+//!     }
 //!     commit;
 //!     squeeze external byte hash[78];
 //!     mssig(hash) sig;
@@ -24,16 +31,26 @@
 //!
 //! * `masked_payload` -- masked part of payload.
 //!
+//! * `version` -- message version in the header: packets with a timestamp are sent with `STREAMS_2_VER`, the others
+//!   with `STREAMS_1_VER` and the layout that version always had.
+//!
+//! * `flags` -- packet flags, `FLAG_TIMESTAMP_MASK` bit selects the `timestamp` oneof branch.
+//!
+//! * `timestamp` -- optional publish time in milliseconds since the Unix epoch, authenticated along with the payloads.
+//!
 //! * `hash` -- hash value to be signed.
 //!
 //! * `sig` -- message signature generated with the senders private key.
 
+use super::FLAG_TIMESTAMP_MASK;
 use iota_streams_app::message::{
     self,
     HasLink,
 };
 use iota_streams_core::{
     sponge::prp::PRP,
+    try_or,
+    Errors::InvalidBitReservation,
     Result,
 };
 use iota_streams_core_edsig::signature::ed25519;
@@ -56,9 +73,28 @@ where
     pub(crate) public_payload: &'a Bytes,
     pub(crate) masked_payload: &'a Bytes,
     pub(crate) sig_kp: &'a ed25519::Keypair,
+    pub(crate) timestamp: Option<u64>,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
+impl<'a, F, Link> ContentWrap<'a, F, Link>
+where
+    Link: HasLink,
+{
+    fn flags(&self) -> Uint8 {
+        Uint8(self.timestamp.map_or(0, |_| FLAG_TIMESTAMP_MASK))
+    }
+
+    /// Version of the message header, see the module documentation.
+    pub(crate) fn version(&self) -> Uint8 {
+        if self.timestamp.is_some() {
+            message::STREAMS_2_VER
+        } else {
+            message::STREAMS_1_VER
+        }
+    }
+}
+
 impl<'a, F, Link> message::ContentSizeof<F> for ContentWrap<'a, F, Link>
 where
     F: PRP,
@@ -70,8 +106,14 @@ where
         ctx.join(&store, self.link)?
             .absorb(&self.sig_kp.public)?
             .absorb(self.public_payload)?
-            .mask(self.masked_payload)?
-            .ed25519(self.sig_kp, HashSig)?;
+            .mask(self.masked_payload)?;
+        if self.version() != message::STREAMS_1_VER {
+            ctx.absorb(self.flags())?;
+            if let Some(timestamp) = self.timestamp {
+                ctx.absorb(Uint64(timestamp))?;
+            }
+        }
+        ctx.ed25519(self.sig_kp, HashSig)?;
         // TODO: Is both public and masked payloads are ok? Leave public only or masked only?
         Ok(ctx)
     }
@@ -92,8 +134,14 @@ where
         ctx.join(store, self.link)?
             .absorb(&self.sig_kp.public)?
             .absorb(self.public_payload)?
-            .mask(self.masked_payload)?
-            .ed25519(self.sig_kp, HashSig)?;
+            .mask(self.masked_payload)?;
+        if self.version() != message::STREAMS_1_VER {
+            ctx.absorb(self.flags())?;
+            if let Some(timestamp) = self.timestamp {
+                ctx.absorb(Uint64(timestamp))?;
+            }
+        }
+        ctx.ed25519(self.sig_kp, HashSig)?;
         Ok(ctx)
     }
 }
@@ -103,6 +151,9 @@ pub struct ContentUnwrap<F, Link: HasLink> {
     pub(crate) public_payload: Bytes,
     pub(crate) masked_payload: Bytes,
     pub(crate) sig_pk: ed25519::PublicKey,
+    pub(crate) timestamp: Option<u64>,
+    /// Version of the message header, selects the layout.
+    pub(crate) version: Uint8,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -117,6 +168,8 @@ where
             public_payload: Bytes::default(),
             masked_payload: Bytes::default(),
             sig_pk: ed25519::PublicKey::default(),
+            timestamp: None,
+            version: message::STREAMS_1_VER,
             _phantom: core::marker::PhantomData,
         }
     }
@@ -137,8 +190,18 @@ where
         ctx.join(store, &mut self.link)?
             .absorb(&mut self.sig_pk)?
             .absorb(&mut self.public_payload)?
-            .mask(&mut self.masked_payload)?
-            .ed25519(&self.sig_pk, HashSig)?;
+            .mask(&mut self.masked_payload)?;
+        if self.version != message::STREAMS_1_VER {
+            let mut flags = Uint8(0);
+            ctx.absorb(&mut flags)?;
+            try_or!(flags.0 & !FLAG_TIMESTAMP_MASK == 0, InvalidBitReservation)?;
+            if flags.0 & FLAG_TIMESTAMP_MASK != 0 {
+                let mut timestamp = Uint64(0);
+                ctx.absorb(&mut timestamp)?;
+                self.timestamp = Some(timestamp.0);
+            }
+        }
+        ctx.ed25519(&self.sig_pk, HashSig)?;
         Ok(ctx)
     }
 }
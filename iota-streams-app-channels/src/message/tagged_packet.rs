@@ -9,6 +9,13 @@
 //!     join link msgid;
 //!     absorb bytes public_payload;
 //!     mask bytes masked_payload;
+//!     if version >= STREAMS_2_VER {
+//!         absorb u8 flags;
+//!         oneof {
+//!             null;
+//!             absorb u64 timestamp;
+//!         }
+//!     }
 //!     commit;
 //!     squeeze byte mac[32];
 //! }
@@ -22,8 +29,16 @@
 //!
 //! * `masked_payload` -- masked part of payload.
 //!
+//! * `version` -- message version in the header: packets with a timestamp are sent with `STREAMS_2_VER`, the others
+//!   with `STREAMS_1_VER` and the layout that version always had.
+//!
+//! * `flags` -- packet flags, `FLAG_TIMESTAMP_MASK` bit selects the `timestamp` oneof branch.
+//!
+//! * `timestamp` -- optional publish time in milliseconds since the Unix epoch, authenticated along with the payloads.
+//!
 //! * `mac` -- MAC of the message.
 
+use super::FLAG_TIMESTAMP_MASK;
 use iota_streams_app::message::{
     self,
     HasLink,
@@ -34,6 +49,8 @@ use iota_streams_core::{
         prp::PRP,
         spongos,
     },
+    try_or,
+    Errors::InvalidBitReservation,
     Result,
 };
 use iota_streams_ddml::{
@@ -54,9 +71,28 @@ where
     pub(crate) link: &'a <Link as HasLink>::Rel,
     pub(crate) public_payload: &'a Bytes,
     pub(crate) masked_payload: &'a Bytes,
+    pub(crate) timestamp: Option<u64>,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
+impl<'a, F, Link> ContentWrap<'a, F, Link>
+where
+    Link: HasLink,
+{
+    fn flags(&self) -> Uint8 {
+        Uint8(self.timestamp.map_or(0, |_| FLAG_TIMESTAMP_MASK))
+    }
+
+    /// Version of the message header, see the module documentation.
+    pub(crate) fn version(&self) -> Uint8 {
+        if self.timestamp.is_some() {
+            message::STREAMS_2_VER
+        } else {
+            message::STREAMS_1_VER
+        }
+    }
+}
+
 impl<'a, F, Link> message::ContentSizeof<F> for ContentWrap<'a, F, Link>
 where
     F: PRP,
@@ -68,9 +104,14 @@ where
         let mac = Mac(spongos::MacSize::<F>::USIZE);
         ctx.join(&store, self.link)?
             .absorb(self.public_payload)?
-            .mask(self.masked_payload)?
-            .commit()?
-            .squeeze(&mac)?;
+            .mask(self.masked_payload)?;
+        if self.version() != message::STREAMS_1_VER {
+            ctx.absorb(self.flags())?;
+            if let Some(timestamp) = self.timestamp {
+                ctx.absorb(Uint64(timestamp))?;
+            }
+        }
+        ctx.commit()?.squeeze(&mac)?;
         // TODO: Is bot public and masked payloads are ok? Leave public only or masked only?
         Ok(ctx)
     }
@@ -91,9 +132,14 @@ where
         let mac = Mac(spongos::MacSize::<F>::USIZE);
         ctx.join(store, self.link)?
             .absorb(self.public_payload)?
-            .mask(self.masked_payload)?
-            .commit()?
-            .squeeze(&mac)?;
+            .mask(self.masked_payload)?;
+        if self.version() != message::STREAMS_1_VER {
+            ctx.absorb(self.flags())?;
+            if let Some(timestamp) = self.timestamp {
+                ctx.absorb(Uint64(timestamp))?;
+            }
+        }
+        ctx.commit()?.squeeze(&mac)?;
         Ok(ctx)
     }
 }
@@ -103,6 +149,9 @@ pub struct ContentUnwrap<F, Link: HasLink> {
     pub(crate) link: <Link as HasLink>::Rel,
     pub(crate) public_payload: Bytes,
     pub(crate) masked_payload: Bytes,
+    pub(crate) timestamp: Option<u64>,
+    /// Version of the message header, selects the layout.
+    pub(crate) version: Uint8,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -115,6 +164,8 @@ where
             link: Link::Rel::default(),
             public_payload: Bytes::default(),
             masked_payload: Bytes::default(),
+            timestamp: None,
+            version: message::STREAMS_1_VER,
             _phantom: core::marker::PhantomData,
         }
     }
@@ -135,9 +186,18 @@ where
         let mac = Mac(spongos::MacSize::<F>::USIZE);
         ctx.join(store, &mut self.link)?
             .absorb(&mut self.public_payload)?
-            .mask(&mut self.masked_payload)?
-            .commit()?
-            .squeeze(&mac)?;
+            .mask(&mut self.masked_payload)?;
+        if self.version != message::STREAMS_1_VER {
+            let mut flags = Uint8(0);
+            ctx.absorb(&mut flags)?;
+            try_or!(flags.0 & !FLAG_TIMESTAMP_MASK == 0, InvalidBitReservation)?;
+            if flags.0 & FLAG_TIMESTAMP_MASK != 0 {
+                let mut timestamp = Uint64(0);
+                ctx.absorb(&mut timestamp)?;
+                self.timestamp = Some(timestamp.0);
+            }
+        }
+        ctx.commit()?.squeeze(&mac)?;
         Ok(ctx)
     }
 }
@@ -5,7 +5,7 @@
 //! message Unsubscribe {
 //!     join link msgid;
 //!     commit;
-//!     squeeze tryte mac[32];
+//!     squeeze byte mac[32];
 //! }
 //! ```
 //!
@@ -14,91 +14,104 @@
 //! * `msgid` -- link to the `Subscribe` message published by the subscriber.
 //!
 //! * `mac` -- authentication tag proving knowledge of the `unsubscribe_key` from the `Subscribe` message.
+//!
+//! Note, the `unsubscribe_key` is not transmitted again: it has been absorbed into the
+//! spongos state of the `Subscribe` message, which is joined here. Only the subscriber
+//! and the channel owner know that state, so a valid `mac` can only be produced by them.
 
-use iota_streams_core::Result;
 use iota_streams_app::message::{
     self,
     HasLink,
 };
 use iota_streams_core::{
+    prelude::typenum::Unsigned as _,
     sponge::{
         prp::PRP,
         spongos,
     },
-    tbits::{
-        trinary,
-        word::SpongosTbitWord,
-    },
+    Result,
 };
 use iota_streams_ddml::{
     command::*,
     io,
+    link_store::{
+        EmptyLinkStore,
+        LinkStore,
+    },
     types::*,
 };
 
-pub struct ContentWrap<'a, TW, F, Link: HasLink> {
+pub struct ContentWrap<'a, F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a,
+{
     pub(crate) link: &'a <Link as HasLink>::Rel,
-    pub(crate) _phantom: std::marker::PhantomData<(TW, F, Link)>,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
-impl<'a, TW, F, Link, Store> message::ContentWrap<TW, F, Store> for ContentWrap<'a, TW, F, Link>
+impl<'a, F, Link> message::ContentSizeof<F> for ContentWrap<'a, F, Link>
 where
-    TW: SpongosTbitWord + trinary::TritWord,
-    F: PRP<TW>,
+    F: PRP,
     Link: HasLink,
-    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<TW, F>,
-    Store: LinkStore<TW, F, <Link as HasLink>::Rel>,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F>,
 {
-    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<TW, F>) -> Result<&'c mut sizeof::Context<TW, F>> {
-        let store = EmptyLinkStore::<TW, F, <Link as HasLink>::Rel, ()>::default();
-        let mac = Mac(spongos::Spongos::<TW, F>::MAC_SIZE);
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+        let mac = Mac(spongos::MacSize::<F>::USIZE);
         ctx.join(&store, self.link)?.commit()?.squeeze(&mac)?;
         Ok(ctx)
     }
+}
 
-    fn wrap<'c, OS: io::OStream<TW>>(
+impl<'a, F, Link, Store> message::ContentWrap<F, Store> for ContentWrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+{
+    fn wrap<'c, OS: io::OStream>(
         &self,
         store: &Store,
-        ctx: &'c mut wrap::Context<TW, F, OS>,
-    ) -> Result<&'c mut wrap::Context<TW, F, OS>> {
-        let mac = Mac(spongos::Spongos::<TW, F>::MAC_SIZE);
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        let mac = Mac(spongos::MacSize::<F>::USIZE);
         ctx.join(store, self.link)?.commit()?.squeeze(&mac)?;
         Ok(ctx)
     }
 }
 
-pub struct ContentUnwrap<TW, F, Link: HasLink> {
-    pub link: <Link as HasLink>::Rel,
-    _phantom: std::marker::PhantomData<(TW, F, Link)>,
+pub struct ContentUnwrap<F, Link: HasLink> {
+    pub(crate) link: <Link as HasLink>::Rel,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
-impl<TW, F, Link> ContentUnwrap<TW, F, Link>
+impl<F, Link> Default for ContentUnwrap<F, Link>
 where
     Link: HasLink,
-    <Link as HasLink>::Rel: Eq + Default + SkipFallback<TW, F>,
 {
-    pub fn new() -> Self {
+    fn default() -> Self {
         Self {
-            link: <<Link as HasLink>::Rel as Default>::default(),
-            _phantom: std::marker::PhantomData,
+            link: Link::Rel::default(),
+            _phantom: core::marker::PhantomData,
         }
     }
 }
 
-impl<TW, F, Link, Store> message::ContentUnwrap<TW, F, Store> for ContentUnwrap<TW, F, Link>
+impl<F, Link, Store> message::ContentUnwrap<F, Store> for ContentUnwrap<F, Link>
 where
-    TW: SpongosTbitWord + trinary::TritWord,
-    F: PRP<TW>,
+    F: PRP,
     Link: HasLink,
-    <Link as HasLink>::Rel: Eq + Default + SkipFallback<TW, F>,
-    Store: LinkStore<TW, F, <Link as HasLink>::Rel>,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
 {
-    fn unwrap<'c, IS: io::IStream<TW>>(
+    fn unwrap<'c, IS: io::IStream>(
         &mut self,
         store: &Store,
-        ctx: &'c mut unwrap::Context<TW, F, IS>,
-    ) -> Result<&'c mut unwrap::Context<TW, F, IS>> {
-        let mac = Mac(spongos::Spongos::<TW, F>::MAC_SIZE);
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        let mac = Mac(spongos::MacSize::<F>::USIZE);
         ctx.join(store, &mut self.link)?.commit()?.squeeze(&mac)?;
         Ok(ctx)
     }
@@ -0,0 +1,205 @@
+//! Fragmentation of binary message bodies across several transport frames.
+//!
+//! A message which does not fit into a single frame is wrapped as a whole with
+//! `HDF.payload_frame_count` set to the total number of frames, then its binary body
+//! is split. The first frame carries the beginning of the body (including the header)
+//! followed by the hash of the second frame, each following frame carries a short frame
+//! header, the hash of the next frame and the next chunk of the body:
+//!
+//! ```ddml
+//! frame {
+//!     byte frame_type;
+//!     byte payload_frame_num[3];
+//!     byte next_frame_hash[32];
+//!     byte chunk[];
+//! }
+//! ```
+//!
+//! # Fields:
+//!
+//! * `frame_type` -- `INTER_PCF_ID`, or `FINAL_PCF_ID` for the last frame.
+//!
+//! * `payload_frame_num` -- 22-bit big-endian frame number, the first continuation frame is number 2.
+//!
+//! * `next_frame_hash` -- Spongos hash of the whole next frame, absent from the last frame.
+//!
+//! * `chunk` -- next part of the message body.
+//!
+//! The frames are built back to front so that each frame commits to the one after it. Given the
+//! first frame, the following frames are thus determined: frames published at the same link by
+//! anyone else do not match the hash and are skipped without trying to join them. The message
+//! itself is unwrapped and verified as a whole once all the frames are joined back, which
+//! authenticates the first frame and, through the hash chain, the following ones.
+
+use iota_streams_core::{
+    prelude::{
+        generic_array::{
+            typenum::U32,
+            GenericArray,
+        },
+        Vec,
+    },
+    sponge::{
+        prp::PRP,
+        spongos,
+    },
+    try_or,
+    Errors::{
+        FrameHashMismatch,
+        FrameSizeTooSmall,
+        InvalidFrameNum,
+        InvalidMsgType,
+        LengthMismatch,
+        MaxSizeExceeded,
+    },
+    Result,
+};
+
+use super::*;
+
+/// Size of the header prepended to continuation frames.
+pub const FRAME_HEADER_SIZE: usize = 4;
+
+/// Size of the hash of the next frame carried by all the frames but the last one.
+pub const FRAME_HASH_SIZE: usize = 32;
+
+/// Hash of a frame, committed to by the frame before it.
+pub type FrameHash = GenericArray<u8, U32>;
+
+/// Hash of the whole frame `frame`.
+pub fn frame_hash<F: PRP>(frame: &[u8]) -> FrameHash {
+    let mut hash = FrameHash::default();
+    spongos::hash_data::<F>(frame, hash.as_mut_slice());
+    hash
+}
+
+fn check_frame_size(frame_size: usize) -> Result<()> {
+    try_or!(
+        FRAME_HEADER_SIZE + FRAME_HASH_SIZE < frame_size,
+        FrameSizeTooSmall(FRAME_HEADER_SIZE + FRAME_HASH_SIZE + 1, frame_size)
+    )
+}
+
+fn frame_type(num: u32, count: u32) -> u8 {
+    if num == count {
+        FINAL_PCF_ID.0
+    } else {
+        INTER_PCF_ID.0
+    }
+}
+
+/// Size of the chunk of the body carried by frame number `num` out of `count`.
+fn chunk_size(num: u32, count: u32, frame_size: usize) -> usize {
+    let mut size = frame_size;
+    if 1 < num {
+        size -= FRAME_HEADER_SIZE;
+    }
+    if num < count {
+        size -= FRAME_HASH_SIZE;
+    }
+    size
+}
+
+/// Number of frames needed to carry a body of `body_size` bytes in frames of at most `frame_size` bytes.
+pub fn frame_count(body_size: usize, frame_size: usize) -> Result<u32> {
+    check_frame_size(frame_size)?;
+    let count = if body_size <= frame_size {
+        1
+    } else {
+        // The first and intermediate frames carry the hash of the next frame, the last one does not
+        let rest = body_size - chunk_size(1, 2, frame_size);
+        let final_chunk_size = chunk_size(2, 2, frame_size);
+        if rest <= final_chunk_size {
+            2
+        } else {
+            let inter_chunk_size = chunk_size(2, 3, frame_size);
+            2 + (rest - final_chunk_size).div_ceil(inter_chunk_size)
+        }
+    };
+    try_or!(count < 0x400000, MaxSizeExceeded(0x400000_usize, count))?;
+    Ok(count as u32)
+}
+
+/// Split message body into frame bodies of at most `frame_size` bytes each, each frame but the last
+/// one ending or starting with the hash of the next frame.
+pub fn split_body<F: PRP>(mut body: Vec<u8>, frame_size: usize) -> Result<Vec<Vec<u8>>> {
+    let count = frame_count(body.len(), frame_size)?;
+    if count == 1 {
+        return Ok(vec![body]);
+    }
+
+    let mut chunks = Vec::with_capacity(count as usize);
+    for num in 1..count {
+        let rest = body.split_off(chunk_size(num, count, frame_size));
+        chunks.push(body);
+        body = rest;
+    }
+    chunks.push(body);
+
+    let mut frames = Vec::with_capacity(count as usize);
+    let mut next_hash: Option<FrameHash> = None;
+    for num in (2..=count).rev() {
+        let chunk = chunks.pop().unwrap_or_default();
+        let mut frame = Vec::with_capacity(frame_size);
+        frame.push(frame_type(num, count));
+        frame.extend_from_slice(&num.to_be_bytes()[1..]);
+        if let Some(hash) = next_hash {
+            frame.extend_from_slice(&hash);
+        }
+        frame.extend_from_slice(&chunk);
+        next_hash = Some(frame_hash::<F>(&frame));
+        frames.push(frame);
+    }
+    let mut first = chunks.pop().unwrap_or_default();
+    if let Some(hash) = next_hash {
+        first.extend_from_slice(&hash);
+    }
+    frames.push(first);
+    frames.reverse();
+    Ok(frames)
+}
+
+/// Cut the hash of the second frame off the first frame of a message split in `count` frames.
+pub fn split_first_frame(body: &mut Vec<u8>, count: u32) -> Result<FrameHash> {
+    debug_assert!(1 < count);
+    try_or!(
+        FRAME_HASH_SIZE < body.len(),
+        FrameSizeTooSmall(FRAME_HASH_SIZE + 1, body.len())
+    )?;
+    let hash = body.split_off(body.len() - FRAME_HASH_SIZE);
+    Ok(FrameHash::clone_from_slice(&hash))
+}
+
+/// Check continuation frame number `num` out of `count` of a message split in frames of
+/// `frame_size` bytes against the hash committed by the previous frame, then append its chunk to
+/// the message body. Returns the hash of the next frame, if any.
+pub fn join_frame<F: PRP>(
+    body: &mut Vec<u8>,
+    frame: &[u8],
+    num: u32,
+    count: u32,
+    frame_size: usize,
+    hash: &FrameHash,
+) -> Result<Option<FrameHash>> {
+    try_or!(frame_hash::<F>(frame) == *hash, FrameHashMismatch(num))?;
+    if num < count {
+        try_or!(frame.len() == frame_size, LengthMismatch(frame_size, frame.len()))?;
+    } else {
+        try_or!(
+            FRAME_HEADER_SIZE < frame.len() && frame.len() <= frame_size,
+            MaxSizeExceeded(frame_size, frame.len())
+        )?;
+    }
+    let expected_type = frame_type(num, count);
+    try_or!(frame[0] == expected_type, InvalidMsgType(expected_type, frame[0]))?;
+    let found_num = u32::from_be_bytes([0, frame[1], frame[2], frame[3]]);
+    try_or!(found_num == num, InvalidFrameNum(num, found_num))?;
+    let (next_hash, chunk) = if num < count {
+        let (hash, chunk) = frame[FRAME_HEADER_SIZE..].split_at(FRAME_HASH_SIZE);
+        (Some(FrameHash::clone_from_slice(hash)), chunk)
+    } else {
+        (None, &frame[FRAME_HEADER_SIZE..])
+    };
+    body.extend_from_slice(chunk);
+    Ok(next_hash)
+}
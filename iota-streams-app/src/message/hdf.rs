@@ -58,6 +58,15 @@ impl<Link: Default> HDF<Link> {
         }
     }
 
+    pub fn with_version(mut self, version: Uint8) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn get_version(&self) -> Uint8 {
+        self.version
+    }
+
     pub fn with_content_type(mut self, content_type: u8) -> Result<Self> {
         try_or!(content_type < 0x10, ValueOutOfRange(0x10_usize, content_type as usize))?;
         self.content_type = content_type;
@@ -259,8 +268,8 @@ where
         ctx.absorb(&mut self.encoding)?
             .absorb(&mut self.version)?
             .guard(
-                self.version == STREAMS_1_VER,
-                InvalidMsgVersion(STREAMS_1_VER.0, self.version.0),
+                self.version == STREAMS_1_VER || self.version == STREAMS_2_VER,
+                InvalidMsgVersion(STREAMS_2_VER.0, self.version.0),
             )?
             .skip(&mut content_type_and_payload_length)?;
         {
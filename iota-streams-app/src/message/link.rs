@@ -30,8 +30,8 @@ pub trait HasLink: Sized + Default + Clone + Eq {
     /// Represent absolute link as bytes
     fn to_bytes(&self) -> Vec<u8>;
 
-    /// Get link from bytes
-    fn from_bytes(bytes: &[u8]) -> Self;
+    /// Get link from bytes, fails if the bytes do not encode a valid link
+    fn from_bytes(bytes: &[u8]) -> Result<Self>;
 }
 
 /// Represents an input state for message identifier generation.
@@ -119,12 +119,23 @@ pub trait LinkGenerator<Link: HasLink>: Default {
     /// Used by Subscriber to initialize link generator with the same state as Author
     fn reset(&mut self, seed: Link);
 
+    /// Used by Subscriber to check that the channel of an announcement link has been generated from
+    /// the Author's public key
+    fn is_channel_of(&self, announcement_link: &Link, pk: &ed25519::PublicKey) -> bool;
+
     /// Used by users to pseudo-randomly generate a new uniform message link from a cursor
     fn uniform_link_from(&self, cursor: Cursor<&<Link as HasLink>::Rel>) -> Link;
 
     /// Used by users to pseudo-randomly generate a new message link from a cursor
     fn link_from<T: AsRef<[u8]>>(&self, id: T, cursor: Cursor<&<Link as HasLink>::Rel>) -> Link;
 
+    /// Used by users to generate a new message link from a cursor, mixing in a `secret` known only
+    /// to the readers of the message at `cursor.link`. Generators deriving links from public data
+    /// only ignore it.
+    fn link_from_secret<T: AsRef<[u8]>>(&self, id: T, cursor: Cursor<&<Link as HasLink>::Rel>, _secret: &[u8]) -> Link {
+        self.link_from(id, cursor)
+    }
+
     /// Derive a new link and construct a header with given content type.
     fn uniform_header_from(
         &self,
@@ -9,6 +9,7 @@ pub mod hdf;
 pub use hdf::HDF;
 pub mod pcf;
 pub use pcf::PCF;
+pub mod fragment;
 
 mod generic;
 pub use generic::*;
@@ -49,13 +49,27 @@ where
     HDF<Link>: ContentWrap<F, Store>,
     Content: ContentWrap<F, Store>,
 {
+    /// Size of the wrapped message body.
+    pub fn sizeof(&self) -> Result<usize> {
+        let mut ctx = sizeof::Context::<F>::new();
+        self.header.sizeof(&mut ctx)?;
+        self.content.sizeof(&mut ctx)?;
+        Ok(ctx.get_size())
+    }
+
+    /// Wrap the message announcing in the header the number of frames of at most `frame_size`
+    /// bytes its body is to be split into, see [`fragment::split_body`].
+    pub fn wrap_frames(mut self, frame_size: usize) -> Result<WrappedMessage<F, Link>> {
+        let frame_count = fragment::frame_count(self.sizeof()?, frame_size)?;
+        self.header.payload_frame_count = frame_count;
+        if 1 < frame_count {
+            self.content.frame_type = INIT_PCF_ID;
+        }
+        self.wrap()
+    }
+
     pub fn wrap(&self) -> Result<WrappedMessage<F, Link>> {
-        let buf_size = {
-            let mut ctx = sizeof::Context::<F>::new();
-            self.header.sizeof(&mut ctx)?;
-            self.content.sizeof(&mut ctx)?;
-            ctx.get_size()
-        };
+        let buf_size = self.sizeof()?;
 
         let mut buf = vec![0; buf_size];
 
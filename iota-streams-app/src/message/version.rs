@@ -16,6 +16,11 @@ use iota_streams_ddml::types::Uint8;
 /// Streams version number.
 pub const STREAMS_1_VER: Uint8 = Uint8(0);
 
+/// Streams version number of messages whose content may carry fields added since
+/// [`STREAMS_1_VER`], eg. the flags and publish timestamp of packets. Messages without such fields
+/// are still sent with [`STREAMS_1_VER`].
+pub const STREAMS_2_VER: Uint8 = Uint8(1);
+
 /// Encoding Constants
 pub const UTF8: Uint8 = Uint8(0);
 
@@ -1,6 +1,7 @@
 use super::*;
 use crate::message::LinkedMessage;
 use core::hash;
+use futures::channel::oneshot;
 
 use iota_streams_core::{
     err,
@@ -14,9 +15,24 @@ use iota_streams_core::{
 #[cfg(feature = "async")]
 use iota_streams_core::Errors::MessageNotUnique;
 
-#[derive(Clone)]
 pub struct BucketTransport<Link, Msg> {
     bucket: HashMap<Link, Vec<Msg>>,
+    /// Pending subscriptions with their watched links.
+    watchers: Vec<(Vec<Link>, oneshot::Sender<Link>)>,
+}
+
+/// Subscriptions are not cloned, they are notified of messages sent to the original bucket only.
+impl<Link, Msg> Clone for BucketTransport<Link, Msg>
+where
+    Link: Clone,
+    Msg: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            bucket: self.bucket.clone(),
+            watchers: Vec::new(),
+        }
+    }
 }
 
 impl<Link, Msg> Default for BucketTransport<Link, Msg>
@@ -24,7 +40,7 @@ where
     Link: Eq + hash::Hash,
 {
     fn default() -> Self {
-        Self { bucket: HashMap::new() }
+        Self::new()
     }
 }
 
@@ -33,7 +49,48 @@ where
     Link: Eq + hash::Hash,
 {
     pub fn new() -> Self {
-        Self { bucket: HashMap::new() }
+        Self {
+            bucket: HashMap::new(),
+            watchers: Vec::new(),
+        }
+    }
+
+    fn store(&mut self, msg: &Msg)
+    where
+        Link: Clone,
+        Msg: LinkedMessage<Link> + Clone,
+    {
+        if let Some(msgs) = self.bucket.get_mut(msg.link()) {
+            msgs.push(msg.clone());
+        } else {
+            self.bucket.insert(msg.link().clone(), vec![msg.clone()]);
+        }
+        self.notify(msg.link());
+    }
+
+    /// Resolve subscriptions watching `link` and drop the abandoned ones.
+    fn notify(&mut self, link: &Link)
+    where
+        Link: Clone,
+    {
+        for (links, tx) in core::mem::take(&mut self.watchers) {
+            if links.contains(link) {
+                let _ = tx.send(link.clone());
+            } else if !tx.is_canceled() {
+                self.watchers.push((links, tx));
+            }
+        }
+    }
+}
+
+impl<Link, Msg> TransportSubscribe<Link> for BucketTransport<Link, Msg>
+where
+    Link: Eq + hash::Hash,
+{
+    fn subscribe(&mut self, links: Vec<Link>) -> Result<Subscription<Link>> {
+        let (tx, rx) = oneshot::channel();
+        self.watchers.push((links, tx));
+        Ok(rx)
     }
 }
 
@@ -62,13 +119,8 @@ where
     Msg: LinkedMessage<Link> + Clone,
 {
     fn send_message(&mut self, msg: &Msg) -> Result<()> {
-        if let Some(msgs) = self.bucket.get_mut(msg.link()) {
-            msgs.push(msg.clone());
-            Ok(())
-        } else {
-            self.bucket.insert(msg.link().clone(), vec![msg.clone()]);
-            Ok(())
-        }
+        self.store(msg);
+        Ok(())
     }
 
     fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
@@ -88,13 +140,8 @@ where
     Msg: LinkedMessage<Link> + Clone + core::marker::Send + core::marker::Sync,
 {
     async fn send_message(&mut self, msg: &Msg) -> Result<()> {
-        if let Some(msgs) = self.bucket.get_mut(msg.link()) {
-            msgs.push(msg.clone());
-            Ok(())
-        } else {
-            self.bucket.insert(msg.link().clone(), vec![msg.clone()]);
-            Ok(())
-        }
+        self.store(msg);
+        Ok(())
     }
 
     async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
@@ -106,7 +153,7 @@ where
     }
 
     async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
-        let mut msgs = self.recv_messages(link).await?;
+        let mut msgs = Transport::recv_messages(self, link).await?;
         if let Some(msg) = msgs.pop() {
             try_or!(msgs.is_empty(), MessageNotUnique(link.to_string())).unwrap();
             Ok(msg)
@@ -127,3 +174,47 @@ where
         Ok(())
     }
 }
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Link, Msg> SendTransport<Link, Msg> for BucketTransport<Link, Msg>
+where
+    Link: Eq + hash::Hash + Clone + core::marker::Send + core::marker::Sync + core::fmt::Display,
+    Msg: LinkedMessage<Link> + Clone + core::marker::Send + core::marker::Sync,
+{
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.store(msg);
+        Ok(())
+    }
+
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        if let Some(msgs) = self.bucket.get(link) {
+            Ok(msgs.clone())
+        } else {
+            err!(MessageLinkNotFound(link.to_string()))
+        }
+    }
+
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let mut msgs = SendTransport::recv_messages(self, link).await?;
+        if let Some(msg) = msgs.pop() {
+            try_or!(msgs.is_empty(), MessageNotUnique(link.to_string()))?;
+            Ok(msg)
+        } else {
+            err!(MessageLinkNotFound(link.to_string()))
+        }
+    }
+}
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Link, Msg> SendTransportDetails<Link> for BucketTransport<Link, Msg>
+where
+    Link: Eq + hash::Hash + Clone + core::marker::Send + core::marker::Sync + core::fmt::Display,
+    Msg: core::marker::Send,
+{
+    type Details = ();
+    async fn get_link_details(&mut self, _opt: &Link) -> Result<Self::Details> {
+        Ok(())
+    }
+}
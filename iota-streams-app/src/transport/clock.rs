@@ -0,0 +1,67 @@
+//! Time sources, eg. of the expiry of cached messages.
+
+use core::{
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    time::Duration,
+};
+
+use iota_streams_core::prelude::Arc;
+
+/// Source of the current time, in milliseconds since the Unix epoch.
+pub trait Clock: Send + Sync {
+    /// Current time in milliseconds since the Unix epoch.
+    fn now(&self) -> u64;
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> u64 {
+        (**self).now()
+    }
+}
+
+/// Wall clock of the system. There is no portable time source without `std`, it always returns 0.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    #[cfg(feature = "std")]
+    fn now(&self) -> u64 {
+        chrono::Utc::now().timestamp_millis() as u64
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn now(&self) -> u64 {
+        0
+    }
+}
+
+/// Clock returning an explicitly set time, eg. for tests or for devices synchronised by other means.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    millis: AtomicU64,
+}
+
+impl ManualClock {
+    pub fn new(millis: u64) -> Self {
+        Self {
+            millis: AtomicU64::new(millis),
+        }
+    }
+
+    pub fn set(&self, millis: u64) {
+        self.millis.store(millis, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.millis.fetch_add(duration.as_millis() as u64, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> u64 {
+        self.millis.load(Ordering::Relaxed)
+    }
+}
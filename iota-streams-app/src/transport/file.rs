@@ -0,0 +1,262 @@
+//! File-backed transport.
+//!
+//! Messages are stored under a root directory, one subdirectory per message index (hex encoded
+//! [`TangleAddress::to_msg_index()`]) and one file per message, numbered in the order of sending:
+//!
+//! ```text
+//! <root>/<msg index>/<n>.msg
+//! ```
+//!
+//! A message file holds the 8-byte big-endian timestamp followed by the binary message body.
+//! Like on the Tangle, only the body is stored, so `prev_link` of received messages is default.
+//!
+//! Message files are written under a temporary name and then hard-linked to the first free
+//! number, so that several processes sharing the root directory neither overwrite each other's
+//! messages nor observe partially written ones.
+
+use core::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+use std::{
+    fs,
+    io,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process,
+};
+
+use super::*;
+use crate::{
+    message::BinaryMessage,
+    transport::tangle::{
+        TangleAddress,
+        TangleMessage,
+    },
+};
+use iota_streams_core::{
+    err,
+    prelude::ToString,
+    try_or,
+    wrapped_err,
+    Error,
+    Errors::{
+        FileTransportFailure,
+        MessageLinkNotFound,
+    },
+    WrappedError,
+};
+
+#[cfg(feature = "async")]
+use iota_streams_core::Errors::MessageNotUnique;
+
+/// Extension of message files.
+const MSG_EXTENSION: &str = "msg";
+
+/// Size of the timestamp prepended to the message body.
+const TIMESTAMP_SIZE: usize = 8;
+
+/// Counter making temporary file names unique within the process.
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn io_err(path: &Path, e: io::Error) -> Error {
+    wrapped_err!(FileTransportFailure(path.display().to_string()), WrappedError(e))
+}
+
+/// Transport storing messages in the local file system.
+#[derive(Clone, Debug)]
+pub struct FileTransport {
+    root: PathBuf,
+}
+
+impl FileTransport {
+    /// Create a transport storing messages under `root`, the directory is created if missing.
+    pub fn new<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root).map_err(|e| io_err(&root, e))?;
+        Ok(Self { root })
+    }
+
+    /// Root directory of the stored messages.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn index_dir(&self, link: &TangleAddress) -> PathBuf {
+        self.root.join(format!("{:x}", link.to_msg_index()))
+    }
+
+    fn write<F>(&self, msg: &TangleMessage<F>) -> Result<()> {
+        let dir = self.index_dir(&msg.binary.link);
+        fs::create_dir_all(&dir).map_err(|e| io_err(&dir, e))?;
+
+        let mut bytes = Vec::with_capacity(TIMESTAMP_SIZE + msg.binary.body.bytes.len());
+        bytes.extend_from_slice(&msg.timestamp.to_be_bytes());
+        bytes.extend_from_slice(&msg.binary.body.bytes);
+        let tmp = dir.join(format!(
+            ".{}-{}.tmp",
+            process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp, &bytes).map_err(|e| io_err(&tmp, e))?;
+
+        let r = Self::publish(&dir, &tmp);
+        let _ = fs::remove_file(&tmp);
+        r
+    }
+
+    /// Link the temporary message file to the first free message number.
+    fn publish(dir: &Path, tmp: &Path) -> Result<()> {
+        let mut n = 0_usize;
+        loop {
+            let path = dir.join(format!("{}.{}", n, MSG_EXTENSION));
+            match fs::hard_link(tmp, &path) {
+                Ok(()) => return Ok(()),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => n += 1,
+                Err(e) => return Err(io_err(&path, e)),
+            }
+        }
+    }
+
+    fn read<F>(&self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
+        let dir = self.index_dir(link);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return err!(MessageLinkNotFound(link.to_string())),
+            Err(e) => return Err(io_err(&dir, e)),
+        };
+
+        let mut files = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| io_err(&dir, e))?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(MSG_EXTENSION) {
+                continue;
+            }
+            if let Some(n) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<usize>().ok())
+            {
+                files.push((n, path));
+            }
+        }
+        files.sort();
+
+        let mut msgs = Vec::with_capacity(files.len());
+        for (_, path) in files {
+            let bytes = fs::read(&path).map_err(|e| io_err(&path, e))?;
+            // Ignore malformed files
+            if bytes.len() < TIMESTAMP_SIZE {
+                continue;
+            }
+            let mut timestamp = [0_u8; TIMESTAMP_SIZE];
+            timestamp.copy_from_slice(&bytes[..TIMESTAMP_SIZE]);
+            let body = bytes[TIMESTAMP_SIZE..].to_vec();
+            let binary = BinaryMessage::new(link.clone(), TangleAddress::default(), body.into());
+            msgs.push(TangleMessage::with_timestamp(binary, u64::from_be_bytes(timestamp)));
+        }
+        try_or!(!msgs.is_empty(), MessageLinkNotFound(link.to_string()))?;
+        Ok(msgs)
+    }
+}
+
+impl TransportOptions for FileTransport {
+    type SendOptions = ();
+    fn get_send_options(&self) {}
+    fn set_send_options(&mut self, _opt: ()) {}
+
+    type RecvOptions = ();
+    fn get_recv_options(&self) {}
+    fn set_recv_options(&mut self, _opt: ()) {}
+}
+
+#[cfg(not(feature = "async"))]
+impl TransportDetails<TangleAddress> for FileTransport {
+    type Details = ();
+    fn get_link_details(&mut self, _link: &TangleAddress) -> Result<Self::Details> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<F> Transport<TangleAddress, TangleMessage<F>> for FileTransport {
+    fn send_message(&mut self, msg: &TangleMessage<F>) -> Result<()> {
+        self.write(msg)
+    }
+
+    fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
+        self.read(link)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl TransportDetails<TangleAddress> for FileTransport {
+    type Details = ();
+    async fn get_link_details(&mut self, _link: &TangleAddress) -> Result<Self::Details> {
+        Ok(())
+    }
+}
+
+/// File system access is blocking, async operations complete without yielding.
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<F> Transport<TangleAddress, TangleMessage<F>> for FileTransport
+where
+    F: 'static + core::marker::Send + core::marker::Sync,
+{
+    async fn send_message(&mut self, msg: &TangleMessage<F>) -> Result<()> {
+        self.write(msg)
+    }
+
+    async fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
+        self.read(link)
+    }
+
+    async fn recv_message(&mut self, link: &TangleAddress) -> Result<TangleMessage<F>> {
+        let mut msgs = self.read(link)?;
+        if let Some(msg) = msgs.pop() {
+            try_or!(msgs.is_empty(), MessageNotUnique(link.to_string()))?;
+            Ok(msg)
+        } else {
+            err!(MessageLinkNotFound(link.to_string()))
+        }
+    }
+}
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl SendTransportDetails<TangleAddress> for FileTransport {
+    type Details = ();
+    async fn get_link_details(&mut self, _link: &TangleAddress) -> Result<Self::Details> {
+        Ok(())
+    }
+}
+
+/// File system access is blocking, async operations complete without yielding.
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<F> SendTransport<TangleAddress, TangleMessage<F>> for FileTransport
+where
+    F: 'static + core::marker::Send + core::marker::Sync,
+{
+    async fn send_message(&mut self, msg: &TangleMessage<F>) -> Result<()> {
+        self.write(msg)
+    }
+
+    async fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
+        self.read(link)
+    }
+
+    async fn recv_message(&mut self, link: &TangleAddress) -> Result<TangleMessage<F>> {
+        let mut msgs = self.read(link)?;
+        if let Some(msg) = msgs.pop() {
+            try_or!(msgs.is_empty(), MessageNotUnique(link.to_string()))?;
+            Ok(msg)
+        } else {
+            err!(MessageLinkNotFound(link.to_string()))
+        }
+    }
+}
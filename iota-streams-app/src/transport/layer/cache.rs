@@ -0,0 +1,428 @@
+use core::{
+    hash,
+    time::Duration,
+};
+
+#[cfg(not(feature = "async"))]
+use core::fmt::{
+    Debug,
+    Display,
+};
+
+#[cfg(feature = "async")]
+use async_trait::async_trait;
+#[cfg(feature = "async")]
+use core::marker::{
+    Send,
+    Sync,
+};
+#[cfg(feature = "async")]
+use iota_streams_core::{
+    err,
+    prelude::{
+        Box,
+        ToString,
+    },
+    try_or,
+    Errors::{
+        MessageLinkNotFound,
+        MessageNotUnique,
+    },
+};
+
+use iota_streams_core::{
+    prelude::{
+        Arc,
+        HashMap,
+        Vec,
+        VecDeque,
+    },
+    Result,
+};
+
+use super::Layer;
+#[cfg(feature = "async-send")]
+use crate::transport::{
+    SendTransport,
+    SendTransportDetails,
+};
+use crate::{
+    message::LinkedMessage,
+    transport::{
+        clock::Clock,
+        Transport,
+        TransportDetails,
+        TransportOptions,
+    },
+};
+
+/// Transport caching messages received from the wrapped transport by link.
+///
+/// Only successful receives are cached. Sending a message drops the cached messages at its
+/// link. Messages published at the same link by other parties after it was cached, eg. the
+/// genuine message following spam that could not be authenticated, are not seen until the entry
+/// expires, is evicted or is invalidated.
+///
+/// A cache is not `Clone`: clones would hold diverging entries. Share it between users with
+/// [`new_shared_transport`](crate::transport::new_shared_transport) instead.
+pub struct Cache<Tsp, Link, Msg> {
+    inner: Tsp,
+    entries: HashMap<Link, Entry<Msg>>,
+    /// Cached links in insertion order, the oldest entry is evicted first.
+    order: VecDeque<Link>,
+    capacity: usize,
+    ttl: Ttl,
+}
+
+/// Messages cached at a link.
+struct Entry<Msg> {
+    msgs: Vec<Msg>,
+    /// Time the messages were received at, in milliseconds.
+    received_at: u64,
+}
+
+/// Lifetime of cache entries measured by a clock.
+#[derive(Clone)]
+struct Ttl {
+    ttl: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+impl<Tsp, Link, Msg> Cache<Tsp, Link, Msg>
+where
+    Link: Eq + hash::Hash + Clone,
+    Msg: Clone,
+{
+    /// Wrap `inner` transport caching messages of at most `capacity` links. Messages are received
+    /// from the wrapped transport again once they have been cached for `ttl` according to `clock`.
+    pub fn new(inner: Tsp, capacity: usize, ttl: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            inner,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+            ttl: Ttl { ttl, clock },
+        }
+    }
+
+    pub fn inner(&self) -> &Tsp {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> Tsp {
+        self.inner
+    }
+
+    /// Number of cached links.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drop cached messages at `link`.
+    pub fn invalidate(&mut self, link: &Link) {
+        if self.entries.remove(link).is_some() {
+            self.order.retain(|l| l != link);
+        }
+    }
+
+    /// Drop all cached messages.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    fn get(&self, link: &Link) -> Option<Vec<Msg>> {
+        let entry = self.entries.get(link)?;
+        let age = self.ttl.clock.now().saturating_sub(entry.received_at);
+        if u128::from(age) >= self.ttl.ttl.as_millis() {
+            return None;
+        }
+        Some(entry.msgs.clone())
+    }
+
+    fn insert(&mut self, link: &Link, msgs: &[Msg]) {
+        if self.capacity == 0 {
+            return;
+        }
+        // A refreshed entry is the newest one
+        self.invalidate(link);
+        if self.entries.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+        let entry = Entry {
+            msgs: msgs.to_vec(),
+            received_at: self.ttl.clock.now(),
+        };
+        self.entries.insert(link.clone(), entry);
+        self.order.push_back(link.clone());
+    }
+}
+
+/// Layer producing [`Cache`] transports.
+pub struct CacheLayer<Link, Msg> {
+    capacity: usize,
+    ttl: Ttl,
+    _phantom: core::marker::PhantomData<(Link, Msg)>,
+}
+
+impl<Link, Msg> CacheLayer<Link, Msg> {
+    /// Cache messages of at most `capacity` links for `ttl` according to `clock`, see
+    /// [`Cache::new`].
+    pub fn new(capacity: usize, ttl: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            capacity,
+            ttl: Ttl { ttl, clock },
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<Tsp, Link, Msg> Layer<Tsp> for CacheLayer<Link, Msg>
+where
+    Link: Eq + hash::Hash + Clone,
+    Msg: Clone,
+{
+    type Transport = Cache<Tsp, Link, Msg>;
+    fn layer(&self, inner: Tsp) -> Cache<Tsp, Link, Msg> {
+        Cache::new(inner, self.capacity, self.ttl.ttl, self.ttl.clock.clone())
+    }
+}
+
+impl<Tsp: TransportOptions, Link, Msg> TransportOptions for Cache<Tsp, Link, Msg> {
+    type SendOptions = <Tsp as TransportOptions>::SendOptions;
+    fn get_send_options(&self) -> Self::SendOptions {
+        self.inner.get_send_options()
+    }
+    fn set_send_options(&mut self, opt: Self::SendOptions) {
+        self.inner.set_send_options(opt)
+    }
+
+    type RecvOptions = <Tsp as TransportOptions>::RecvOptions;
+    fn get_recv_options(&self) -> Self::RecvOptions {
+        self.inner.get_recv_options()
+    }
+    fn set_recv_options(&mut self, opt: Self::RecvOptions) {
+        self.inner.set_recv_options(opt)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Tsp: TransportDetails<Link>, Link, Msg> TransportDetails<Link> for Cache<Tsp, Link, Msg> {
+    type Details = <Tsp as TransportDetails<Link>>::Details;
+    fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.inner.get_link_details(link)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Tsp, Link, Msg> Transport<Link, Msg> for Cache<Tsp, Link, Msg>
+where
+    Tsp: Transport<Link, Msg>,
+    Link: Eq + hash::Hash + Clone + Debug + Display,
+    Msg: LinkedMessage<Link> + Clone,
+{
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.inner.send_message(msg)?;
+        self.invalidate(msg.link());
+        Ok(())
+    }
+
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        if let Some(msgs) = self.get(link) {
+            return Ok(msgs);
+        }
+        let msgs = self.inner.recv_messages(link)?;
+        self.insert(link, &msgs);
+        Ok(msgs)
+    }
+
+    fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        self.inner.confirmation_timestamp(msg)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Tsp, Link, Msg> TransportDetails<Link> for Cache<Tsp, Link, Msg>
+where
+    Tsp: TransportDetails<Link>,
+    Link: 'static + Send + Sync,
+{
+    type Details = <Tsp as TransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.inner.get_link_details(link).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Tsp, Link, Msg> Transport<Link, Msg> for Cache<Tsp, Link, Msg>
+where
+    Tsp: Transport<Link, Msg>,
+    Link: 'static + Eq + hash::Hash + Clone + Send + Sync + core::fmt::Display,
+    Msg: 'static + LinkedMessage<Link> + Clone + Send + Sync,
+{
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.inner.send_message(msg).await?;
+        self.invalidate(msg.link());
+        Ok(())
+    }
+
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        if let Some(msgs) = self.get(link) {
+            return Ok(msgs);
+        }
+        let msgs = self.inner.recv_messages(link).await?;
+        self.insert(link, &msgs);
+        Ok(msgs)
+    }
+
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let mut msgs = self.recv_messages(link).await?;
+        if let Some(msg) = msgs.pop() {
+            try_or!(msgs.is_empty(), MessageNotUnique(link.to_string()))?;
+            Ok(msg)
+        } else {
+            err!(MessageLinkNotFound(link.to_string()))
+        }
+    }
+
+    async fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        self.inner.confirmation_timestamp(msg).await
+    }
+}
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Tsp, Link, Msg> SendTransportDetails<Link> for Cache<Tsp, Link, Msg>
+where
+    Tsp: SendTransportDetails<Link>,
+    Link: 'static + Send + Sync,
+    Msg: Send + Sync,
+{
+    type Details = <Tsp as SendTransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.inner.get_link_details(link).await
+    }
+}
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Tsp, Link, Msg> SendTransport<Link, Msg> for Cache<Tsp, Link, Msg>
+where
+    Tsp: SendTransport<Link, Msg>,
+    Link: 'static + Eq + hash::Hash + Clone + Send + Sync + core::fmt::Display,
+    Msg: 'static + LinkedMessage<Link> + Clone + Send + Sync,
+{
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.inner.send_message(msg).await?;
+        self.invalidate(msg.link());
+        Ok(())
+    }
+
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        if let Some(msgs) = self.get(link) {
+            return Ok(msgs);
+        }
+        let msgs = self.inner.recv_messages(link).await?;
+        self.insert(link, &msgs);
+        Ok(msgs)
+    }
+
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let mut msgs = SendTransport::recv_messages(self, link).await?;
+        if let Some(msg) = msgs.pop() {
+            try_or!(msgs.is_empty(), MessageNotUnique(link.to_string()))?;
+            Ok(msg)
+        } else {
+            err!(MessageLinkNotFound(link.to_string()))
+        }
+    }
+
+    async fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        self.inner.confirmation_timestamp(msg).await
+    }
+}
+
+#[cfg(all(test, not(feature = "async"), feature = "std"))]
+mod tests {
+    use core::time::Duration;
+    use iota_streams_core::prelude::Arc;
+
+    use super::*;
+    use crate::transport::{
+        clock::ManualClock,
+        layer::{
+            BasicMetrics,
+            LayerExt,
+            MetricsLayer,
+            Operation,
+        },
+        new_shared_transport,
+        test::TestMessage,
+        BucketTransport,
+    };
+
+    /// Received messages are served from the cache until they are invalidated by a send, evicted by
+    /// newer entries or expired.
+    #[test]
+    fn cache_hits_invalidation_eviction_and_expiry() {
+        let bucket = new_shared_transport(BucketTransport::<u64, TestMessage>::new());
+        let misses = Arc::new(BasicMetrics::new());
+        let clock = Arc::new(ManualClock::new(0));
+        let cache = bucket
+            .clone()
+            .with_layer(MetricsLayer::new(misses.clone()))
+            .with_layer(CacheLayer::new(2, Duration::from_secs(60), clock.clone()));
+        let mut transport = new_shared_transport(cache);
+        let mut shared = transport.clone();
+        let (a, b, c) = (1, 2, 3);
+        let recvs = || misses.get(Operation::Recv).calls();
+
+        transport.send_message(&TestMessage::new(a, 1)).unwrap();
+        assert_eq!(transport.recv_messages(&a).unwrap().len(), 1);
+        assert_eq!(shared.recv_messages(&a).unwrap().len(), 1);
+        assert_eq!(recvs(), 1);
+
+        // Messages published by others are not seen until the entry expires
+        let mut other = bucket;
+        other.send_message(&TestMessage::new(a, 2)).unwrap();
+        assert_eq!(transport.recv_messages(&a).unwrap().len(), 1);
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(transport.recv_messages(&a).unwrap().len(), 2);
+        assert_eq!(recvs(), 2);
+
+        // Sending through any handle of the shared cache invalidates the link
+        shared.send_message(&TestMessage::new(a, 3)).unwrap();
+        assert_eq!(transport.recv_messages(&a).unwrap().len(), 3);
+        assert_eq!(recvs(), 3);
+
+        // Failed receives are not cached
+        assert!(transport.recv_messages(&b).is_err());
+        assert!(transport.recv_messages(&b).is_err());
+        assert_eq!(recvs(), 5);
+        assert_eq!(transport.borrow().len(), 1);
+
+        // The oldest entry is evicted first
+        transport.send_message(&TestMessage::new(b, 1)).unwrap();
+        transport.send_message(&TestMessage::new(c, 1)).unwrap();
+        transport.recv_messages(&b).unwrap();
+        transport.recv_messages(&c).unwrap();
+        assert_eq!(recvs(), 7);
+        assert_eq!(transport.borrow().len(), 2);
+        transport.recv_messages(&c).unwrap();
+        assert_eq!(recvs(), 7);
+        transport.recv_messages(&a).unwrap();
+        assert_eq!(recvs(), 8);
+
+        transport.borrow_mut().invalidate(&a);
+        transport.recv_messages(&a).unwrap();
+        assert_eq!(recvs(), 9);
+    }
+}
@@ -0,0 +1,349 @@
+use core::{
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    time::Duration,
+};
+
+#[cfg(not(feature = "async"))]
+use core::fmt::{
+    Debug,
+    Display,
+};
+
+#[cfg(feature = "async")]
+use async_trait::async_trait;
+#[cfg(feature = "async")]
+use core::marker::{
+    Send,
+    Sync,
+};
+#[cfg(feature = "async")]
+use iota_streams_core::prelude::Box;
+
+#[cfg(not(feature = "wasm-client"))]
+use std::time::Instant;
+#[cfg(feature = "wasm-client")]
+use wasm_timer::Instant;
+
+use iota_streams_core::{
+    prelude::{
+        Arc,
+        Rc,
+        Vec,
+    },
+    Result,
+};
+
+use super::Layer;
+#[cfg(feature = "async-send")]
+use crate::transport::{
+    SendTransport,
+    SendTransportDetails,
+};
+use crate::transport::{
+    Transport,
+    TransportDetails,
+    TransportOptions,
+};
+
+/// Measured transport operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// `send_message`
+    Send,
+    /// `recv_messages`, `recv_message` and `confirmation_timestamp`
+    Recv,
+}
+
+/// Sink of transport measurements.
+pub trait TransportMetrics {
+    /// Record an `op` call which took `latency` and succeeded or not.
+    fn record(&self, op: Operation, success: bool, latency: Duration);
+}
+
+impl<M: TransportMetrics> TransportMetrics for Arc<M> {
+    fn record(&self, op: Operation, success: bool, latency: Duration) {
+        (**self).record(op, success, latency)
+    }
+}
+
+impl<M: TransportMetrics> TransportMetrics for Rc<M> {
+    fn record(&self, op: Operation, success: bool, latency: Duration) {
+        (**self).record(op, success, latency)
+    }
+}
+
+/// Upper bounds (inclusive) of the latency histogram buckets in milliseconds, latencies
+/// above the last bound are counted in an extra overflow bucket.
+pub const LATENCY_BUCKETS_MS: [u64; 10] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1000];
+
+/// Counters and latency histogram of one operation.
+#[derive(Default, Debug)]
+pub struct OperationMetrics {
+    calls: AtomicU64,
+    failures: AtomicU64,
+    /// One bucket per bound of `LATENCY_BUCKETS_MS` plus the overflow bucket.
+    latencies: [AtomicU64; 11],
+}
+
+impl OperationMetrics {
+    /// Number of calls.
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    /// Number of failed calls.
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Number of calls per latency bucket, see [`LATENCY_BUCKETS_MS`].
+    pub fn latency_histogram(&self) -> Vec<u64> {
+        self.latencies.iter().map(|n| n.load(Ordering::Relaxed)).collect()
+    }
+
+    fn record(&self, success: bool, latency: Duration) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+        let ms = latency.as_millis();
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| ms <= *bound as u128)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.latencies[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Thread-safe in-memory metrics, share it with `Arc` to read it while the transport is in use.
+#[derive(Default, Debug)]
+pub struct BasicMetrics {
+    send: OperationMetrics,
+    recv: OperationMetrics,
+}
+
+impl BasicMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Metrics of the `op` operation.
+    pub fn get(&self, op: Operation) -> &OperationMetrics {
+        match op {
+            Operation::Send => &self.send,
+            Operation::Recv => &self.recv,
+        }
+    }
+}
+
+impl TransportMetrics for BasicMetrics {
+    fn record(&self, op: Operation, success: bool, latency: Duration) {
+        self.get(op).record(success, latency)
+    }
+}
+
+/// Transport recording the outcome and latency of every `send_message` and `recv_messages` call.
+#[derive(Clone)]
+pub struct Metrics<Tsp, M> {
+    inner: Tsp,
+    metrics: M,
+}
+
+impl<Tsp, M: TransportMetrics> Metrics<Tsp, M> {
+    pub fn new(inner: Tsp, metrics: M) -> Self {
+        Self { inner, metrics }
+    }
+
+    pub fn metrics(&self) -> &M {
+        &self.metrics
+    }
+
+    pub fn inner(&self) -> &Tsp {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> Tsp {
+        self.inner
+    }
+
+    fn record<T>(&self, op: Operation, start: Instant, r: Result<T>) -> Result<T> {
+        self.metrics.record(op, r.is_ok(), start.elapsed());
+        r
+    }
+}
+
+/// Layer producing [`Metrics`] transports, each of them recording into a clone of the sink.
+#[derive(Clone, Debug, Default)]
+pub struct MetricsLayer<M> {
+    metrics: M,
+}
+
+impl<M> MetricsLayer<M> {
+    pub fn new(metrics: M) -> Self {
+        Self { metrics }
+    }
+}
+
+impl<Tsp, M: TransportMetrics + Clone> Layer<Tsp> for MetricsLayer<M> {
+    type Transport = Metrics<Tsp, M>;
+    fn layer(&self, inner: Tsp) -> Metrics<Tsp, M> {
+        Metrics::new(inner, self.metrics.clone())
+    }
+}
+
+impl<Tsp: TransportOptions, M> TransportOptions for Metrics<Tsp, M> {
+    type SendOptions = <Tsp as TransportOptions>::SendOptions;
+    fn get_send_options(&self) -> Self::SendOptions {
+        self.inner.get_send_options()
+    }
+    fn set_send_options(&mut self, opt: Self::SendOptions) {
+        self.inner.set_send_options(opt)
+    }
+
+    type RecvOptions = <Tsp as TransportOptions>::RecvOptions;
+    fn get_recv_options(&self) -> Self::RecvOptions {
+        self.inner.get_recv_options()
+    }
+    fn set_recv_options(&mut self, opt: Self::RecvOptions) {
+        self.inner.set_recv_options(opt)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link, Tsp: TransportDetails<Link>, M> TransportDetails<Link> for Metrics<Tsp, M> {
+    type Details = <Tsp as TransportDetails<Link>>::Details;
+    fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.inner.get_link_details(link)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link, Msg, Tsp, M> Transport<Link, Msg> for Metrics<Tsp, M>
+where
+    Link: Debug + Display,
+    Tsp: Transport<Link, Msg>,
+    M: TransportMetrics,
+{
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let start = Instant::now();
+        let r = self.inner.send_message(msg);
+        self.record(Operation::Send, start, r)
+    }
+
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        let start = Instant::now();
+        let r = self.inner.recv_messages(link);
+        self.record(Operation::Recv, start, r)
+    }
+
+    fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let start = Instant::now();
+        let r = self.inner.recv_message(link);
+        self.record(Operation::Recv, start, r)
+    }
+
+    fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        let start = Instant::now();
+        let r = self.inner.confirmation_timestamp(msg);
+        self.record(Operation::Recv, start, r)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Tsp, M> TransportDetails<Link> for Metrics<Tsp, M>
+where
+    Link: 'static + Send + Sync,
+    Tsp: TransportDetails<Link>,
+    M: TransportMetrics,
+{
+    type Details = <Tsp as TransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.inner.get_link_details(link).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Msg, Tsp, M> Transport<Link, Msg> for Metrics<Tsp, M>
+where
+    Link: 'static + Send + Sync,
+    Msg: 'static + Send + Sync,
+    Tsp: Transport<Link, Msg>,
+    M: TransportMetrics,
+{
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let start = Instant::now();
+        let r = self.inner.send_message(msg).await;
+        self.record(Operation::Send, start, r)
+    }
+
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        let start = Instant::now();
+        let r = self.inner.recv_messages(link).await;
+        self.record(Operation::Recv, start, r)
+    }
+
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let start = Instant::now();
+        let r = self.inner.recv_message(link).await;
+        self.record(Operation::Recv, start, r)
+    }
+
+    async fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        let start = Instant::now();
+        let r = self.inner.confirmation_timestamp(msg).await;
+        self.record(Operation::Recv, start, r)
+    }
+}
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Link, Tsp, M> SendTransportDetails<Link> for Metrics<Tsp, M>
+where
+    Link: 'static + Send + Sync,
+    Tsp: SendTransportDetails<Link>,
+    M: TransportMetrics + Send,
+{
+    type Details = <Tsp as SendTransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.inner.get_link_details(link).await
+    }
+}
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Link, Msg, Tsp, M> SendTransport<Link, Msg> for Metrics<Tsp, M>
+where
+    Link: 'static + Send + Sync,
+    Msg: 'static + Send + Sync,
+    Tsp: SendTransport<Link, Msg>,
+    M: TransportMetrics + Send,
+{
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let start = Instant::now();
+        let r = self.inner.send_message(msg).await;
+        self.record(Operation::Send, start, r)
+    }
+
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        let start = Instant::now();
+        let r = self.inner.recv_messages(link).await;
+        self.record(Operation::Recv, start, r)
+    }
+
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let start = Instant::now();
+        let r = self.inner.recv_message(link).await;
+        self.record(Operation::Recv, start, r)
+    }
+
+    async fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        let start = Instant::now();
+        let r = self.inner.confirmation_timestamp(msg).await;
+        self.record(Operation::Recv, start, r)
+    }
+}
@@ -0,0 +1,70 @@
+//! Composable transport middleware.
+//!
+//! Each layer wraps any transport and adds a single behaviour to it, the wrapped transport is a
+//! transport itself so layers can be stacked:
+//!
+//! ```ignore
+//! let metrics = Arc::new(BasicMetrics::default());
+//! let transport = BucketTransport::new()
+//!     .with_layer(CacheLayer::new(1024, Duration::from_secs(10), Arc::new(SystemClock)))
+//!     .with_layer(RetryLayer::new(RetryPolicy::default()))
+//!     .with_layer(MetricsLayer::new(metrics.clone()));
+//! let transport = new_shared_transport(transport);
+//! ```
+//!
+//! The last added layer is the outermost one: in the example above every call is measured
+//! including its retries, and only cache misses are forwarded to the bucket. The stack is shared
+//! between users with `new_shared_transport`, so that they all read and invalidate one cache.
+
+/// Retry failed operations with exponential backoff.
+mod retry;
+#[cfg(feature = "async")]
+pub use retry::sleep;
+#[cfg(not(feature = "async"))]
+pub use retry::Sleep;
+pub use retry::{
+    is_retriable,
+    Retry,
+    RetryLayer,
+    RetryPolicy,
+};
+
+/// Read-through cache of received messages.
+mod cache;
+pub use cache::{
+    Cache,
+    CacheLayer,
+};
+
+/// Operation counters and latency histograms.
+#[cfg(feature = "std")]
+mod metrics;
+#[cfg(feature = "std")]
+pub use metrics::{
+    BasicMetrics,
+    Metrics,
+    MetricsLayer,
+    Operation,
+    OperationMetrics,
+    TransportMetrics,
+    LATENCY_BUCKETS_MS,
+};
+
+/// Transport decorator factory.
+pub trait Layer<Tsp> {
+    /// Transport wrapping `Tsp`.
+    type Transport;
+
+    /// Wrap `inner` transport.
+    fn layer(&self, inner: Tsp) -> Self::Transport;
+}
+
+/// Stack layers on top of a transport.
+pub trait LayerExt: Sized {
+    /// Wrap this transport with `layer`.
+    fn with_layer<L: Layer<Self>>(self, layer: L) -> L::Transport {
+        layer.layer(self)
+    }
+}
+
+impl<Tsp> LayerExt for Tsp {}
@@ -0,0 +1,453 @@
+use core::time::Duration;
+
+#[cfg(not(feature = "async"))]
+use core::fmt::{
+    Debug,
+    Display,
+};
+
+#[cfg(feature = "async")]
+use async_trait::async_trait;
+#[cfg(feature = "async")]
+use core::marker::{
+    Send,
+    Sync,
+};
+#[cfg(not(feature = "async"))]
+use iota_streams_core::prelude::Arc;
+#[cfg(feature = "async")]
+use iota_streams_core::prelude::Box;
+
+use iota_streams_core::{
+    prelude::Vec,
+    Error,
+    Errors,
+    Result,
+};
+
+use super::Layer;
+#[cfg(feature = "async-send")]
+use crate::transport::{
+    SendTransport,
+    SendTransportDetails,
+};
+use crate::transport::{
+    Transport,
+    TransportDetails,
+    TransportOptions,
+};
+
+/// Retry policy with exponential backoff.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first failed attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each following retry.
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay between retries.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_backoff,
+            max_backoff,
+        }
+    }
+
+    /// Delay before retry number `retry`, starting from 0.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        1_u32
+            .checked_shl(retry)
+            .and_then(|factor| self.initial_backoff.checked_mul(factor))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+
+    /// Delays before each of the retries.
+    pub fn backoffs(&self) -> impl Iterator<Item = Duration> + '_ {
+        (0..self.max_retries).map(move |retry| self.backoff(retry))
+    }
+}
+
+/// Missing or ambiguous messages and an unavailable transport are not transient failures, they
+/// are reported straight away.
+pub fn is_retriable(e: &Error) -> bool {
+    !matches!(
+        e.downcast_ref::<Errors>(),
+        Some(Errors::MessageLinkNotFound(_)) | Some(Errors::MessageNotUnique(_)) | Some(Errors::TransportNotAvailable)
+    )
+}
+
+/// Evaluate `$op` until it succeeds or fails with an error which is not retriable, waiting with
+/// `$sleep` for each backoff of `$policy` in between. The last error is returned once the
+/// retries are exhausted.
+macro_rules! retry {
+    ($policy:expr, |$backoff:ident| $sleep:expr, $op:expr) => {{
+        let mut backoffs = $policy.backoffs();
+        loop {
+            match $op {
+                Err(e) if is_retriable(&e) => match backoffs.next() {
+                    Some($backoff) => $sleep,
+                    None => break Err(e),
+                },
+                r => break r,
+            }
+        }
+    }};
+}
+
+/// Wait between retries, see [`Retry::with_sleep`].
+#[cfg(not(feature = "async"))]
+pub type Sleep = Arc<dyn Fn(Duration) + Send + Sync>;
+
+/// Default wait between retries: blocks the thread.
+#[cfg(all(not(feature = "async"), feature = "std"))]
+fn default_sleep() -> Sleep {
+    Arc::new(std::thread::sleep)
+}
+
+/// Default wait between retries: there is no portable way to wait without `std`, retries follow
+/// each other immediately unless a sleep function is given with [`Retry::with_sleep`].
+#[cfg(all(not(feature = "async"), not(feature = "std")))]
+fn default_sleep() -> Sleep {
+    Arc::new(|_| {})
+}
+
+/// Asynchronous wait between retries.
+#[cfg(feature = "async")]
+pub async fn sleep(duration: Duration) {
+    let _ = wasm_timer::Delay::new(duration).await;
+}
+
+/// Transport retrying failed `send_message` and `recv_messages` calls with exponential backoff.
+#[derive(Clone)]
+pub struct Retry<Tsp> {
+    inner: Tsp,
+    policy: RetryPolicy,
+    #[cfg(not(feature = "async"))]
+    sleep: Sleep,
+}
+
+impl<Tsp> Retry<Tsp> {
+    pub fn new(inner: Tsp, policy: RetryPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            #[cfg(not(feature = "async"))]
+            sleep: default_sleep(),
+        }
+    }
+
+    /// Wait between retries with `sleep`, eg. a platform timer without `std` or a recorder in tests.
+    #[cfg(not(feature = "async"))]
+    pub fn with_sleep(mut self, sleep: Sleep) -> Self {
+        self.sleep = sleep;
+        self
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    pub fn inner(&self) -> &Tsp {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> Tsp {
+        self.inner
+    }
+}
+
+/// Layer producing [`Retry`] transports.
+#[derive(Clone, Default)]
+pub struct RetryLayer {
+    policy: RetryPolicy,
+    #[cfg(not(feature = "async"))]
+    sleep: Option<Sleep>,
+}
+
+impl RetryLayer {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+            #[cfg(not(feature = "async"))]
+            sleep: None,
+        }
+    }
+
+    /// Wait between retries with `sleep`, see [`Retry::with_sleep`].
+    #[cfg(not(feature = "async"))]
+    pub fn with_sleep(mut self, sleep: Sleep) -> Self {
+        self.sleep = Some(sleep);
+        self
+    }
+}
+
+impl<Tsp> Layer<Tsp> for RetryLayer {
+    type Transport = Retry<Tsp>;
+
+    #[cfg(not(feature = "async"))]
+    fn layer(&self, inner: Tsp) -> Retry<Tsp> {
+        match &self.sleep {
+            Some(sleep) => Retry::new(inner, self.policy).with_sleep(sleep.clone()),
+            None => Retry::new(inner, self.policy),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn layer(&self, inner: Tsp) -> Retry<Tsp> {
+        Retry::new(inner, self.policy)
+    }
+}
+
+impl<Tsp: TransportOptions> TransportOptions for Retry<Tsp> {
+    type SendOptions = <Tsp as TransportOptions>::SendOptions;
+    fn get_send_options(&self) -> Self::SendOptions {
+        self.inner.get_send_options()
+    }
+    fn set_send_options(&mut self, opt: Self::SendOptions) {
+        self.inner.set_send_options(opt)
+    }
+
+    type RecvOptions = <Tsp as TransportOptions>::RecvOptions;
+    fn get_recv_options(&self) -> Self::RecvOptions {
+        self.inner.get_recv_options()
+    }
+    fn set_recv_options(&mut self, opt: Self::RecvOptions) {
+        self.inner.set_recv_options(opt)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link, Tsp: TransportDetails<Link>> TransportDetails<Link> for Retry<Tsp> {
+    type Details = <Tsp as TransportDetails<Link>>::Details;
+    fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.inner.get_link_details(link)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link: Debug + Display, Msg, Tsp: Transport<Link, Msg>> Transport<Link, Msg> for Retry<Tsp> {
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        retry!(
+            self.policy,
+            |backoff| (self.sleep)(backoff),
+            self.inner.send_message(msg)
+        )
+    }
+
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        retry!(
+            self.policy,
+            |backoff| (self.sleep)(backoff),
+            self.inner.recv_messages(link)
+        )
+    }
+
+    fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        retry!(
+            self.policy,
+            |backoff| (self.sleep)(backoff),
+            self.inner.recv_message(link)
+        )
+    }
+
+    fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        retry!(
+            self.policy,
+            |backoff| (self.sleep)(backoff),
+            self.inner.confirmation_timestamp(msg)
+        )
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Tsp> TransportDetails<Link> for Retry<Tsp>
+where
+    Link: 'static + Send + Sync,
+    Tsp: TransportDetails<Link>,
+{
+    type Details = <Tsp as TransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.inner.get_link_details(link).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Msg, Tsp> Transport<Link, Msg> for Retry<Tsp>
+where
+    Link: 'static + Send + Sync,
+    Msg: 'static + Send + Sync,
+    Tsp: Transport<Link, Msg>,
+{
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        retry!(
+            self.policy,
+            |backoff| sleep(backoff).await,
+            self.inner.send_message(msg).await
+        )
+    }
+
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        retry!(
+            self.policy,
+            |backoff| sleep(backoff).await,
+            self.inner.recv_messages(link).await
+        )
+    }
+
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        retry!(
+            self.policy,
+            |backoff| sleep(backoff).await,
+            self.inner.recv_message(link).await
+        )
+    }
+
+    async fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        retry!(
+            self.policy,
+            |backoff| sleep(backoff).await,
+            self.inner.confirmation_timestamp(msg).await
+        )
+    }
+}
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Link, Tsp> SendTransportDetails<Link> for Retry<Tsp>
+where
+    Link: 'static + Send + Sync,
+    Tsp: SendTransportDetails<Link>,
+{
+    type Details = <Tsp as SendTransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.inner.get_link_details(link).await
+    }
+}
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Link, Msg, Tsp> SendTransport<Link, Msg> for Retry<Tsp>
+where
+    Link: 'static + Send + Sync,
+    Msg: 'static + Send + Sync,
+    Tsp: SendTransport<Link, Msg>,
+{
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        retry!(
+            self.policy,
+            |backoff| sleep(backoff).await,
+            self.inner.send_message(msg).await
+        )
+    }
+
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        retry!(
+            self.policy,
+            |backoff| sleep(backoff).await,
+            self.inner.recv_messages(link).await
+        )
+    }
+
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        retry!(
+            self.policy,
+            |backoff| sleep(backoff).await,
+            self.inner.recv_message(link).await
+        )
+    }
+
+    async fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        retry!(
+            self.policy,
+            |backoff| sleep(backoff).await,
+            self.inner.confirmation_timestamp(msg).await
+        )
+    }
+}
+
+#[cfg(all(test, not(feature = "async"), feature = "std"))]
+mod tests {
+    use core::time::Duration;
+    use iota_streams_core::prelude::Arc;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::transport::{
+        layer::{
+            BasicMetrics,
+            LayerExt,
+            MetricsLayer,
+            Operation,
+        },
+        test::{
+            FaultyTransport,
+            TestMessage,
+        },
+    };
+
+    /// Transient failures are retried with exponential backoff, missing or ambiguous messages are not.
+    #[test]
+    fn retry_transient_failures_with_backoff() {
+        let faulty = FaultyTransport::new();
+        let attempts = Arc::new(BasicMetrics::new());
+        let backoffs = Arc::new(Mutex::new(Vec::new()));
+        let recorded = backoffs.clone();
+        let policy = RetryPolicy::new(3, Duration::from_millis(10), Duration::from_millis(25));
+        let sleep: Sleep = Arc::new(move |backoff| recorded.lock().unwrap().push(backoff));
+        let mut transport = faulty
+            .clone()
+            .with_layer(MetricsLayer::new(attempts.clone()))
+            .with_layer(RetryLayer::new(policy).with_sleep(sleep));
+        let take_backoffs = || core::mem::take(&mut *backoffs.lock().unwrap());
+
+        faulty.sends_left.set(Some(0));
+        assert!(transport.send_message(&TestMessage::new(1, 1)).is_err());
+        assert_eq!(attempts.get(Operation::Send).calls(), 4);
+        assert_eq!(attempts.get(Operation::Send).failures(), 4);
+        assert_eq!(
+            take_backoffs(),
+            vec![
+                Duration::from_millis(10),
+                Duration::from_millis(20),
+                Duration::from_millis(25)
+            ]
+        );
+
+        faulty.sends_left.set(None);
+        transport.send_message(&TestMessage::new(1, 1)).unwrap();
+        transport.send_message(&TestMessage::new(1, 2)).unwrap();
+        assert_eq!(attempts.get(Operation::Send).calls(), 6);
+        assert!(take_backoffs().is_empty());
+
+        faulty.recvs_fail.set(true);
+        assert!(transport.recv_messages(&1).is_err());
+        assert_eq!(attempts.get(Operation::Recv).calls(), 4);
+        assert_eq!(take_backoffs().len(), 3);
+        faulty.recvs_fail.set(false);
+
+        // Missing and ambiguous messages are reported after a single attempt
+        assert!(transport.recv_messages(&2).is_err());
+        assert!(transport.recv_message(&1).is_err());
+        assert_eq!(attempts.get(Operation::Recv).calls(), 6);
+        assert_eq!(attempts.get(Operation::Recv).failures(), 6);
+        assert!(take_backoffs().is_empty());
+        assert_eq!(transport.recv_messages(&1).unwrap().len(), 2);
+        assert_eq!(attempts.get(Operation::Recv).failures(), 6);
+    }
+}
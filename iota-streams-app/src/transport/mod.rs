@@ -5,13 +5,13 @@ use core::cell::RefCell;
 #[cfg(feature = "async")]
 use async_trait::async_trait;
 #[cfg(feature = "async")]
-use atomic_refcell::AtomicRefCell;
-#[cfg(feature = "async")]
 use core::marker::{
     Send,
     Sync,
 };
 #[cfg(feature = "async")]
+use futures::lock::Mutex;
+#[cfg(feature = "async")]
 use iota_streams_core::prelude::{
     Arc,
     Box,
@@ -41,6 +41,17 @@ where
     async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details>;
 }
 
+/// `Send` variant of [`TransportDetails`] so that its futures can be spawned on multi-threaded executors.
+#[cfg(feature = "async-send")]
+#[async_trait]
+pub trait SendTransportDetails<Link>: Send
+where
+    Link: Send + Sync,
+{
+    type Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details>;
+}
+
 pub trait TransportOptions {
     type SendOptions;
     fn get_send_options(&self) -> Self::SendOptions;
@@ -51,6 +62,18 @@ pub trait TransportOptions {
     fn set_recv_options(&mut self, opt: Self::RecvOptions);
 }
 
+/// Pending notification of a [`TransportSubscribe`] transport, resolves to the watched link
+/// something was published at.
+pub type Subscription<Link> = futures::channel::oneshot::Receiver<Link>;
+
+/// Transport able to notify about messages published at watched links, so that readers can wait
+/// for new messages instead of polling.
+pub trait TransportSubscribe<Link> {
+    /// Watch `links` until something is published at one of them. Messages published before the
+    /// call are not reported, dropping the subscription stops watching.
+    fn subscribe(&mut self, links: Vec<Link>) -> Result<Subscription<Link>>;
+}
+
 /// Network transport abstraction.
 /// Parametrized by the type of message links.
 /// Message link is used to identify/locate a message (eg. like URL for HTTP).
@@ -72,6 +95,13 @@ pub trait Transport<Link: Debug + Display, Msg>: TransportOptions + TransportDet
             err!(MessageLinkNotFound(link.to_string()))
         }
     }
+
+    /// Look up the time a received message has been confirmed at in milliseconds since the Unix
+    /// epoch, eg. the timestamp of the milestone referencing it on the Tangle. `None` if the
+    /// message is not confirmed yet or the transport has no notion of confirmation.
+    fn confirmation_timestamp(&mut self, _msg: &Msg) -> Result<Option<u64>> {
+        Ok(None)
+    }
 }
 
 #[cfg(feature = "async")]
@@ -89,6 +119,13 @@ where
 
     /// Receive a message with default options.
     async fn recv_message(&mut self, link: &Link) -> Result<Msg>;
+
+    /// Look up the time a received message has been confirmed at in milliseconds since the Unix
+    /// epoch, eg. the timestamp of the milestone referencing it on the Tangle. `None` if the
+    /// message is not confirmed yet or the transport has no notion of confirmation.
+    async fn confirmation_timestamp(&mut self, _msg: &Msg) -> Result<Option<u64>> {
+        Ok(None)
+    }
     // For some reason compiler requires (Msg: `async_trait) lifetime bound for this default implementation.
     // {
     // let mut msgs = self.recv_messages(link).await?;
@@ -101,6 +138,32 @@ where
     // }
 }
 
+/// `Send` variant of [`Transport`] so that its futures can be spawned on multi-threaded executors.
+///
+/// Implemented by the bucket, file and client transports, the transport layers and shared transports.
+#[cfg(feature = "async-send")]
+#[async_trait]
+pub trait SendTransport<Link, Msg>: TransportOptions + SendTransportDetails<Link>
+where
+    Link: Send + Sync,
+    Msg: Send + Sync,
+{
+    /// Send a message with default options.
+    async fn send_message(&mut self, msg: &Msg) -> Result<()>;
+
+    /// Receive messages with default options.
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>>;
+
+    /// Receive a message with default options.
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg>;
+
+    /// Look up the time a received message has been confirmed at, see
+    /// [`Transport::confirmation_timestamp`].
+    async fn confirmation_timestamp(&mut self, _msg: &Msg) -> Result<Option<u64>> {
+        Ok(None)
+    }
+}
+
 impl<Tsp: TransportOptions> TransportOptions for Rc<RefCell<Tsp>> {
     type SendOptions = <Tsp as TransportOptions>::SendOptions;
     fn get_send_options(&self) -> Self::SendOptions {
@@ -152,6 +215,23 @@ impl<Link: Debug + Display, Msg, Tsp: Transport<Link, Msg>> Transport<Link, Msg>
             Err(err) => Err(wrapped_err!(TransportNotAvailable, WrappedError(err))),
         }
     }
+
+    /// Look up the time a received message has been confirmed at.
+    fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        match (&*self).try_borrow_mut() {
+            Ok(mut tsp) => tsp.confirmation_timestamp(msg),
+            Err(err) => Err(wrapped_err!(TransportNotAvailable, WrappedError(err))),
+        }
+    }
+}
+
+impl<Link, Tsp: TransportSubscribe<Link>> TransportSubscribe<Link> for Rc<RefCell<Tsp>> {
+    fn subscribe(&mut self, links: Vec<Link>) -> Result<Subscription<Link>> {
+        match (&*self).try_borrow_mut() {
+            Ok(mut tsp) => tsp.subscribe(links),
+            Err(_err) => err!(TransportNotAvailable),
+        }
+    }
 }
 
 #[cfg(not(feature = "async"))]
@@ -162,76 +242,174 @@ pub fn new_shared_transport<T>(tsp: T) -> Rc<RefCell<T>> {
     Rc::new(RefCell::new(tsp))
 }
 
+/// Options can't wait for the transport, they panic while an operation on a clone is in progress.
 #[cfg(feature = "async")]
-impl<Tsp: TransportOptions> TransportOptions for Arc<AtomicRefCell<Tsp>> {
+impl<Tsp: TransportOptions> TransportOptions for Arc<Mutex<Tsp>> {
     type SendOptions = <Tsp as TransportOptions>::SendOptions;
     fn get_send_options(&self) -> Self::SendOptions {
-        (&*self).borrow().get_send_options()
+        self.try_lock().expect(TRANSPORT_IN_USE).get_send_options()
     }
     fn set_send_options(&mut self, opt: Self::SendOptions) {
-        (&*self).borrow_mut().set_send_options(opt)
+        self.try_lock().expect(TRANSPORT_IN_USE).set_send_options(opt)
     }
 
     type RecvOptions = <Tsp as TransportOptions>::RecvOptions;
     fn get_recv_options(&self) -> Self::RecvOptions {
-        (&*self).borrow().get_recv_options()
+        self.try_lock().expect(TRANSPORT_IN_USE).get_recv_options()
     }
     fn set_recv_options(&mut self, opt: Self::RecvOptions) {
-        (&*self).borrow_mut().set_recv_options(opt)
+        self.try_lock().expect(TRANSPORT_IN_USE).set_recv_options(opt)
     }
 }
 
-// The impl below is too restrictive: Link and Msg require 'async_trait life-time, Tsp is Sync + Send.
-// #[cfg(feature = "async")]
-// #[async_trait]
-// impl<Link, Msg, Tsp: Transport<Link, Msg>> Transport<Link, Msg> for Arc<AtomicRefCell<Tsp>> where
-// Link: 'static + core::marker::Send + core::marker::Sync,
-// Msg: 'static + core::marker::Send + core::marker::Sync,
-// Tsp: core::marker::Send + core::marker::Sync,
-// {
-// Send a message.
-// async fn send_message(&mut self, msg: &Msg) -> Result<()> {
-// (&*self).borrow_mut().send_message(msg).await
-// }
-//
-// Receive messages with default options.
-// async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
-// (&*self).borrow_mut().recv_messages(link).await
-// }
-//
-// Receive a message with default options.
-// async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
-// (&*self).borrow_mut().recv_message(link).await
-// }
-// }
+#[cfg(feature = "async")]
+const TRANSPORT_IN_USE: &str = "Shared transport is in use";
 
 #[cfg(feature = "async")]
-pub type SharedTransport<T> = Arc<AtomicRefCell<T>>;
+#[async_trait(?Send)]
+impl<Tsp: TransportDetails<Link>, Link> TransportDetails<Link> for Arc<Mutex<Tsp>>
+where
+    Link: 'static + Send + Sync,
+{
+    type Details = <Tsp as TransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.lock().await.get_link_details(link).await
+    }
+}
 
+/// Operations on clones of a shared transport wait for each other.
 #[cfg(feature = "async")]
-pub fn new_shared_transport<T>(tsp: T) -> Arc<AtomicRefCell<T>> {
-    Arc::new(AtomicRefCell::new(tsp))
+#[async_trait(?Send)]
+impl<Link, Msg, Tsp: Transport<Link, Msg>> Transport<Link, Msg> for Arc<Mutex<Tsp>>
+where
+    Link: 'static + Send + Sync,
+    Msg: 'static + Send + Sync,
+{
+    /// Send a message.
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.lock().await.send_message(msg).await
+    }
+
+    /// Receive messages with default options.
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        self.lock().await.recv_messages(link).await
+    }
+
+    /// Receive a message with default options.
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        self.lock().await.recv_message(link).await
+    }
+
+    /// Look up the time a received message has been confirmed at.
+    async fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        self.lock().await.confirmation_timestamp(msg).await
+    }
+}
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Tsp: SendTransportDetails<Link>, Link> SendTransportDetails<Link> for Arc<Mutex<Tsp>>
+where
+    Link: 'static + Send + Sync,
+{
+    type Details = <Tsp as SendTransportDetails<Link>>::Details;
+    async fn get_link_details(&mut self, link: &Link) -> Result<Self::Details> {
+        self.lock().await.get_link_details(link).await
+    }
+}
+
+/// Operations on clones of a shared transport wait for each other.
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Link, Msg, Tsp: SendTransport<Link, Msg>> SendTransport<Link, Msg> for Arc<Mutex<Tsp>>
+where
+    Link: 'static + Send + Sync,
+    Msg: 'static + Send + Sync,
+{
+    /// Send a message.
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.lock().await.send_message(msg).await
+    }
+
+    /// Receive messages with default options.
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        self.lock().await.recv_messages(link).await
+    }
+
+    /// Receive a message with default options.
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        self.lock().await.recv_message(link).await
+    }
+
+    /// Look up the time a received message has been confirmed at.
+    async fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        self.lock().await.confirmation_timestamp(msg).await
+    }
+}
+
+/// Subscribing can't wait for the transport, it fails while an operation on a clone is in progress.
+#[cfg(feature = "async")]
+impl<Link, Tsp: TransportSubscribe<Link>> TransportSubscribe<Link> for Arc<Mutex<Tsp>> {
+    fn subscribe(&mut self, links: Vec<Link>) -> Result<Subscription<Link>> {
+        match self.try_lock() {
+            Some(mut tsp) => tsp.subscribe(links),
+            None => err!(TransportNotAvailable),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+pub type SharedTransport<T> = Arc<Mutex<T>>;
+
+#[cfg(feature = "async")]
+pub fn new_shared_transport<T>(tsp: T) -> Arc<Mutex<T>> {
+    Arc::new(Mutex::new(tsp))
 }
 
 mod bucket;
 pub use bucket::BucketTransport;
 
+/// Time sources, eg. of the expiry of cached messages.
+pub mod clock;
+
+/// Composable transport middleware: retry, cache and metrics layers.
+pub mod layer;
+
+mod multi;
+pub use multi::{
+    BackendFailure,
+    DynTransport,
+    MultiTransport,
+    ReadPolicy,
+    WritePolicy,
+};
+
+#[cfg(all(test, not(feature = "async")))]
+mod test;
+
+#[cfg(all(feature = "std", feature = "tangle"))]
+mod file;
+#[cfg(all(feature = "std", feature = "tangle"))]
+pub use file::FileTransport;
+
 #[cfg(not(feature = "async"))]
 use core::fmt::{
     Debug,
     Display,
 };
 
-use iota_streams_core::try_or;
+use iota_streams_core::{
+    err,
+    try_or,
+    Errors::TransportNotAvailable,
+};
 
 #[cfg(not(feature = "async"))]
 use iota_streams_core::{
-    err,
     wrapped_err,
     Errors::{
         MessageLinkNotFound,
         MessageNotUnique,
-        TransportNotAvailable,
     },
     WrappedError,
 };
@@ -0,0 +1,616 @@
+//! Transport replicating messages over several backends.
+//!
+//! Every message is sent to all backends, the [`WritePolicy`] decides how many of them must
+//! accept it for the send to succeed. Messages are received according to the [`ReadPolicy`]:
+//! either from the first backend which has them or merged from all of them. Backends may be of
+//! different types, eg. a Tangle client mirrored into a local bucket:
+//!
+//! ```ignore
+//! let transport = MultiTransport::new(WritePolicy::All, ReadPolicy::FirstSuccess)
+//!     .with_transport(client)
+//!     .with_transport(bucket);
+//! ```
+//!
+//! Failures of individual backends which did not fail the whole operation are kept until the next
+//! operation and can be inspected with [`MultiTransport::last_failures`].
+
+use core::fmt::{
+    self,
+    Display,
+};
+
+#[cfg(not(feature = "async"))]
+use core::fmt::Debug;
+
+#[cfg(feature = "async")]
+use async_trait::async_trait;
+#[cfg(feature = "async")]
+use core::marker::{
+    Send,
+    Sync,
+};
+#[cfg(feature = "async")]
+use futures::{
+    future::join_all,
+    stream::{
+        FuturesUnordered,
+        StreamExt,
+    },
+};
+
+use iota_streams_core::{
+    err,
+    prelude::{
+        Box,
+        ToString,
+        Vec,
+    },
+    try_or,
+    Error,
+    Errors,
+    Errors::{
+        AllTransportsFailed,
+        MessageLinkNotFound,
+        TransportWritePolicyNotMet,
+    },
+    Result,
+};
+
+#[cfg(feature = "async")]
+use iota_streams_core::Errors::MessageNotUnique;
+
+use crate::transport::{
+    Transport,
+    TransportDetails,
+    TransportOptions,
+};
+
+/// Number of backends which must accept a message for `send_message` to succeed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WritePolicy {
+    /// Every backend.
+    All,
+    /// More than half of the backends.
+    Quorum,
+    /// At least one backend.
+    Any,
+}
+
+impl WritePolicy {
+    /// Number of successful sends required out of `backends`, at least one.
+    pub fn required(&self, backends: usize) -> usize {
+        let required = match self {
+            WritePolicy::All => backends,
+            WritePolicy::Quorum => backends / 2 + 1,
+            WritePolicy::Any => 1,
+        };
+        required.max(1)
+    }
+}
+
+impl Default for WritePolicy {
+    fn default() -> Self {
+        WritePolicy::All
+    }
+}
+
+/// How messages are received from the backends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadPolicy {
+    /// Return messages of the first backend which has them.
+    FirstSuccess,
+    /// Return messages of all backends which have them, without duplicates.
+    Merge,
+}
+
+impl Default for ReadPolicy {
+    fn default() -> Self {
+        ReadPolicy::FirstSuccess
+    }
+}
+
+/// Failure of one backend of a [`MultiTransport`].
+#[derive(Debug)]
+pub struct BackendFailure {
+    /// Index of the backend in the order it was added.
+    pub backend: usize,
+    pub error: Error,
+}
+
+impl Display for BackendFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "transport {}: {}", self.backend, self.error)
+    }
+}
+
+fn is_link_not_found(e: &Error) -> bool {
+    matches!(e.downcast_ref::<Errors>(), Some(MessageLinkNotFound(_)))
+}
+
+/// Object-safe subset of [`Transport`] so that backends of different types can be combined.
+///
+/// Implemented for every cloneable transport.
+#[cfg(not(feature = "async"))]
+pub trait DynTransport<Link, Msg> {
+    fn send_message(&mut self, msg: &Msg) -> Result<()>;
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>>;
+    fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>>;
+    fn clone_box(&self) -> Box<dyn DynTransport<Link, Msg>>;
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link, Msg, Tsp> DynTransport<Link, Msg> for Tsp
+where
+    Link: Debug + Display,
+    Tsp: 'static + Transport<Link, Msg> + Clone,
+{
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        Transport::send_message(self, msg)
+    }
+
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        Transport::recv_messages(self, link)
+    }
+
+    fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        Transport::confirmation_timestamp(self, msg)
+    }
+
+    fn clone_box(&self) -> Box<dyn DynTransport<Link, Msg>> {
+        Box::new(self.clone())
+    }
+}
+
+/// Object-safe subset of [`Transport`] so that backends of different types can be combined.
+///
+/// Implemented for every cloneable transport.
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+pub trait DynTransport<Link, Msg> {
+    async fn send_message(&mut self, msg: &Msg) -> Result<()>;
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>>;
+    async fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>>;
+    fn clone_box(&self) -> Box<dyn DynTransport<Link, Msg>>;
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Msg, Tsp> DynTransport<Link, Msg> for Tsp
+where
+    Link: 'static + Send + Sync,
+    Msg: 'static + Send + Sync,
+    Tsp: 'static + Transport<Link, Msg> + Clone,
+{
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        Transport::send_message(self, msg).await
+    }
+
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        Transport::recv_messages(self, link).await
+    }
+
+    async fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        Transport::confirmation_timestamp(self, msg).await
+    }
+
+    fn clone_box(&self) -> Box<dyn DynTransport<Link, Msg>> {
+        Box::new(self.clone())
+    }
+}
+
+/// Transport sending messages to and receiving them from a list of backends.
+pub struct MultiTransport<Link, Msg> {
+    backends: Vec<Box<dyn DynTransport<Link, Msg>>>,
+    write_policy: WritePolicy,
+    read_policy: ReadPolicy,
+    failures: Vec<BackendFailure>,
+}
+
+impl<Link, Msg> Clone for MultiTransport<Link, Msg> {
+    /// Clone backends, failures of the last operation are not cloned.
+    fn clone(&self) -> Self {
+        Self {
+            backends: self.backends.iter().map(|tsp| tsp.clone_box()).collect(),
+            write_policy: self.write_policy,
+            read_policy: self.read_policy,
+            failures: Vec::new(),
+        }
+    }
+}
+
+impl<Link, Msg> Default for MultiTransport<Link, Msg> {
+    fn default() -> Self {
+        Self::new(WritePolicy::default(), ReadPolicy::default())
+    }
+}
+
+impl<Link, Msg> MultiTransport<Link, Msg> {
+    /// Create a transport without backends.
+    pub fn new(write_policy: WritePolicy, read_policy: ReadPolicy) -> Self {
+        Self {
+            backends: Vec::new(),
+            write_policy,
+            read_policy,
+            failures: Vec::new(),
+        }
+    }
+
+    /// Add a backend.
+    pub fn add_transport<Tsp: 'static + DynTransport<Link, Msg>>(&mut self, tsp: Tsp) {
+        self.backends.push(Box::new(tsp));
+    }
+
+    /// Add a backend, builder style.
+    pub fn with_transport<Tsp: 'static + DynTransport<Link, Msg>>(mut self, tsp: Tsp) -> Self {
+        self.add_transport(tsp);
+        self
+    }
+
+    /// Number of backends.
+    pub fn len(&self) -> usize {
+        self.backends.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.backends.is_empty()
+    }
+
+    pub fn write_policy(&self) -> WritePolicy {
+        self.write_policy
+    }
+
+    pub fn set_write_policy(&mut self, write_policy: WritePolicy) {
+        self.write_policy = write_policy;
+    }
+
+    pub fn read_policy(&self) -> ReadPolicy {
+        self.read_policy
+    }
+
+    pub fn set_read_policy(&mut self, read_policy: ReadPolicy) {
+        self.read_policy = read_policy;
+    }
+
+    /// Backend failures of the last operation, including those tolerated by the policy.
+    pub fn last_failures(&self) -> &[BackendFailure] {
+        &self.failures
+    }
+
+    fn check_write(&self, succeeded: usize) -> Result<()> {
+        let required = self.write_policy.required(self.backends.len());
+        try_or!(succeeded >= required, TransportWritePolicyNotMet(required, succeeded))
+    }
+
+    /// Error of a receive no backend succeeded at.
+    ///
+    /// A backend not having the message is an answer rather than an outage, the message is
+    /// reported as not found unless every backend failed for another reason.
+    fn read_error<T>(&self, link: &Link) -> Result<T>
+    where
+        Link: Display,
+    {
+        if self.failures.is_empty() || self.failures.iter().any(|f| is_link_not_found(&f.error)) {
+            err!(MessageLinkNotFound(link.to_string()))
+        } else {
+            err!(AllTransportsFailed(self.failures.len()))
+        }
+    }
+
+    /// Merge messages received from all backends.
+    fn merge(&mut self, link: &Link, results: Vec<Result<Vec<Msg>>>) -> Result<Vec<Msg>>
+    where
+        Link: Display,
+        Msg: PartialEq,
+    {
+        let mut found = false;
+        let mut merged: Vec<Msg> = Vec::new();
+        for (backend, r) in results.into_iter().enumerate() {
+            match r {
+                Ok(msgs) => {
+                    found = true;
+                    for msg in msgs {
+                        if !merged.contains(&msg) {
+                            merged.push(msg);
+                        }
+                    }
+                }
+                Err(error) => self.failures.push(BackendFailure { backend, error }),
+            }
+        }
+        if found {
+            Ok(merged)
+        } else {
+            self.read_error(link)
+        }
+    }
+
+    /// Confirmation timestamp of the first backend which knows it. A backend not knowing it is an
+    /// answer, the lookup only fails if every backend failed.
+    fn confirmation(&self, answered: bool) -> Result<Option<u64>> {
+        if answered || self.failures.is_empty() {
+            Ok(None)
+        } else {
+            err!(AllTransportsFailed(self.failures.len()))
+        }
+    }
+}
+
+impl<Link, Msg> TransportOptions for MultiTransport<Link, Msg> {
+    /// Options are set on each backend before adding it.
+    type SendOptions = ();
+    fn get_send_options(&self) {}
+    fn set_send_options(&mut self, _opt: ()) {}
+
+    type RecvOptions = ();
+    fn get_recv_options(&self) {}
+    fn set_recv_options(&mut self, _opt: ()) {}
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link, Msg> TransportDetails<Link> for MultiTransport<Link, Msg> {
+    /// Backends have different details, none are reported.
+    type Details = ();
+    fn get_link_details(&mut self, _link: &Link) -> Result<Self::Details> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link, Msg> Transport<Link, Msg> for MultiTransport<Link, Msg>
+where
+    Link: Debug + Display,
+    Msg: PartialEq,
+{
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.failures.clear();
+        let mut succeeded = 0;
+        for (backend, tsp) in self.backends.iter_mut().enumerate() {
+            match tsp.send_message(msg) {
+                Ok(()) => succeeded += 1,
+                Err(error) => self.failures.push(BackendFailure { backend, error }),
+            }
+        }
+        self.check_write(succeeded)
+    }
+
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        self.failures.clear();
+        match self.read_policy {
+            ReadPolicy::FirstSuccess => {
+                for (backend, tsp) in self.backends.iter_mut().enumerate() {
+                    match tsp.recv_messages(link) {
+                        Ok(msgs) => return Ok(msgs),
+                        Err(error) => self.failures.push(BackendFailure { backend, error }),
+                    }
+                }
+                self.read_error(link)
+            }
+            ReadPolicy::Merge => {
+                let results = self.backends.iter_mut().map(|tsp| tsp.recv_messages(link)).collect();
+                self.merge(link, results)
+            }
+        }
+    }
+
+    fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        self.failures.clear();
+        let mut answered = false;
+        for (backend, tsp) in self.backends.iter_mut().enumerate() {
+            match tsp.confirmation_timestamp(msg) {
+                Ok(Some(timestamp)) => return Ok(Some(timestamp)),
+                Ok(None) => answered = true,
+                Err(error) => self.failures.push(BackendFailure { backend, error }),
+            }
+        }
+        self.confirmation(answered)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Msg> TransportDetails<Link> for MultiTransport<Link, Msg>
+where
+    Link: 'static + Send + Sync,
+    Msg: 'static,
+{
+    /// Backends have different details, none are reported.
+    type Details = ();
+    async fn get_link_details(&mut self, _link: &Link) -> Result<Self::Details> {
+        Ok(())
+    }
+}
+
+/// Backends are queried concurrently.
+#[cfg(feature = "async")]
+#[async_trait(?Send)]
+impl<Link, Msg> Transport<Link, Msg> for MultiTransport<Link, Msg>
+where
+    Link: 'static + Send + Sync + Display,
+    Msg: 'static + Send + Sync + PartialEq,
+{
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.failures.clear();
+        let results = join_all(self.backends.iter_mut().map(|tsp| tsp.send_message(msg))).await;
+        let mut succeeded = 0;
+        for (backend, r) in results.into_iter().enumerate() {
+            match r {
+                Ok(()) => succeeded += 1,
+                Err(error) => self.failures.push(BackendFailure { backend, error }),
+            }
+        }
+        self.check_write(succeeded)
+    }
+
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        self.failures.clear();
+        match self.read_policy {
+            ReadPolicy::FirstSuccess => {
+                let mut found = None;
+                let mut failures = Vec::new();
+                {
+                    let mut pending = self
+                        .backends
+                        .iter_mut()
+                        .enumerate()
+                        .map(|(backend, tsp)| async move { (backend, tsp.recv_messages(link).await) })
+                        .collect::<FuturesUnordered<_>>();
+                    while let Some((backend, r)) = pending.next().await {
+                        match r {
+                            Ok(msgs) => {
+                                found = Some(msgs);
+                                break;
+                            }
+                            Err(error) => failures.push(BackendFailure { backend, error }),
+                        }
+                    }
+                }
+                self.failures = failures;
+                match found {
+                    Some(msgs) => Ok(msgs),
+                    None => self.read_error(link),
+                }
+            }
+            ReadPolicy::Merge => {
+                let results = join_all(self.backends.iter_mut().map(|tsp| tsp.recv_messages(link))).await;
+                self.merge(link, results)
+            }
+        }
+    }
+
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let mut msgs = Transport::recv_messages(self, link).await?;
+        if let Some(msg) = msgs.pop() {
+            try_or!(msgs.is_empty(), MessageNotUnique(link.to_string()))?;
+            Ok(msg)
+        } else {
+            err!(MessageLinkNotFound(link.to_string()))
+        }
+    }
+
+    /// Backends are queried in turn until one knows the timestamp.
+    async fn confirmation_timestamp(&mut self, msg: &Msg) -> Result<Option<u64>> {
+        self.failures.clear();
+        let mut answered = false;
+        for (backend, tsp) in self.backends.iter_mut().enumerate() {
+            match tsp.confirmation_timestamp(msg).await {
+                Ok(Some(timestamp)) => return Ok(Some(timestamp)),
+                Ok(None) => answered = true,
+                Err(error) => self.failures.push(BackendFailure { backend, error }),
+            }
+        }
+        self.confirmation(answered)
+    }
+}
+
+#[cfg(all(test, not(feature = "async"), feature = "std"))]
+mod tests {
+    use super::{
+        AllTransportsFailed,
+        Errors,
+        MessageLinkNotFound,
+        MultiTransport,
+        ReadPolicy,
+        Result,
+        Transport,
+        TransportWritePolicyNotMet,
+        Vec,
+        WritePolicy,
+    };
+    use crate::transport::test::{
+        FaultyTransport,
+        TestMessage,
+    };
+
+    /// Sends succeed once the write policy is met, failures of the other backends are reported.
+    #[test]
+    fn multi_transport_write_policies() {
+        let backends: Vec<FaultyTransport> = (0..3).map(|_| FaultyTransport::new()).collect();
+        let mut transport = MultiTransport::new(WritePolicy::Quorum, ReadPolicy::FirstSuccess);
+        for backend in &backends {
+            transport.add_transport(backend.clone());
+        }
+        let failed_backends = |transport: &MultiTransport<u64, TestMessage>| {
+            transport.last_failures().iter().map(|f| f.backend).collect::<Vec<_>>()
+        };
+
+        backends[1].sends_left.set(Some(0));
+        transport.send_message(&TestMessage::new(1, 1)).unwrap();
+        assert_eq!(failed_backends(&transport), vec![1]);
+        assert!(backends[1].inner.clone().recv_messages(&1).is_err());
+
+        backends[2].sends_left.set(Some(0));
+        let e = transport.send_message(&TestMessage::new(1, 2)).err().unwrap();
+        assert!(matches!(
+            e.downcast_ref::<Errors>(),
+            Some(TransportWritePolicyNotMet(2, 1))
+        ));
+        assert_eq!(failed_backends(&transport), vec![1, 2]);
+
+        transport.set_write_policy(WritePolicy::Any);
+        transport.send_message(&TestMessage::new(1, 3)).unwrap();
+        assert_eq!(failed_backends(&transport), vec![1, 2]);
+
+        backends[0].sends_left.set(Some(0));
+        let e = transport.send_message(&TestMessage::new(1, 4)).err().unwrap();
+        assert!(matches!(
+            e.downcast_ref::<Errors>(),
+            Some(TransportWritePolicyNotMet(1, 0))
+        ));
+
+        // A successful operation clears the failures of the previous one
+        for backend in &backends {
+            backend.sends_left.set(None);
+        }
+        transport.set_write_policy(WritePolicy::All);
+        transport.send_message(&TestMessage::new(1, 5)).unwrap();
+        assert!(transport.last_failures().is_empty());
+    }
+
+    /// Merged reads drop duplicates, failed reads tell missing messages from unreachable backends.
+    #[test]
+    fn multi_transport_reads() {
+        let backends: Vec<FaultyTransport> = (0..2).map(|_| FaultyTransport::new()).collect();
+        let mut transport = MultiTransport::new(WritePolicy::All, ReadPolicy::Merge);
+        for backend in &backends {
+            transport.add_transport(backend.clone());
+        }
+        let read_error = |r: Result<Vec<TestMessage>>| r.err().unwrap().downcast::<Errors>().unwrap();
+
+        // Both backends have the first message, only the second one has the other
+        transport.send_message(&TestMessage::new(1, 1)).unwrap();
+        let mut second = backends[1].inner.clone();
+        second.send_message(&TestMessage::new(1, 2)).unwrap();
+        let msgs = transport.recv_messages(&1).unwrap();
+        assert_eq!(msgs, vec![TestMessage::new(1, 1), TestMessage::new(1, 2)]);
+
+        // Partial failures are tolerated and reported
+        backends[0].recvs_fail.set(true);
+        assert_eq!(transport.recv_messages(&1).unwrap().len(), 2);
+        assert_eq!(transport.last_failures().len(), 1);
+        assert_eq!(transport.last_failures()[0].backend, 0);
+
+        transport.set_read_policy(ReadPolicy::FirstSuccess);
+        assert_eq!(transport.recv_messages(&1).unwrap().len(), 2);
+        assert_eq!(transport.last_failures().len(), 1);
+
+        // The message is missing as long as one backend answers, even if others are unreachable
+        for policy in [ReadPolicy::FirstSuccess, ReadPolicy::Merge].iter() {
+            transport.set_read_policy(*policy);
+            assert!(matches!(
+                read_error(transport.recv_messages(&2)),
+                MessageLinkNotFound(_)
+            ));
+            assert_eq!(transport.last_failures().len(), 2);
+        }
+
+        backends[1].recvs_fail.set(true);
+        for policy in [ReadPolicy::FirstSuccess, ReadPolicy::Merge].iter() {
+            transport.set_read_policy(*policy);
+            assert!(matches!(
+                read_error(transport.recv_messages(&1)),
+                AllTransportsFailed(2)
+            ));
+            assert_eq!(transport.last_failures().len(), 2);
+        }
+    }
+}
@@ -16,11 +16,15 @@ use iota_client::{
 use iota_client::bee_message::{
     payload::Payload,
     Message,
+    MessageId,
 };
 
 use iota_streams_core::{
     err,
-    prelude::Vec,
+    prelude::{
+        HashMap,
+        Vec,
+    },
     try_or,
     wrapped_err,
     Errors::*,
@@ -76,6 +80,9 @@ fn handle_client_result<T>(result: iota_client::Result<T>) -> Result<T> {
 ///
 /// The input bundle is not checked (for validity of the hash, consistency of indices, etc.).
 /// Checked bundles are returned by `client.get_message().index`.
+///
+/// The message is not timestamped, the milestone confirming it is looked up on demand, see
+/// [`async_get_confirmation_timestamp`].
 pub fn msg_from_tangle_message<F>(message: &Message, link: &TangleAddress) -> Result<TangleMessage<F>> {
     if let Some(Payload::Indexation(i)) = message.payload().as_ref() {
         let mut bytes = Vec::<u8>::new();
@@ -84,25 +91,25 @@ pub fn msg_from_tangle_message<F>(message: &Message, link: &TangleAddress) -> Re
         }
 
         let binary = BinaryMessage::new(link.clone(), TangleAddress::default(), bytes.into());
-        // TODO get timestamp
-        let timestamp: u64 = 0;
-
-        Ok(TangleMessage { binary, timestamp })
+        Ok(TangleMessage::with_timestamp(binary, 0))
     } else {
         err!(BadMessagePayload)
     }
 }
 
-async fn get_messages(client: &iota_client::Client, link: &TangleAddress) -> Result<Vec<Message>> {
+/// Timestamp in milliseconds of a milestone, milestone timestamps are in seconds.
+pub fn milestone_timestamp(milestone: &MilestoneResponse) -> u64 {
+    milestone.timestamp * 1000
+}
+
+async fn get_messages(client: &iota_client::Client, link: &TangleAddress) -> Result<Vec<(MessageId, Message)>> {
     let hash = link.to_msg_index();
     let msg_ids = handle_client_result(client.get_message().index(hash).await)?;
-    try_or!(!msg_ids.is_empty(), IndexNotFound)?;
+    try_or!(!msg_ids.is_empty(), MessageLinkNotFound(link.to_string()))?;
 
-    let msgs = join_all(
-        msg_ids
-            .iter()
-            .map(|msg| async move { handle_client_result(client.get_message().data(msg).await) }),
-    )
+    let msgs = join_all(msg_ids.iter().map(|msg_id| async move {
+        handle_client_result(client.get_message().data(msg_id).await).map(|msg| (*msg_id, msg))
+    }))
     .await
     .into_iter()
     .filter_map(|msg| msg.ok())
@@ -130,13 +137,17 @@ pub async fn async_recv_messages<F>(
     client: &iota_client::Client,
     link: &TangleAddress,
 ) -> Result<Vec<TangleMessage<F>>> {
-    match get_messages(client, link).await {
-        Ok(txs) => Ok(txs
-            .iter()
-            .filter_map(|b| msg_from_tangle_message(b, link).ok()) // Ignore errors
-            .collect()),
-        Err(_) => Ok(Vec::new()), // Just ignore the error?
-    }
+    let txs = get_messages(client, link).await?;
+    Ok(txs
+        .iter()
+        .filter_map(|(msg_id, b)| {
+            let mut msg = msg_from_tangle_message(b, link).ok()?; // Ignore errors
+            let mut tangle_id = [0; 32];
+            tangle_id.copy_from_slice(msg_id.as_ref());
+            msg.tangle_id = Some(tangle_id);
+            Some(msg)
+        })
+        .collect())
 }
 
 /// Retrieve details of a link from the tangle using a node client
@@ -155,6 +166,33 @@ pub async fn async_get_link_details(client: &iota_client::Client, link: &TangleA
     Ok(Details { metadata, milestone })
 }
 
+/// Retrieve the timestamp of the milestone confirming a message received from a node, `None` if
+/// it is not confirmed yet or was not received from a node.
+///
+/// Milestone timestamps are cached by milestone index in `milestones`: messages confirmed by the
+/// same milestone only query it once.
+pub async fn async_get_confirmation_timestamp<F>(
+    client: &iota_client::Client,
+    milestones: &mut HashMap<u32, u64>,
+    msg: &TangleMessage<F>,
+) -> Result<Option<u64>> {
+    let tangle_id = match msg.tangle_id {
+        Some(tangle_id) => MessageId::new(tangle_id),
+        None => return Ok(None),
+    };
+    let metadata = handle_client_result(client.get_message().metadata(&tangle_id).await)?;
+    let ms_index = match metadata.referenced_by_milestone_index {
+        Some(ms_index) => ms_index,
+        None => return Ok(None),
+    };
+    if let Some(timestamp) = milestones.get(&ms_index) {
+        return Ok(Some(*timestamp));
+    }
+    let timestamp = milestone_timestamp(&handle_client_result(client.get_milestone(ms_index).await)?);
+    milestones.insert(ms_index, timestamp);
+    Ok(Some(timestamp))
+}
+
 /// Synchronised - Send message to the tangle using a node client
 #[cfg(not(feature = "async"))]
 pub fn sync_send_message_with_options<F>(client: &iota_client::Client, msg: &TangleMessage<F>) -> Result<()> {
@@ -173,10 +211,22 @@ pub fn sync_get_link_details(client: &iota_client::Client, link: &TangleAddress)
     block_on(async_get_link_details(client, link))
 }
 
+/// Synchronised - Retrieve the timestamp of the milestone confirming a message using a node client
+#[cfg(not(feature = "async"))]
+pub fn sync_get_confirmation_timestamp<F>(
+    client: &iota_client::Client,
+    milestones: &mut HashMap<u32, u64>,
+    msg: &TangleMessage<F>,
+) -> Result<Option<u64>> {
+    block_on(async_get_confirmation_timestamp(client, milestones, msg))
+}
+
 /// Stub type for iota_client::Client.  Removed: Copy, Default, Clone
 pub struct Client {
     send_opt: SendOptions,
     client: iota_client::Client,
+    /// Timestamps of the milestones confirming received messages, by milestone index.
+    milestones: HashMap<u32, u64>,
 }
 
 impl Default for Client {
@@ -191,6 +241,7 @@ impl Default for Client {
                     .finish(),
             )
             .unwrap(),
+            milestones: HashMap::new(),
         }
     }
 }
@@ -201,6 +252,7 @@ impl Client {
         Self {
             send_opt: options,
             client,
+            milestones: HashMap::new(),
         }
     }
 
@@ -219,6 +271,7 @@ impl Client {
                     .finish(),
             )
             .unwrap(),
+            milestones: HashMap::new(),
         }
     }
 }
@@ -235,6 +288,7 @@ impl Clone for Client {
                     .finish(),
             )
             .unwrap(),
+            milestones: HashMap::new(),
         }
     }
 }
@@ -275,6 +329,11 @@ impl<F> Transport<TangleAddress, TangleMessage<F>> for Client {
     fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
         sync_recv_messages(&self.client, link)
     }
+
+    /// Look up the timestamp of the milestone confirming a received message.
+    fn confirmation_timestamp(&mut self, msg: &TangleMessage<F>) -> Result<Option<u64>> {
+        sync_get_confirmation_timestamp(&self.client, &mut self.milestones, msg)
+    }
 }
 
 #[cfg(feature = "async")]
@@ -302,6 +361,11 @@ where
             err!(MessageLinkNotFound(link.to_string()))
         }
     }
+
+    /// Look up the timestamp of the milestone confirming a received message.
+    async fn confirmation_timestamp(&mut self, msg: &TangleMessage<F>) -> Result<Option<u64>> {
+        async_get_confirmation_timestamp(&self.client, &mut self.milestones, msg).await
+    }
 }
 
 #[cfg(feature = "async")]
@@ -313,6 +377,47 @@ impl TransportDetails<TangleAddress> for Client {
     }
 }
 
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<F> SendTransport<TangleAddress, TangleMessage<F>> for Client
+where
+    F: 'static + core::marker::Send + core::marker::Sync,
+{
+    /// Send a Streams message over the Tangle with the current timestamp and default SendOptions.
+    async fn send_message(&mut self, msg: &TangleMessage<F>) -> Result<()> {
+        async_send_message_with_options(&self.client, msg).await
+    }
+
+    /// Receive a message.
+    async fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
+        async_recv_messages(&self.client, link).await
+    }
+
+    async fn recv_message(&mut self, link: &TangleAddress) -> Result<TangleMessage<F>> {
+        let mut msgs = async_recv_messages(&self.client, link).await?;
+        if let Some(msg) = msgs.pop() {
+            try_or!(msgs.is_empty(), MessageNotUnique(link.to_string()))?;
+            Ok(msg)
+        } else {
+            err!(MessageLinkNotFound(link.to_string()))
+        }
+    }
+
+    /// Look up the timestamp of the milestone confirming a received message.
+    async fn confirmation_timestamp(&mut self, msg: &TangleMessage<F>) -> Result<Option<u64>> {
+        async_get_confirmation_timestamp(&self.client, &mut self.milestones, msg).await
+    }
+}
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl SendTransportDetails<TangleAddress> for Client {
+    type Details = Details;
+    async fn get_link_details(&mut self, link: &TangleAddress) -> Result<Self::Details> {
+        async_get_link_details(&self.client, link).await
+    }
+}
+
 #[cfg(feature = "async")]
 #[async_trait(?Send)]
 impl TransportDetails<TangleAddress> for Rc<RefCell<Client>> {
@@ -362,4 +467,15 @@ where
             Err(_err) => err!(TransportNotAvailable),
         }
     }
+
+    /// Look up the timestamp of the milestone confirming a received message.
+    async fn confirmation_timestamp(&mut self, msg: &TangleMessage<F>) -> Result<Option<u64>> {
+        match (&*self).try_borrow_mut() {
+            Ok(mut tsp) => {
+                let tsp = &mut *tsp;
+                async_get_confirmation_timestamp(&tsp.client, &mut tsp.milestones, msg).await
+            }
+            Err(_err) => err!(TransportNotAvailable),
+        }
+    }
 }
@@ -4,6 +4,7 @@ use core::{
     convert::{
         AsMut,
         AsRef,
+        TryFrom,
     },
     fmt,
     ptr::null,
@@ -32,11 +33,15 @@ use iota_streams_core::{
         prp::PRP,
         spongos::Spongos,
     },
+    try_or,
     wrapped_err,
     Error,
     Errors::{
+        BadBech32Format,
         BadHexFormat,
+        LengthMismatch,
         MalformedAddressString,
+        UnexpectedBech32Prefix,
     },
     WrappedError,
 };
@@ -47,9 +52,19 @@ use iota_streams_ddml::{
     types::*,
 };
 
+use bech32::{
+    FromBase32,
+    ToBase32,
+    Variant,
+};
 use cstr_core::CStr;
 use cty::c_char;
 
+use crate::transport::clock::{
+    Clock,
+    SystemClock,
+};
+
 use crate::message::{
     BinaryMessage,
     Cursor,
@@ -70,6 +85,18 @@ pub struct TangleMessage<F> {
     /// Timestamp is not an intrinsic part of Streams message; it's a part of the bundle.
     /// Timestamp is checked with Kerl as part of bundle essense trits.
     pub timestamp: u64,
+
+    /// Id of the Tangle message carrying it when received from a node, used to look up the
+    /// milestone confirming it, see [`crate::transport::Transport::confirmation_timestamp`].
+    pub tangle_id: Option<[u8; 32]>,
+}
+
+/// Messages with the same link and body are equal, timestamp and `prev_link` are not stored
+/// in the message itself and may differ depending on where it was received from.
+impl<F> PartialEq for TangleMessage<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.binary.link == other.binary.link && self.binary.body == other.binary.body
+    }
 }
 
 impl<F> LinkedMessage<TangleAddress> for TangleMessage<F> {
@@ -81,60 +108,24 @@ impl<F> LinkedMessage<TangleAddress> for TangleMessage<F> {
     }
 }
 
-// TODO: Use better feature to detect `chrono::Utc::new()`.
-#[cfg(all(feature = "std"))] //, not(feature = "wasmbind")
-                             //#[cfg(all(feature = "std"))]
 impl<F> TangleMessage<F> {
-    /// Create TangleMessage from BinaryMessage and add the current timestamp.
+    /// Create TangleMessage from BinaryMessage and add the current timestamp of the system clock.
     pub fn new(msg: BinaryMessage<F, TangleAddress>) -> Self {
-        Self {
-            binary: msg,
-            timestamp: chrono::Utc::now().timestamp_millis() as u64,
-        }
+        Self::with_clock(msg, &SystemClock)
     }
-}
 
-// #[cfg(feature = "wasmbind")]
-// impl<F> TangleMessage<F> {
-// Create TangleMessage from BinaryMessage and add the current timestamp.
-// pub fn new(msg: BinaryMessage<F, TangleAddress>) -> Self {
-// let timestamp = js_sys::Date::new_0().value_of() as u64;
-// Self {
-// binary: msg,
-// timestamp,
-// }
-// }
-// }
-// #[cfg(feature = "wasmbind")]
-// impl<F> TangleMessage<F> {
-// Create TangleMessage from BinaryMessage and add the current timestamp.
-// pub fn new(msg: BinaryMessage<F, TangleAddress>) -> Self {
-// Self {
-// binary: msg,
-// timestamp: wasm_timer::SystemTime::now()
-// .duration_since(wasm_timer::SystemTime::UNIX_EPOCH)
-// .unwrap()
-// .as_millis() as u64,
-// }
-// }
-// }
-
-//#[cfg(all(not(feature = "std"), not(feature = "wasmbind")))]
-#[cfg(not(feature = "std"))]
-impl<F> TangleMessage<F> {
-    /// Create TangleMessage from BinaryMessage and add the current timestamp.
-    pub fn new(msg: BinaryMessage<F, TangleAddress>) -> Self {
-        Self {
-            binary: msg,
-            timestamp: 0_u64,
-        }
+    /// Create TangleMessage from BinaryMessage and add the current timestamp of `clock`.
+    pub fn with_clock(msg: BinaryMessage<F, TangleAddress>, clock: &dyn Clock) -> Self {
+        Self::with_timestamp(msg, clock.now())
     }
-}
 
-impl<F> TangleMessage<F> {
     /// Create TangleMessage from BinaryMessage and an explicit timestamp.
     pub fn with_timestamp(msg: BinaryMessage<F, TangleAddress>, timestamp: u64) -> Self {
-        Self { binary: msg, timestamp }
+        Self {
+            binary: msg,
+            timestamp,
+            tangle_id: None,
+        }
     }
 }
 
@@ -156,11 +147,12 @@ impl<F> TangleMessage<F> {
 /// [`core::fmt::LowerHex`] or [`core::fmt::UpperHex`]:
 ///
 /// ```
+/// # use core::convert::TryInto;
 /// # use iota_streams_app::transport::tangle::TangleAddress;
 /// # use iota_streams_ddml::types::NBytes;
 /// #
 /// # fn main() -> anyhow::Result<()> {
-/// let address = TangleAddress::new([172_u8; 40][..].into(), [171_u8; 12][..].into());
+/// let address = TangleAddress::new([172_u8; 40][..].try_into()?, [171_u8; 12][..].try_into()?);
 /// assert_eq!(
 ///     address.to_msg_index().as_ref(),
 ///     &[
@@ -182,11 +174,12 @@ impl<F> TangleMessage<F> {
 /// [`str::parse()`]). This method encodes the `TangleAddress` as a colon-separated string containing the `appinst` and
 /// `msgid` in hexadecimal:
 /// ```
+/// # use core::convert::TryInto;
 /// # use iota_streams_app::transport::tangle::TangleAddress;
 /// # use iota_streams_ddml::types::NBytes;
 /// #
 /// # fn main() -> anyhow::Result<()> {
-/// let address = TangleAddress::new([170_u8; 40][..].into(), [255_u8; 12][..].into());
+/// let address = TangleAddress::new([170_u8; 40][..].try_into()?, [255_u8; 12][..].try_into()?);
 /// let address_str = address.to_string();
 /// assert_eq!(
 ///     address_str,
@@ -198,6 +191,31 @@ impl<F> TangleMessage<F> {
 /// # }
 /// ```
 ///
+/// ### Bech32 encoding
+/// To catch mistyped addresses, a `TangleAddress` can also be encoded with checksums using
+/// [`TangleAddress::to_bech32()`]. The `appinst` and `msgid` are encoded as separate [Bech32]
+/// strings with the [`APPINST_HRP`] and [`MSGID_HRP`] prefixes, each within the 90 characters
+/// [BIP-173] allows, and joined with a colon. [`TangleAddress::from_str`] accepts them as well:
+/// ```
+/// # use core::convert::TryInto;
+/// # use iota_streams_app::transport::tangle::TangleAddress;
+/// #
+/// # fn main() -> anyhow::Result<()> {
+/// let address = TangleAddress::new([170_u8; 40][..].try_into()?, [255_u8; 12][..].try_into()?);
+/// let address_str = address.to_bech32();
+/// let (appinst_str, msgid_str) = address_str.split_once(':').unwrap();
+/// assert!(appinst_str.starts_with("channel1") && appinst_str.len() <= 90);
+/// assert!(msgid_str.starts_with("msg1") && msgid_str.len() <= 90);
+/// assert_eq!(address_str.parse::<TangleAddress>()?, address);
+///
+/// let mut mistyped = address_str.clone();
+/// let last = mistyped.pop().unwrap();
+/// mistyped.push(if last == 'q' { 'p' } else { 'q' });
+/// assert!(mistyped.parse::<TangleAddress>().is_err());
+/// #   Ok(())
+/// # }
+/// ```
+///
 /// ## Debugging
 ///
 /// For debugging purposes, `TangleAddress` implements `Debug`, which can be triggered with the formatting `{:?}`
@@ -205,6 +223,8 @@ impl<F> TangleMessage<F> {
 /// `{:x?}` or `{:#x?}` to render them as hexadecimal arrays.
 ///
 /// [Display]: #impl-Display
+/// [Bech32]: https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki
+/// [BIP-173]: https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki#bech32
 #[derive(Debug, Clone, PartialEq, Eq, Default, Hash)]
 pub struct TangleAddress {
     pub appinst: AppInst,
@@ -232,6 +252,22 @@ impl TangleAddress {
         self.to_blake2b()
     }
 
+    /// Colon-separated conjunction of the checksummed Bech32 `appinst` and `msgid`
+    ///
+    /// See [Bech32 encoding](#bech32-encoding).
+    pub fn to_bech32(&self) -> String {
+        format!("{}:{}", self.appinst.to_bech32(), self.msgid.to_bech32())
+    }
+
+    /// Decode a representation produced by [`TangleAddress::to_bech32()`], verifying its checksums
+    pub fn from_bech32(string: &str) -> Result<Self> {
+        let (appinst_str, msgid_str) = split_address_str(string)?;
+        Ok(TangleAddress {
+            appinst: AppInst::from_bech32(appinst_str)?,
+            msgid: MsgId::from_bech32(msgid_str)?,
+        })
+    }
+
     /// # Safety
     ///
     /// This function uses CStr::from_ptr which is unsafe...
@@ -258,26 +294,65 @@ impl fmt::Display for TangleAddress {
 /// Create a TangleAddress out of it's string representation
 ///
 /// This method is the opposite of [`TangleAddress::to_string()`][`Display`]
-/// (see [`Display`]): it expects a colon-separated string containing the
-/// hex-encoded `appinst` and `msgid`.
+/// (see [`Display`]) and [`TangleAddress::to_bech32()`]: it expects a
+/// colon-separated string containing the `appinst` and `msgid`, each of them
+/// either hex-encoded or Bech32-encoded with its prefix.
 ///
 /// [`Display`]: #impl-Display
 impl FromStr for TangleAddress {
     type Err = Error;
     fn from_str(string: &str) -> Result<Self, Self::Err> {
-        let (appinst_str, msgid_str) = string
-            .split_once(':')
-            .ok_or_else(|| wrapped_err!(MalformedAddressString, WrappedError(string)))?;
-        let appinst = AppInst::from_str(appinst_str)
-            .map_err(|e| wrapped_err!(BadHexFormat(appinst_str.into()), WrappedError(e)))?;
-
-        let msgid =
-            MsgId::from_str(msgid_str).map_err(|e| wrapped_err!(BadHexFormat(appinst_str.into()), WrappedError(e)))?;
+        let (appinst_str, msgid_str) = split_address_str(string)?;
+        let appinst = if has_bech32_prefix(APPINST_HRP, appinst_str) {
+            AppInst::from_bech32(appinst_str)?
+        } else {
+            AppInst::from_str(appinst_str)
+                .map_err(|e| wrapped_err!(BadHexFormat(appinst_str.into()), WrappedError(e)))?
+        };
+
+        let msgid = if has_bech32_prefix(MSGID_HRP, msgid_str) {
+            MsgId::from_bech32(msgid_str)?
+        } else {
+            MsgId::from_str(msgid_str).map_err(|e| wrapped_err!(BadHexFormat(msgid_str.into()), WrappedError(e)))?
+        };
 
         Ok(TangleAddress { appinst, msgid })
     }
 }
 
+fn split_address_str(string: &str) -> Result<(&str, &str)> {
+    string
+        .split_once(':')
+        .ok_or_else(|| wrapped_err!(MalformedAddressString, WrappedError(string)))
+}
+
+/// Human-readable prefix of Bech32 encoded [`AppInst`]s (channel addresses)
+pub const APPINST_HRP: &str = "channel";
+
+/// Human-readable prefix of Bech32 encoded [`MsgId`]s
+pub const MSGID_HRP: &str = "msg";
+
+/// Whether `string` starts with the Bech32 prefix `hrp` and the separator (case insensitive)
+fn has_bech32_prefix(hrp: &str, string: &str) -> bool {
+    string.len() > hrp.len()
+        && string.is_char_boundary(hrp.len())
+        && string[..hrp.len()].eq_ignore_ascii_case(hrp)
+        && string[hrp.len()..].starts_with('1')
+}
+
+fn to_bech32(hrp: &str, bytes: &[u8]) -> String {
+    // Encoding only fails on invalid prefixes, which are constant
+    bech32::encode(hrp, bytes.to_base32(), Variant::Bech32).unwrap()
+}
+
+fn from_bech32(hrp: &str, string: &str) -> Result<Vec<u8>> {
+    let (found_hrp, data, variant) =
+        bech32::decode(string).map_err(|e| wrapped_err!(BadBech32Format(string.into()), WrappedError(e)))?;
+    try_or!(found_hrp == hrp, UnexpectedBech32Prefix(hrp.into(), found_hrp))?;
+    try_or!(variant == Variant::Bech32, BadBech32Format(string.into()))?;
+    Vec::<u8>::from_base32(&data).map_err(|e| wrapped_err!(BadBech32Format(string.into()), WrappedError(e)))
+}
+
 impl HasLink for TangleAddress {
     type Base = AppInst;
     type Rel = MsgId;
@@ -303,11 +378,15 @@ impl HasLink for TangleAddress {
         bytes
     }
 
-    fn from_bytes(bytes: &[u8]) -> Self {
-        TangleAddress::new(
-            AppInst::from(&bytes[0..APPINST_SIZE]),
-            MsgId::from(&bytes[APPINST_SIZE..]),
-        )
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        try_or!(
+            bytes.len() == APPINST_SIZE + MSGID_SIZE,
+            LengthMismatch(APPINST_SIZE + MSGID_SIZE, bytes.len())
+        )?;
+        Ok(TangleAddress::new(
+            AppInst::try_from(&bytes[0..APPINST_SIZE])?,
+            MsgId::try_from(&bytes[APPINST_SIZE..])?,
+        ))
     }
 }
 
@@ -357,6 +436,19 @@ impl<F: PRP> DefaultTangleLinkGenerator<F> {
         s.squeeze(new.id.as_mut());
         new
     }
+    fn gen_secret_msgid(&self, secret: &[u8], id_bytes: &[u8], cursor: Cursor<&MsgId>) -> MsgId {
+        let mut s = Spongos::<F>::init();
+        s.absorb(secret);
+        s.absorb(self.addr.appinst.id.as_ref());
+        s.absorb(id_bytes);
+        s.absorb(cursor.link.id.as_ref());
+        s.absorb(cursor.branch_no.to_be_bytes());
+        s.absorb(cursor.seq_no.to_be_bytes());
+        s.commit();
+        let mut new = MsgId::default();
+        s.squeeze(new.id.as_mut());
+        new
+    }
 }
 
 impl<F: PRP> LinkGenerator<TangleAddress> for DefaultTangleLinkGenerator<F> {
@@ -376,6 +468,12 @@ impl<F: PRP> LinkGenerator<TangleAddress> for DefaultTangleLinkGenerator<F> {
         self.addr = announcement_link;
     }
 
+    /// Used by Subscriber to check that the channel of an announcement link has been generated from
+    /// the Author's public key
+    fn is_channel_of(&self, announcement_link: &TangleAddress, pk: &ed25519::PublicKey) -> bool {
+        announcement_link.appinst.is_of(pk)
+    }
+
     /// Used by users to pseudo-randomly generate a new uniform message link from a cursor
     fn uniform_link_from(&self, cursor: Cursor<&MsgId>) -> TangleAddress {
         TangleAddress {
@@ -391,6 +489,15 @@ impl<F: PRP> LinkGenerator<TangleAddress> for DefaultTangleLinkGenerator<F> {
             msgid: self.gen_msgid(id.as_ref(), cursor),
         }
     }
+
+    /// Used by users to generate a new message link from a cursor and a secret: only the users
+    /// who know the secret can predict the link or tell which publisher it belongs to
+    fn link_from_secret<T: AsRef<[u8]>>(&self, id: T, cursor: Cursor<&MsgId>, secret: &[u8]) -> TangleAddress {
+        TangleAddress {
+            appinst: self.addr.appinst.clone(),
+            msgid: self.gen_secret_msgid(secret, id.as_ref(), cursor),
+        }
+    }
 }
 
 pub type AppInstSize = U40;
@@ -412,6 +519,11 @@ impl AppInst {
             id: unsafe { core::mem::transmute(id) },
         }
     }
+
+    /// Whether the application instance embeds the given Author public key
+    pub fn is_of(&self, pk: &ed25519::PublicKey) -> bool {
+        self.id.as_ref()[..ed25519::PUBLIC_KEY_LENGTH] == pk.as_bytes()[..]
+    }
 }
 
 impl AppInst {
@@ -419,22 +531,37 @@ impl AppInst {
     pub fn to_hex_string(&self) -> String {
         format!("{:x}", self.id)
     }
+
+    /// Checksummed Bech32 representation with the [`APPINST_HRP`] prefix
+    pub fn to_bech32(&self) -> String {
+        to_bech32(APPINST_HRP, self.as_ref())
+    }
+
+    /// Decode a Bech32 representation produced by [`AppInst::to_bech32()`], verifying its checksum
+    pub fn from_bech32(s: &str) -> Result<Self> {
+        let bytes = from_bech32(APPINST_HRP, s)?;
+        try_or!(bytes.len() == APPINST_SIZE, BadBech32Format(s.into()))?;
+        AppInst::try_from(&bytes[..])
+    }
 }
 
-impl<'a> From<&'a [u8]> for AppInst {
-    fn from(v: &[u8]) -> AppInst {
-        AppInst {
-            // TODO: Implement safer TryFrom or force check for length at call site.
+impl<'a> TryFrom<&'a [u8]> for AppInst {
+    type Error = Error;
+    fn try_from(v: &[u8]) -> Result<AppInst> {
+        try_or!(v.len() == APPINST_SIZE, LengthMismatch(APPINST_SIZE, v.len()))?;
+        Ok(AppInst {
             id: *<&NBytes<AppInstSize>>::from(v),
-        }
+        })
     }
 }
 
+/// Parse AppInst from its lowercase hex or Bech32 representation
 impl FromStr for AppInst {
     type Err = ();
     fn from_str(s: &str) -> Result<Self, ()> {
-        // TODO: format for `s`: Bech32 (https://github.com/rust-bitcoin/rust-bech32)
-        // currently lowercase hex
+        if has_bech32_prefix(APPINST_HRP, s) {
+            return AppInst::from_bech32(s).map_err(|_| ());
+        }
         hex::decode(s).map_or(Err(()), |x| {
             if x.len() == AppInstSize::USIZE {
                 Ok(AppInst {
@@ -523,22 +650,37 @@ impl MsgId {
     pub fn to_hex_string(&self) -> String {
         format!("{:x}", self.id)
     }
+
+    /// Checksummed Bech32 representation with the [`MSGID_HRP`] prefix
+    pub fn to_bech32(&self) -> String {
+        to_bech32(MSGID_HRP, self.as_ref())
+    }
+
+    /// Decode a Bech32 representation produced by [`MsgId::to_bech32()`], verifying its checksum
+    pub fn from_bech32(s: &str) -> Result<Self> {
+        let bytes = from_bech32(MSGID_HRP, s)?;
+        try_or!(bytes.len() == MSGID_SIZE, BadBech32Format(s.into()))?;
+        MsgId::try_from(&bytes[..])
+    }
 }
 
-impl<'a> From<&'a [u8]> for MsgId {
-    fn from(v: &[u8]) -> MsgId {
-        MsgId {
-            // TODO: Implement safer TryFrom or force check for length at call site.
+impl<'a> TryFrom<&'a [u8]> for MsgId {
+    type Error = Error;
+    fn try_from(v: &[u8]) -> Result<MsgId> {
+        try_or!(v.len() == MSGID_SIZE, LengthMismatch(MSGID_SIZE, v.len()))?;
+        Ok(MsgId {
             id: *<&NBytes<MsgIdSize>>::from(v),
-        }
+        })
     }
 }
 
+/// Parse MsgId from its lowercase hex or Bech32 representation
 impl FromStr for MsgId {
     type Err = ();
     fn from_str(s: &str) -> Result<Self, ()> {
-        // TODO: format for `s`: Bech32 (https://github.com/rust-bitcoin/rust-bech32)
-        // currently lowercase hex
+        if has_bech32_prefix(MSGID_HRP, s) {
+            return MsgId::from_bech32(s).map_err(|_| ());
+        }
         hex::decode(s).map_or(Err(()), |x| {
             if x.len() == MsgIdSize::USIZE {
                 Ok(MsgId {
@@ -0,0 +1,102 @@
+//! Messages and transports shared by the transport tests.
+
+use core::cell::{
+    Cell,
+    RefCell,
+};
+
+use iota_streams_core::{
+    err,
+    prelude::{
+        Rc,
+        Vec,
+    },
+    Errors::ClientOperationFailure,
+    Result,
+};
+
+use super::{
+    new_shared_transport,
+    BucketTransport,
+    Transport,
+    TransportDetails,
+    TransportOptions,
+};
+use crate::message::LinkedMessage;
+
+/// Message published at a numbered link and carrying a single byte.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestMessage {
+    pub link: u64,
+    pub body: u8,
+}
+
+impl TestMessage {
+    pub fn new(link: u64, body: u8) -> Self {
+        Self { link, body }
+    }
+}
+
+impl LinkedMessage<u64> for TestMessage {
+    fn link(&self) -> &u64 {
+        &self.link
+    }
+
+    fn prev_link(&self) -> &u64 {
+        &self.link
+    }
+}
+
+/// Shared bucket failing every send once `sends_left` sends have succeeded, and every receive while
+/// `recvs_fail` is set.
+#[derive(Clone)]
+pub struct FaultyTransport {
+    pub inner: Rc<RefCell<BucketTransport<u64, TestMessage>>>,
+    pub sends_left: Rc<Cell<Option<usize>>>,
+    pub recvs_fail: Rc<Cell<bool>>,
+}
+
+impl FaultyTransport {
+    pub fn new() -> Self {
+        Self {
+            inner: new_shared_transport(BucketTransport::new()),
+            sends_left: Default::default(),
+            recvs_fail: Default::default(),
+        }
+    }
+}
+
+impl TransportOptions for FaultyTransport {
+    type SendOptions = ();
+    fn get_send_options(&self) {}
+    fn set_send_options(&mut self, _opt: ()) {}
+
+    type RecvOptions = ();
+    fn get_recv_options(&self) {}
+    fn set_recv_options(&mut self, _opt: ()) {}
+}
+
+impl TransportDetails<u64> for FaultyTransport {
+    type Details = ();
+    fn get_link_details(&mut self, _link: &u64) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Transport<u64, TestMessage> for FaultyTransport {
+    fn send_message(&mut self, msg: &TestMessage) -> Result<()> {
+        match self.sends_left.get() {
+            Some(0) => return err!(ClientOperationFailure),
+            Some(n) => self.sends_left.set(Some(n - 1)),
+            None => {}
+        }
+        self.inner.send_message(msg)
+    }
+
+    fn recv_messages(&mut self, link: &u64) -> Result<Vec<TestMessage>> {
+        if self.recvs_fail.get() {
+            return err!(ClientOperationFailure);
+        }
+        self.inner.recv_messages(link)
+    }
+}
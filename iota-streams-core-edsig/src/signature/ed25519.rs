@@ -15,7 +15,7 @@ use core::hash::{
 
 pub type IPk<'a> = &'a PublicKey;
 
-#[derive(Copy, Clone, Default, Eq)]
+#[derive(Copy, Clone, Default, Eq, Debug)]
 pub struct PublicKeyWrap(pub PublicKey);
 
 impl PartialEq for PublicKeyWrap {
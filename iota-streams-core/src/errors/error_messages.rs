@@ -26,8 +26,12 @@ pub enum Errors {
     BadIdentifier,
     /// Psk has already been stored
     PskAlreadyStored,
+    /// Psk is not stored
+    PskNotFound,
     /// Input string {0} is not in hex format
     BadHexFormat(String),
+    /// Byte string is not valid UTF-8
+    InvalidUtf8String,
 
     //////////
     // Cryptographic
@@ -76,12 +80,24 @@ pub enum Errors {
     MessageLinkNotFoundInTangle(String),
     /// Transport object is already borrowed
     TransportNotAvailable,
+    /// File transport failed to access {0}
+    FileTransportFailure(String),
+    /// Write policy not met: {0} transports required, {1} succeeded
+    TransportWritePolicyNotMet(usize, usize),
+    /// All {0} transports failed
+    AllTransportsFailed(usize),
+    /// Transport subscription was closed before anything was published
+    SubscriptionClosed,
 
     //////////
     // Iota Transport
     //////////
     /// Malformed address string: missing colon (':') separator between appinst and msgid
     MalformedAddressString,
+    /// Input string {0} is not in Bech32 format or its checksum does not match
+    BadBech32Format(String),
+    /// Bad Bech32 human-readable prefix (expected: {0}, found: {1})
+    UnexpectedBech32Prefix(String, String),
 
     //////////
     // Iota Client
@@ -120,24 +136,44 @@ pub enum Errors {
     InvalidBitReservation,
     /// Message is not an announcement (found: {0})
     NotAnnouncement(u8),
+    /// Message is not an unsubscribe (found: {0})
+    NotUnsubscribe(u8),
     /// Message info provided is not registered (found: {0})
     BadMessageInfo(u8),
     /// Failed to make message
     MessageCreationFailure,
     /// Identifier could not be generated with given bytes. Must be an ed25519 Public Key or a PskId
     IdentifierGenerationFailure,
+    /// Message frame is out of order (expected: {0}, found: {1})
+    InvalidFrameNum(u32, u32),
+    /// Frame size is too small to carry message fragments (min: {0}, found: {1})
+    FrameSizeTooSmall(usize, usize),
+    /// Message frame does not match the hash committed to by the previous frame (frame: {0})
+    FrameHashMismatch(u32),
+    /// Message carries no authenticated timestamp
+    MissingMsgTimestamp,
+    /// Message timestamp is ahead of local time by more than the allowed clock skew (timestamp: {0}, now: {1})
+    MsgTimestampInFuture(u64, u64),
+    /// Message timestamp is older than the allowed message age (timestamp: {0}, now: {1})
+    MsgTimestampExpired(u64, u64),
 
     //////////
     // Users
     //////////
     /// Cannot create a channel, user is already registered to channel {0}
     ChannelCreationFailure(String),
+    /// Cannot set channel metadata, channel {0} has already been announced
+    ChannelAlreadyAnnounced(String),
     /// Cannot unwrap announcement message, already registered to channel {0}
     UserAlreadyRegistered(String),
     /// User is not registered to a channel
     UserNotRegistered,
     /// Message application instance does not match user channel (expected: {0}, found: {1}
     MessageAppInstMismatch(String, String),
+    /// Channel {0} is not bound to the announced author public key
+    ChannelAuthorMismatch(String),
+    /// Announced author public key does not match the trusted author key (expected: {0}, found: {1})
+    UntrustedAuthorKey(String, String),
     /// Author public x25519 exchange key not found in user instance
     AuthorExchangeKeyNotFound,
     /// Author public ed25519 signature key not found in user instance
@@ -154,6 +190,14 @@ pub enum Errors {
     SingleDepthOperationFailure,
     /// Operation only available on single depth channels
     ChannelNotSingleDepth,
+    /// Unsubscribe message does not reference the Subscribe message of {0}
+    UnsubscribeLinkMismatch(String),
+    /// Subscriber {0} is not known to the user
+    SubscriberNotFound(String),
+    /// A message whose send failed is staged, retry or discard it before sending another
+    StagedMessagePending,
+    /// No message whose send failed is staged
+    NoStagedMessage,
 
     //////////
     // User Recovery
@@ -162,10 +206,30 @@ pub enum Errors {
     AppInstRecoveryFailure(u8),
     /// Author signature pubkey recovery failed (expected: 0 | 1, found: {0})
     AuthorSigPkRecoveryFailure(u8),
+    /// Pre-shared key recovery failed (expected: 0 | 1, found: {0})
+    PskRecoveryFailure(u8),
+    /// Anchor recovery failed (expected: 0 | 1, found: {0})
+    AnchorRecoveryFailure(u8),
+    /// Channel metadata recovery failed (expected: 0 | 1, found: {0})
+    ChannelMetadataRecoveryFailure(u8),
     /// User Version does not match (expected: {0}, found: {1}
     UserVersionRecoveryFailure(u8, u8),
     /// Recovered flag does not match expected: flag (expected: {0}, found: {1})
     UserFlagRecoveryFailure(u8, u8),
+    /// Password key derivation cost is out of bounds (memory: {0} KiB, iterations: {1})
+    KdfCostOutOfBounds(u32, u32),
+    /// Password key derivation cost exceeds the accepted limit (memory: {0} KiB, iterations: {1})
+    KdfCostAboveLimit(u32, u32),
+    /// Outbox recovery failed, bytes are truncated (expected at least: {0}, found: {1})
+    OutboxRecoveryFailure(usize, usize),
+    /// State update recovery failed, unknown update type (found: {0})
+    StateUpdateRecoveryFailure(u8),
+    /// State store recovery failed, record is truncated (expected at least: {0}, found: {1})
+    StateStoreRecoveryFailure(usize, usize),
+    /// State store failed to access {0}
+    StateStoreFileFailure(String),
+    /// State store was saved by a user created with another seed (expected public key: {0}, found: {1})
+    StateStoreSeedMismatch(String, String),
 
     //////////
     // Examples
@@ -19,13 +19,24 @@ use crate::{
             Spongos,
         },
     },
+    try_or,
+    wrapped_err,
+    Errors::KdfCostOutOfBounds,
+    Result,
+    WrappedError,
+};
+
+/// Random number generators accepted by [`random_bytes`].
+pub use rand::{
+    CryptoRng,
+    RngCore,
 };
 
 /// Generate cryptographically secure bytes.
 /// Suitable for generating session and ephemeral keys.
 pub fn random_bytes<R, N: ArrayLength<u8>>(rng: &mut R) -> GenericArray<u8, N>
 where
-    R: rand::RngCore + rand::CryptoRng,
+    R: RngCore + CryptoRng,
 {
     let mut rnd = GenericArray::default();
     rng.fill_bytes(rnd.as_mut_slice());
@@ -145,6 +156,111 @@ pub fn from_seed<G: PRP>(domain: &str, seed: &str) -> Prng<G> {
     Prng::init(s.squeeze_arr())
 }
 
+/// Cost parameters of the Argon2id password-based key derivation in [`from_password`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Amount of memory required to derive the key, in KiB.
+    pub memory_kib: u32,
+    /// Number of passes over the memory.
+    pub iterations: u32,
+}
+
+impl KdfParams {
+    /// Lower bound of `memory_kib` accepted when deriving a key, Argon2 requires 8 KiB per lane.
+    pub const MIN_MEMORY_KIB: u32 = 8;
+    /// Upper bound of `memory_kib` accepted when deriving a key: 1 GiB.
+    pub const MAX_MEMORY_KIB: u32 = 1 << 20;
+    /// Upper bound of `iterations` accepted when deriving a key.
+    pub const MAX_ITERATIONS: u32 = 1 << 10;
+
+    pub fn new(memory_kib: u32, iterations: u32) -> Self {
+        Self { memory_kib, iterations }
+    }
+
+    /// Check that the parameters are within the accepted bounds.
+    pub fn is_valid(&self) -> bool {
+        Self::MIN_MEMORY_KIB <= self.memory_kib
+            && self.memory_kib <= Self::MAX_MEMORY_KIB
+            && 0 < self.iterations
+            && self.iterations <= Self::MAX_ITERATIONS
+    }
+
+    /// Check that the cost does not exceed `limits`.
+    pub fn is_within(&self, limits: &KdfLimits) -> bool {
+        self.memory_kib <= limits.memory_kib && self.iterations <= limits.iterations
+    }
+}
+
+impl Default for KdfParams {
+    /// 19 MiB and 2 passes.
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+        }
+    }
+}
+
+/// Highest key derivation cost accepted from serialized parameters, eg. the header of imported
+/// state. Parameters read from untrusted data could otherwise make key derivation exhaust memory
+/// or time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfLimits {
+    /// Maximum amount of memory, in KiB.
+    pub memory_kib: u32,
+    /// Maximum number of passes over the memory.
+    pub iterations: u32,
+}
+
+impl KdfLimits {
+    /// Limits of the given memory, in KiB, and number of passes.
+    pub fn new(memory_kib: u32, iterations: u32) -> Self {
+        Self { memory_kib, iterations }
+    }
+}
+
+impl Default for KdfLimits {
+    /// 64 MiB and 8 passes, well above the default export cost.
+    fn default() -> Self {
+        Self {
+            memory_kib: 64 * 1024,
+            iterations: 8,
+        }
+    }
+}
+
+/// Derive PRNG secret key from a password and a salt with Argon2id of the cost given by `params`.
+///
+/// Argon2id runs with a single lane, its output is bound to `domain` by the sponge.
+pub fn from_password<G: PRP>(domain: &str, pwd: &str, salt: &[u8], params: &KdfParams) -> Result<Prng<G>> {
+    try_or!(
+        params.is_valid(),
+        KdfCostOutOfBounds(params.memory_kib, params.iterations)
+    )?;
+    let argon2_params = argon2::Params::new(params.memory_kib, params.iterations, 1, Some(32)).map_err(|e| {
+        wrapped_err!(
+            KdfCostOutOfBounds(params.memory_kib, params.iterations),
+            WrappedError(e)
+        )
+    })?;
+    let mut key = Key::default();
+    argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, argon2_params)
+        .hash_password_into(pwd.as_bytes(), salt, &mut key)
+        .map_err(|e| {
+            wrapped_err!(
+                KdfCostOutOfBounds(params.memory_kib, params.iterations),
+                WrappedError(e)
+            )
+        })?;
+
+    let mut s = Spongos::<G>::init();
+    s.absorb(&key);
+    s.commit();
+    s.absorb(domain.as_bytes());
+    s.commit();
+    Ok(Prng::init(s.squeeze_arr()))
+}
+
 pub fn dbg_init_str<G: PRP>(secret_key: &str) -> Prng<G> {
     from_seed("IOTA Streams dbg prng init", secret_key)
 }
@@ -188,3 +188,92 @@ where
         self.map.iter().collect()
     }
 }
+
+/// Link store keeping a journal of the links put into or erased from the inner store, so that the
+/// changes can be persisted incrementally instead of exporting the whole store.
+///
+/// Journaling is disabled by default, the journal would otherwise grow with the store.
+pub struct JournaledLinkStore<LS, Link> {
+    store: LS,
+    journal: Option<Vec<Link>>,
+}
+
+impl<LS: Default, Link> Default for JournaledLinkStore<LS, Link> {
+    fn default() -> Self {
+        Self {
+            store: LS::default(),
+            journal: None,
+        }
+    }
+}
+
+impl<LS, Link> JournaledLinkStore<LS, Link> {
+    /// Start or stop journaling, the current journal is cleared.
+    pub fn set_journaling(&mut self, enabled: bool) {
+        self.journal = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    pub fn is_journaling(&self) -> bool {
+        self.journal.is_some()
+    }
+
+    /// Links put into or erased from the store since the journal was last cleared, in order. A
+    /// link may occur several times.
+    pub fn journal(&self) -> &[Link] {
+        self.journal.as_ref().map_or(&[], |journal| &journal[..])
+    }
+
+    pub fn clear_journal(&mut self) {
+        if let Some(journal) = &mut self.journal {
+            journal.clear();
+        }
+    }
+
+    fn record(&mut self, link: &Link)
+    where
+        Link: Clone,
+    {
+        if let Some(journal) = &mut self.journal {
+            journal.push(link.clone());
+        }
+    }
+}
+
+impl<F, Link, LS> LinkStore<F, Link> for JournaledLinkStore<LS, Link>
+where
+    Link: Clone,
+    LS: LinkStore<F, Link>,
+{
+    type Info = LS::Info;
+
+    fn lookup(&self, link: &Link) -> Result<(Spongos<F>, Self::Info)> {
+        self.store.lookup(link)
+    }
+
+    fn update(&mut self, link: &Link, spongos: Spongos<F>, info: Self::Info) -> Result<()> {
+        self.store.update(link, spongos, info)?;
+        self.record(link);
+        Ok(())
+    }
+
+    fn insert(&mut self, link: &Link, spongos: Inner<F>, info: Self::Info) -> Result<()>
+    where
+        F: PRP,
+    {
+        self.store.insert(link, spongos, info)?;
+        self.record(link);
+        Ok(())
+    }
+
+    fn erase(&mut self, link: &Link) {
+        self.store.erase(link);
+        self.record(link);
+    }
+
+    fn iter(&self) -> Vec<(&Link, &(Inner<F>, Self::Info))>
+    where
+        F: PRP,
+    {
+        self.store.iter()
+    }
+}